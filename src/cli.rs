@@ -0,0 +1,58 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::db;
+use crate::errors::response::MyError;
+use crate::models::catalog::ConflictPolicy;
+use crate::routes::catalog::{export_catalog, import_catalog};
+
+const USAGE: &str = "Usage:
+  threesixfive-server catalog export <archive.tar>
+  threesixfive-server catalog import <archive.tar> [--policy skip|overwrite|duplicate] [--dry-run]";
+
+/// Runs `catalog export` and `catalog import` against the database in
+/// `MONGO_URI`, the same as `GET /catalog/export` and `POST /catalog/import`.
+pub async fn catalog(args: &[String]) -> Result<(), String> {
+    let (command, file) = match args {
+        [command, file, ..] => (command.as_str(), file),
+        _ => return Err(USAGE.to_string()),
+    };
+    let mut policy = ConflictPolicy::skip;
+    let mut dry_run = false;
+    let mut options = args[2..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--dry-run" => dry_run = true,
+            "--policy" => {
+                let value = options.next().ok_or(USAGE.to_string())?;
+                policy = ConflictPolicy::from_str(value)
+                    .map_err(|_| format!("Unknown policy {}.\n{}", value, USAGE))?;
+            }
+            _ => return Err(format!("Unknown option {}.\n{}", option, USAGE)),
+        }
+    }
+
+    let database = db::connect()
+        .await
+        .map_err(|err| format!("Cannot connect to instance: {:?}", err))?;
+    let failed = |err: MyError| serde_json::to_string(&err).unwrap_or_default();
+    match command {
+        "export" => {
+            let archive = export_catalog(&database).await.map_err(failed)?;
+            std::fs::write(file, &archive).map_err(|err| format!("Writing {} failed: {}", file, err))?;
+            println!("Exported the catalog to {} ({} bytes).", file, archive.len());
+            Ok(())
+        }
+        "import" => {
+            let report = import_catalog(&database, Path::new(file), policy, dry_run)
+                .await
+                .map_err(failed)?;
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).map_err(|err| err.to_string())?
+            );
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
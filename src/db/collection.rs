@@ -0,0 +1,122 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_collections_collection};
+use crate::models::collection::{Collection, CollectionDocument, Visibility};
+use crate::models::image::Image;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, to_bson, DateTime};
+use mongodb::results::{InsertOneResult, UpdateResult};
+use mongodb::Database;
+
+pub async fn insert_collection(
+    db: &Database,
+    collection: CollectionDocument,
+) -> Result<InsertOneResult, DbError> {
+    let collections = get_collections_collection(&db);
+    crud::insert_one(collections, collection).await
+}
+
+pub async fn find_one_collection(
+    db: &Database,
+    id: ObjectId,
+) -> Result<Option<Collection>, DbError> {
+    let collections = get_collections_collection(&db);
+    crud::find_one(collections, id).await
+}
+
+/// Lists the user's collections, most recently changed first.
+pub async fn find_user_collections(
+    db: &Database,
+    user_id: &str,
+    limit: i64,
+    page: i64,
+) -> Result<Vec<Collection>, DbError> {
+    let collections = get_collections_collection(&db);
+    let options = crud::page_options(limit, page, doc! { "updated_at": -1 });
+    crud::find_many(collections, doc! { "user_id": user_id }, Some(options)).await
+}
+
+/// Lists public collections, most recently changed first.
+pub async fn find_public_collections(
+    db: &Database,
+    limit: i64,
+    page: i64,
+) -> Result<Vec<Collection>, DbError> {
+    let collections = get_collections_collection(&db);
+    let options = crud::page_options(limit, page, doc! { "updated_at": -1 });
+    let filter = doc! { "visibility": Visibility::public.to_string() };
+    crud::find_many(collections, filter, Some(options)).await
+}
+
+/// Collections containing the recipe that are public or owned by `user_id`.
+pub async fn find_recipe_collections(
+    db: &Database,
+    recipe_id: ObjectId,
+    user_id: Option<&str>,
+) -> Result<Vec<Collection>, DbError> {
+    let collections = get_collections_collection(&db);
+    let mut visible = vec![doc! { "visibility": Visibility::public.to_string() }];
+    if let Some(user_id) = user_id {
+        visible.push(doc! { "user_id": user_id });
+    }
+    let filter = doc! { "recipe_ids": recipe_id, "$or": visible };
+    crud::find_many(collections, filter, None).await
+}
+
+pub async fn update_collection_details(
+    db: &Database,
+    id: ObjectId,
+    name: String,
+    description: Option<String>,
+    cover_image: Option<Image>,
+    visibility: Visibility,
+) -> Result<(), DbError> {
+    let collections = get_collections_collection(&db);
+    let cover_image = to_bson(&cover_image).map_err(|err| DbError::new(err.to_string()))?;
+    crud::set_fields(
+        collections,
+        id,
+        doc! {
+            "name": name,
+            "description": description,
+            "cover_image": cover_image,
+            "visibility": visibility.to_string(),
+            "updated_at": DateTime::now(),
+        },
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Replaces the recipes of the collection, in order.
+pub async fn set_recipe_ids(
+    db: &Database,
+    id: ObjectId,
+    recipe_ids: Vec<ObjectId>,
+) -> Result<(), DbError> {
+    let collections = get_collections_collection(&db);
+    crud::set_fields(
+        collections,
+        id,
+        doc! { "recipe_ids": recipe_ids, "updated_at": DateTime::now() },
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Takes a purged recipe out of every collection.
+pub async fn remove_recipe_everywhere(db: &Database, recipe_id: ObjectId) -> Result<(), DbError> {
+    get_collections_collection(&db)
+        .update_many(
+            doc! { "recipe_ids": recipe_id },
+            doc! { "$pull": { "recipe_ids": recipe_id } },
+            None,
+        )
+        .await
+        .map(|_| ())
+        .map_err(|_err| DbError::new("Failed to remove_recipe_everywhere.".to_string()))
+}
+
+pub async fn delete_one_collection(db: &Database, id: ObjectId) -> Result<UpdateResult, DbError> {
+    let collections = get_collections_collection(&db);
+    crud::delete_one(collections, id).await
+}
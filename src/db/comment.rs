@@ -0,0 +1,96 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_comments_collection};
+use crate::models::comment::{Comment, CommentDocument};
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, DateTime};
+use mongodb::results::{InsertOneResult, UpdateResult};
+use mongodb::Database;
+
+pub async fn insert_comment(
+    db: &Database,
+    comment: CommentDocument,
+) -> Result<InsertOneResult, DbError> {
+    let collection = get_comments_collection(&db);
+    crud::insert_one(collection, comment).await
+}
+
+pub async fn find_one_comment(db: &Database, id: ObjectId) -> Result<Option<Comment>, DbError> {
+    let collection = get_comments_collection(&db);
+    crud::find_one(collection, id).await
+}
+
+/// Top level comments of a recipe, newest first. With `step_id` only the
+/// comments about that step are returned. Trashed comments are included,
+/// they show as tombstones.
+pub async fn find_root_comments(
+    db: &Database,
+    recipe_id: ObjectId,
+    step_id: Option<ObjectId>,
+    limit: i64,
+    page: i64,
+) -> Result<Vec<Comment>, DbError> {
+    let collection = get_comments_collection(&db);
+    let mut filter = doc! { "recipe_id": recipe_id, "parent_id": null };
+    if let Some(step_id) = step_id {
+        filter.insert("step_id", step_id);
+    }
+    let options = crud::page_options(limit, page, doc! { "created_at": -1 });
+    crud::find_many_with_deleted(collection, filter, Some(options)).await
+}
+
+/// All replies, at any depth, within the given threads, trashed ones as
+/// tombstones. Left as stored for `CommentThread::build` to sort.
+pub async fn find_thread_replies(
+    db: &Database,
+    thread_ids: Vec<ObjectId>,
+) -> Result<Vec<CommentDocument>, DbError> {
+    let collection = get_comments_collection(&db);
+    collection
+        .find(doc! { "thread_id": { "$in": thread_ids } }, None)
+        .await
+        .map_err(|_err| DbError::new("Failed to find_thread_replies.".to_string()))?
+        .try_collect()
+        .await
+        .map_err(|_err| DbError::new("Failed to find_thread_replies.".to_string()))
+}
+
+pub async fn update_comment_text(db: &Database, id: ObjectId, text: String) -> Result<(), DbError> {
+    let collection = get_comments_collection(&db);
+    crud::set_fields(
+        collection,
+        id,
+        doc! { "text": text, "updated_at": DateTime::now() },
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Moves the comment to the trash. It shows as a tombstone until restored.
+pub async fn delete_one_comment(db: &Database, id: ObjectId) -> Result<UpdateResult, DbError> {
+    let collection = get_comments_collection(&db);
+    crud::delete_one(collection, id).await
+}
+
+/// Turns the comments trashed before `before` into tombstones for good,
+/// rather than deleting them, so their replies keep their place. Returns
+/// how many were purged.
+pub async fn purge_deleted_comments(db: &Database, before: DateTime) -> Result<usize, DbError> {
+    let collection = get_comments_collection(&db);
+    let filter = doc! { "deleted_at": { "$ne": Bson::Null, "$lte": before } };
+    let update = doc! {
+        "$set": { "text": "", "deleted": true, "updated_at": DateTime::now() },
+        "$unset": { "deleted_at": "" },
+    };
+    collection
+        .update_many(filter, update, None)
+        .await
+        .map(|result| result.modified_count as usize)
+        .map_err(|_err| DbError::new("Failed to purge_deleted_comments.".to_string()))
+}
+
+/// Deletes for good the comments of a purged recipe.
+pub async fn purge_recipe_comments(db: &Database, recipe_id: ObjectId) -> Result<(), DbError> {
+    let collection = get_comments_collection(&db);
+    crud::purge_many(collection, doc! { "recipe_id": recipe_id }).await
+}
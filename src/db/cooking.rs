@@ -0,0 +1,115 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_cooking_sessions_collection};
+use crate::models::cooking::{CookingSession, CookingSessionDocument, CookingTimerDocument};
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, to_bson, DateTime};
+use mongodb::results::InsertOneResult;
+use mongodb::Database;
+
+pub async fn insert_cooking_session(
+    db: &Database,
+    session: CookingSessionDocument,
+) -> Result<InsertOneResult, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    crud::insert_one(collection, session).await
+}
+
+pub async fn find_one_cooking_session(
+    db: &Database,
+    id: ObjectId,
+) -> Result<Option<CookingSession>, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    crud::find_one(collection, id).await
+}
+
+/// The session the user already has for the recipe, to resume it.
+pub async fn find_user_cooking_session(
+    db: &Database,
+    user_id: &str,
+    recipe_id: ObjectId,
+) -> Result<Option<CookingSession>, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    let filter = doc! { "user_id": user_id, "recipe_id": recipe_id };
+    Ok(crud::find_many(collection, filter, None).await?.into_iter().next())
+}
+
+pub async fn set_step_index(db: &Database, id: ObjectId, step_index: i32) -> Result<bool, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    let fields = doc! { "step_index": step_index, "updated_at": DateTime::now() };
+    crud::set_fields(collection, id, fields)
+        .await
+        .map(|result| result.matched_count == 1)
+}
+
+pub async fn add_timer(db: &Database, id: ObjectId, timer: &CookingTimerDocument) -> Result<bool, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    let timer = to_bson(timer).map_err(|err| DbError::new(err.to_string()))?;
+    let update = doc! {
+        "$push": { "timers": timer },
+        "$set": { "updated_at": DateTime::now() },
+    };
+    collection
+        .update_one(crud::not_deleted(doc! { "_id": id }), update, None)
+        .await
+        .map(|result| result.matched_count == 1)
+        .map_err(|_err| DbError::new("Failed to add_timer.".to_string()))
+}
+
+pub async fn remove_timer(db: &Database, id: ObjectId, timer_id: &str) -> Result<bool, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    let filter = crud::not_deleted(doc! { "_id": id, "timers.id": timer_id });
+    let update = doc! {
+        "$pull": { "timers": { "id": timer_id } },
+        "$set": { "updated_at": DateTime::now() },
+    };
+    collection
+        .update_one(filter, update, None)
+        .await
+        .map(|result| result.modified_count == 1)
+        .map_err(|_err| DbError::new("Failed to remove_timer.".to_string()))
+}
+
+/// Sessions with a timer that ran out by `now` and wasn't pushed yet.
+pub async fn find_sessions_with_due_timers(
+    db: &Database,
+    now: DateTime,
+) -> Result<Vec<CookingSessionDocument>, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    let filter = crud::not_deleted(doc! {
+        "timers": { "$elemMatch": { "expired": false, "ends_at": { "$lte": now } } }
+    });
+    collection
+        .find(filter, None)
+        .await
+        .map_err(|_err| DbError::new("Failed to find_sessions_with_due_timers.".to_string()))?
+        .try_collect()
+        .await
+        .map_err(|_err| DbError::new("Failed to find_sessions_with_due_timers.".to_string()))
+}
+
+/// Marks the timer as expired. `false` if it was already, or was cancelled
+/// in the meantime, so each expiry is pushed once.
+pub async fn expire_timer(db: &Database, id: ObjectId, timer_id: &str) -> Result<bool, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    let filter = doc! {
+        "_id": id,
+        "timers": { "$elemMatch": { "id": timer_id, "expired": false } },
+    };
+    collection
+        .update_one(filter, doc! { "$set": { "timers.$.expired": true } }, None)
+        .await
+        .map(|result| result.modified_count == 1)
+        .map_err(|_err| DbError::new("Failed to expire_timer.".to_string()))
+}
+
+/// Ends the session. Sessions are not kept in the trash, there is nothing
+/// to restore once cooking is done.
+pub async fn delete_one_cooking_session(db: &Database, id: ObjectId) -> Result<bool, DbError> {
+    let collection = get_cooking_sessions_collection(&db);
+    collection
+        .delete_one(doc! { "_id": id }, None)
+        .await
+        .map(|result| result.deleted_count == 1)
+        .map_err(|_err| DbError::new("Failed to delete_one_cooking_session.".to_string()))
+}
@@ -1,13 +1,23 @@
 use crate::models::ObjectConvertable;
 use futures::{TryStream, TryStreamExt};
-use mongodb::results::InsertOneResult;
-use mongodb::{bson::oid::ObjectId, results::DeleteResult, Collection, Database};
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use mongodb::options::{FindOptions, ReplaceOptions};
+use mongodb::results::{InsertOneResult, UpdateResult};
+use mongodb::{bson::oid::ObjectId, Collection, Database};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::borrow::Borrow;
 
 use super::{create_filter, error::DbError, get_recipe_steps_collection};
 
+/// Narrows `filter` to documents that aren't in the trash. Every find and
+/// update here goes through it, so trashed documents are gone to the API
+/// until restored.
+pub fn not_deleted(mut filter: Document) -> Document {
+    filter.insert("deleted_at", Bson::Null);
+    filter
+}
+
 pub async fn insert_one<T, U>(
     collection: Collection<T>,
     doc: impl Borrow<U> + Borrow<T>,
@@ -25,7 +35,7 @@ pub async fn find_one<T, U>(collection: Collection<T>, id: ObjectId) -> Result<O
 where
     T: DeserializeOwned + Unpin + Send + Sync + ObjectConvertable<U>,
 {
-    let filter = create_filter(&id)?;
+    let filter = not_deleted(create_filter(&id)?);
     collection
         .find_one(filter, None)
         .await
@@ -43,7 +53,7 @@ where
     T: DeserializeOwned + Unpin + Send + Sync + ObjectConvertable<U>,
 {
     let cursor = collection
-        .find(None, None)
+        .find(not_deleted(Document::new()), None)
         .await
         .map_err(|_err| DbError::new("Failed to find_all_images.".to_string()))?;
 
@@ -60,6 +70,89 @@ where
     Ok(objects)
 }
 
+pub async fn find_many<T, U>(
+    collection: Collection<T>,
+    filter: Document,
+    options: Option<FindOptions>,
+) -> Result<Vec<U>, DbError>
+where
+    T: DeserializeOwned + Unpin + Send + Sync + ObjectConvertable<U>,
+{
+    let cursor = collection
+        .find(not_deleted(filter), options)
+        .await
+        .map_err(|_err| DbError::new("Failed to find_many.".to_string()))?;
+
+    let object_documents: Vec<T> = cursor
+        .try_collect()
+        .await
+        .map_err(|_err| DbError::new("Failed to find_many.".to_string()))?;
+
+    Ok(object_documents
+        .into_iter()
+        .map(|doc| doc.to_object())
+        .collect())
+}
+
+/// Like `find_many`, trashed documents included.
+pub async fn find_many_with_deleted<T, U>(
+    collection: Collection<T>,
+    filter: Document,
+    options: Option<FindOptions>,
+) -> Result<Vec<U>, DbError>
+where
+    T: DeserializeOwned + Unpin + Send + Sync + ObjectConvertable<U>,
+{
+    let cursor = collection
+        .find(filter, options)
+        .await
+        .map_err(|_err| DbError::new("Failed to find_many_with_deleted.".to_string()))?;
+
+    let object_documents: Vec<T> = cursor
+        .try_collect()
+        .await
+        .map_err(|_err| DbError::new("Failed to find_many_with_deleted.".to_string()))?;
+
+    Ok(object_documents
+        .into_iter()
+        .map(|doc| doc.to_object())
+        .collect())
+}
+
+/// Deletes for good the documents matching `filter`, trashed or not.
+pub async fn purge_many<T>(collection: Collection<T>, filter: Document) -> Result<(), DbError> {
+    collection
+        .delete_many(filter, None)
+        .await
+        .map(|_| ())
+        .map_err(|_err| DbError::new("Failed to purge_many.".to_string()))
+}
+
+/// Builds `FindOptions` for the `limit`/`page` pagination used across the API.
+/// Pages start at 1.
+pub fn page_options(limit: i64, page: i64, sort: Document) -> FindOptions {
+    let limit = limit.max(1);
+    let page = page.max(1);
+    FindOptions::builder()
+        .limit(limit)
+        .skip(u64::try_from((page - 1) * limit).unwrap_or(0))
+        .sort(sort)
+        .build()
+}
+
+/// Applies `$set` with the given fields, leaving the rest of the document untouched.
+pub async fn set_fields<T>(
+    collection: Collection<T>,
+    id: ObjectId,
+    fields: Document,
+) -> Result<UpdateResult, DbError> {
+    let filter = not_deleted(create_filter(&id)?);
+    collection
+        .update_one(filter, doc! { "$set": fields }, None)
+        .await
+        .map_err(|_err| DbError::new("Failed to set_fields.".to_string()))
+}
+
 pub async fn update_one<T, U>(
     collection: Collection<T>,
     id: ObjectId,
@@ -68,7 +161,7 @@ pub async fn update_one<T, U>(
 where
     T: Serialize + DeserializeOwned + ObjectConvertable<U>,
 {
-    let filter = create_filter(&id)?;
+    let filter = not_deleted(create_filter(&id)?);
     collection
         .find_one_and_replace(filter, doc, None)
         .await
@@ -79,13 +172,87 @@ where
         .map_err(|_err| DbError::new("Failed to update_one.".to_string()))
 }
 
-pub async fn delete_one<T>(
+/// Replaces the document with `id`, inserting it under that id if there is
+/// none. A trashed document is replaced as well and so back out of the trash.
+pub async fn replace_or_insert<T>(
     collection: Collection<T>,
     id: ObjectId,
-) -> Result<DeleteResult, DbError> {
+    doc: impl Borrow<T>,
+) -> Result<UpdateResult, DbError>
+where
+    T: Serialize,
+{
     let filter = create_filter(&id)?;
+    let options = ReplaceOptions::builder().upsert(true).build();
+    collection
+        .replace_one(filter, doc, options)
+        .await
+        .map_err(|_err| DbError::new("Failed to replace_or_insert.".to_string()))
+}
+
+/// Moves the document to the trash by setting `deleted_at`. It is left out
+/// of finds from then on, until restored or purged.
+pub async fn delete_one<T>(
+    collection: Collection<T>,
+    id: ObjectId,
+) -> Result<UpdateResult, DbError> {
+    let filter = not_deleted(create_filter(&id)?);
+    collection
+        .update_one(filter, doc! { "$set": { "deleted_at": DateTime::now() } }, None)
+        .await
+        .map_err(|_err| DbError::new("Failed to delete_one.".to_string()))
+}
+
+/// Takes the document out of the trash.
+pub async fn restore_one<T>(
+    collection: Collection<T>,
+    id: ObjectId,
+) -> Result<UpdateResult, DbError> {
+    let filter = doc! { "_id": id, "deleted_at": { "$ne": Bson::Null } };
+    collection
+        .update_one(filter, doc! { "$unset": { "deleted_at": "" } }, None)
+        .await
+        .map_err(|_err| DbError::new("Failed to restore_one.".to_string()))
+}
+
+/// The trashed documents as stored, most recently deleted first.
+pub async fn find_deleted<T>(collection: Collection<T>) -> Result<Vec<Document>, DbError> {
+    let options = FindOptions::builder().sort(doc! { "deleted_at": -1 }).build();
+    collection
+        .clone_with_type::<Document>()
+        .find(doc! { "deleted_at": { "$ne": Bson::Null } }, options)
+        .await
+        .map_err(|_err| DbError::new("Failed to find_deleted.".to_string()))?
+        .try_collect()
+        .await
+        .map_err(|_err| DbError::new("Failed to find_deleted.".to_string()))
+}
+
+/// Deletes for good the documents trashed before `before` and returns them
+/// as they were stored.
+pub async fn purge_deleted<T>(
+    collection: Collection<T>,
+    before: DateTime,
+) -> Result<Vec<Document>, DbError> {
+    let collection = collection.clone_with_type::<Document>();
+    let filter = doc! { "deleted_at": { "$ne": Bson::Null, "$lte": before } };
+    let purged: Vec<Document> = collection
+        .find(filter.clone(), None)
+        .await
+        .map_err(|_err| DbError::new("Failed to purge_deleted.".to_string()))?
+        .try_collect()
+        .await
+        .map_err(|_err| DbError::new("Failed to purge_deleted.".to_string()))?;
+    let ids: Vec<ObjectId> = purged
+        .iter()
+        .filter_map(|document| document.get_object_id("_id").ok())
+        .collect();
+    // Still trashed, in case one was restored in the meantime.
+    let mut purge = filter;
+    purge.insert("_id", doc! { "$in": ids.clone() });
     collection
-        .delete_one(filter, None)
+        .delete_many(purge, None)
         .await
-        .map_err(|_err| DbError::new("Failed to delete_one_recipe.".to_string()))
+        .map_err(|_err| DbError::new("Failed to purge_deleted.".to_string()))?;
+    Ok(purged)
 }
@@ -0,0 +1,70 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_daily_recipes_collection};
+use crate::models::daily::DailyRecipePick;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, DateTime};
+use mongodb::options::UpdateOptions;
+use mongodb::Database;
+
+/// Picks stored for the days from `from` to `to`, both `YYYY-MM-DD` and inclusive.
+pub async fn find_picks(
+    db: &Database,
+    from: &str,
+    to: &str,
+) -> Result<Vec<DailyRecipePick>, DbError> {
+    let collection = get_daily_recipes_collection(&db);
+    let filter = doc! { "date": { "$gte": from, "$lte": to } };
+    crud::find_many(collection, filter, None).await
+}
+
+/// Stores the pick for the day unless one exists already, and returns
+/// whichever pick is stored, so concurrent requests agree on the recipe.
+pub async fn save_pick(
+    db: &Database,
+    date: &str,
+    recipe_id: ObjectId,
+) -> Result<Option<DailyRecipePick>, DbError> {
+    let collection = get_daily_recipes_collection(&db);
+    let update = doc! {
+        "$setOnInsert": { "date": date, "recipe_id": recipe_id, "created_at": DateTime::now() }
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    collection
+        .update_one(crud::not_deleted(doc! { "date": date }), update, options)
+        .await
+        .map_err(|_err| DbError::new("Failed to save_pick.".to_string()))?;
+    Ok(find_picks(db, date, date).await?.pop())
+}
+
+/// Moves the pick of a day to the trash, e.g. when its recipe was deleted.
+pub async fn delete_pick(db: &Database, date: &str) -> Result<(), DbError> {
+    let collection = get_daily_recipes_collection(&db);
+    collection
+        .update_many(
+            crud::not_deleted(doc! { "date": date }),
+            doc! { "$set": { "deleted_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map(|_| ())
+        .map_err(|_err| DbError::new("Failed to delete_pick.".to_string()))
+}
+
+/// Takes a pick out of the trash, moving the pick made for its day since
+/// to the trash in its place. Returns false if it isn't in the trash.
+pub async fn restore_pick(db: &Database, id: ObjectId) -> Result<bool, DbError> {
+    let collection = get_daily_recipes_collection(&db);
+    let trashed = collection
+        .find_one(doc! { "_id": id, "deleted_at": { "$ne": Bson::Null } }, None)
+        .await
+        .map_err(|_err| DbError::new("Failed to restore_pick.".to_string()))?;
+    match trashed {
+        Some(pick) => {
+            delete_pick(db, &pick.date).await?;
+            crud::restore_one(collection, id)
+                .await
+                .map(|result| result.modified_count == 1)
+        }
+        None => Ok(false),
+    }
+}
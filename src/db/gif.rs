@@ -2,8 +2,9 @@ use crate::db::error::DbError;
 use crate::db::{crud, get_recipe_steps_collection, get_recipes_collection};
 use crate::models::gif::RecipeStep;
 use crate::models::DocumentConvertable;
+use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
-use mongodb::results::{DeleteResult, InsertOneResult};
+use mongodb::results::{InsertOneResult, UpdateResult};
 use mongodb::Database;
 
 pub async fn insert_recipe_step(
@@ -36,7 +37,13 @@ pub async fn update_recipe_step(
     crud::update_one(collection, id, recipe_step.to_document()).await
 }
 
-pub async fn delete_one_recipe_step(db: &Database, id: ObjectId) -> Result<DeleteResult, DbError> {
+pub async fn delete_one_recipe_step(db: &Database, id: ObjectId) -> Result<UpdateResult, DbError> {
     let collection = get_recipe_steps_collection(&db);
     crud::delete_one(collection, id).await
 }
+
+/// Deletes for good the step records of a purged recipe.
+pub async fn purge_recipe_steps(db: &Database, ids: Vec<ObjectId>) -> Result<(), DbError> {
+    let collection = get_recipe_steps_collection(&db);
+    crud::purge_many(collection, doc! { "_id": { "$in": ids } }).await
+}
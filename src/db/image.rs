@@ -2,8 +2,9 @@ use crate::db::error::DbError;
 use crate::db::{crud, get_images_collection, get_recipes_collection};
 use crate::models::image::Image;
 use crate::models::DocumentConvertable;
+use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
-use mongodb::results::{DeleteResult, InsertOneResult};
+use mongodb::results::{InsertOneResult, UpdateResult};
 use mongodb::Database;
 
 pub async fn insert_image(db: &Database, image: Image) -> Result<InsertOneResult, DbError> {
@@ -21,7 +22,13 @@ pub async fn find_all_images(db: &Database) -> Result<Vec<Image>, DbError> {
     crud::find_all(collection).await
 }
 
-pub async fn delete_one_image(db: &Database, id: ObjectId) -> Result<DeleteResult, DbError> {
+pub async fn delete_one_image(db: &Database, id: ObjectId) -> Result<UpdateResult, DbError> {
     let collection = get_images_collection(&db);
     crud::delete_one(collection, id).await
 }
+
+/// Deletes for good the image records of a purged recipe.
+pub async fn purge_images(db: &Database, ids: Vec<ObjectId>) -> Result<(), DbError> {
+    let collection = get_images_collection(&db);
+    crud::purge_many(collection, doc! { "_id": { "$in": ids } }).await
+}
@@ -0,0 +1,80 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_meal_plan_collection};
+use crate::models::meal_plan::{MealPlanEntry, MealPlanEntryDocument, MealSlot};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, to_bson, DateTime};
+use mongodb::results::{InsertOneResult, UpdateResult};
+use mongodb::Database;
+
+pub async fn insert_entry(
+    db: &Database,
+    entry: MealPlanEntryDocument,
+) -> Result<InsertOneResult, DbError> {
+    let collection = get_meal_plan_collection(&db);
+    crud::insert_one(collection, entry).await
+}
+
+/// Inserts several entries at once. Returns how many were inserted.
+pub async fn insert_entries(
+    db: &Database,
+    entries: Vec<MealPlanEntryDocument>,
+) -> Result<usize, DbError> {
+    if entries.is_empty() {
+        return Ok(0);
+    }
+    let collection = get_meal_plan_collection(&db);
+    collection
+        .insert_many(entries, None)
+        .await
+        .map(|result| result.inserted_ids.len())
+        .map_err(|_err| DbError::new("Failed to insert_entries.".to_string()))
+}
+
+pub async fn find_one_entry(db: &Database, id: ObjectId) -> Result<Option<MealPlanEntry>, DbError> {
+    let collection = get_meal_plan_collection(&db);
+    crud::find_one(collection, id).await
+}
+
+/// The user's entries from `from` to `to`, both `YYYY-MM-DD` and inclusive.
+pub async fn find_entries(
+    db: &Database,
+    user_id: &str,
+    from: &str,
+    to: &str,
+) -> Result<Vec<MealPlanEntry>, DbError> {
+    let collection = get_meal_plan_collection(&db);
+    let filter = doc! { "user_id": user_id, "date": { "$gte": from, "$lte": to } };
+    crud::find_many(collection, filter, None).await
+}
+
+pub async fn update_entry(
+    db: &Database,
+    id: ObjectId,
+    date: String,
+    slot: MealSlot,
+    recipe_id: ObjectId,
+    servings: i32,
+    note: Option<String>,
+) -> Result<(), DbError> {
+    let collection = get_meal_plan_collection(&db);
+    let slot = to_bson(&slot).map_err(|err| DbError::new(err.to_string()))?;
+    crud::set_fields(
+        collection,
+        id,
+        doc! {
+            "date": date,
+            "slot": slot,
+            "recipe_id": recipe_id,
+            "servings": servings,
+            "note": note,
+            "updated_at": DateTime::now(),
+        },
+    )
+    .await
+    .map(|_| ())
+}
+
+pub async fn delete_one_entry(db: &Database, id: ObjectId) -> Result<UpdateResult, DbError> {
+    let collection = get_meal_plan_collection(&db);
+    crud::delete_one(collection, id).await
+}
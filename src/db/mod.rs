@@ -1,19 +1,38 @@
 use crate::db::error::DbError;
+use crate::models::collection::CollectionDocument;
+use crate::models::comment::CommentDocument;
+use crate::models::cooking::CookingSessionDocument;
+use crate::models::daily::DailyRecipeDocument;
 use crate::models::gif::RecipeStepDocument;
 use crate::models::image::{Image, ImageDocument, ImageFile};
+use crate::models::meal_plan::MealPlanEntryDocument;
+use crate::models::publishing::StatusChangeDocument;
 use crate::models::recipe::{Recipe, RecipeDocument};
+use crate::models::review::ReviewDocument;
+use crate::models::shopping_list::ShoppingListDocument;
+use crate::models::taxonomy::TaxonomyTermDocument;
 use mongodb::bson::oid::ObjectId;
 use mongodb::bson::{doc, Document};
 use mongodb::{Client, Collection, Database};
 use rocket::fairing::AdHoc;
 use std::env;
 
+pub mod collection;
+pub mod comment;
+pub mod cooking;
 pub mod crud;
 pub mod customer;
+pub mod daily;
 pub mod error;
 pub mod gif;
 pub mod image;
+pub mod meal_plan;
+pub mod publishing;
 pub mod recipe;
+pub mod review;
+pub mod shopping_list;
+pub mod taxonomy;
+pub mod trash;
 
 pub fn init() -> AdHoc {
     AdHoc::on_ignite("Connecting to MongoDB", |rocket| async {
@@ -26,7 +45,7 @@ pub fn init() -> AdHoc {
     })
 }
 
-async fn connect() -> mongodb::error::Result<Database> {
+pub async fn connect() -> mongodb::error::Result<Database> {
     let mongo_uri = env::var("MONGO_URI").expect("MONGO_URI is not found.");
     let mongo_db_name = env::var("MONGO_DB_NAME").expect("MONGO_DB_NAME is not found.");
 
@@ -50,6 +69,42 @@ fn get_images_collection(db: &Database) -> Collection<ImageDocument> {
     db.collection::<ImageDocument>("Images")
 }
 
+fn get_reviews_collection(db: &Database) -> Collection<ReviewDocument> {
+    db.collection::<ReviewDocument>("Reviews")
+}
+
+fn get_comments_collection(db: &Database) -> Collection<CommentDocument> {
+    db.collection::<CommentDocument>("Comments")
+}
+
+fn get_taxonomy_collection(db: &Database) -> Collection<TaxonomyTermDocument> {
+    db.collection::<TaxonomyTermDocument>("Taxonomy")
+}
+
+fn get_shopping_lists_collection(db: &Database) -> Collection<ShoppingListDocument> {
+    db.collection::<ShoppingListDocument>("ShoppingLists")
+}
+
+fn get_meal_plan_collection(db: &Database) -> Collection<MealPlanEntryDocument> {
+    db.collection::<MealPlanEntryDocument>("MealPlan")
+}
+
+fn get_daily_recipes_collection(db: &Database) -> Collection<DailyRecipeDocument> {
+    db.collection::<DailyRecipeDocument>("DailyRecipes")
+}
+
+fn get_collections_collection(db: &Database) -> Collection<CollectionDocument> {
+    db.collection::<CollectionDocument>("Collections")
+}
+
+fn get_status_changes_collection(db: &Database) -> Collection<StatusChangeDocument> {
+    db.collection::<StatusChangeDocument>("RecipeStatusChanges")
+}
+
+fn get_cooking_sessions_collection(db: &Database) -> Collection<CookingSessionDocument> {
+    db.collection::<CookingSessionDocument>("CookingSessions")
+}
+
 fn create_filter(id: &ObjectId) -> Result<Document, DbError> {
     Ok(doc! { "_id": id })
 }
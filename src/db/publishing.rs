@@ -0,0 +1,27 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_status_changes_collection};
+use crate::models::publishing::{StatusChange, StatusChangeDocument};
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::results::InsertOneResult;
+use mongodb::Database;
+
+pub async fn insert_status_change(
+    db: &Database,
+    change: StatusChangeDocument,
+) -> Result<InsertOneResult, DbError> {
+    let collection = get_status_changes_collection(&db);
+    crud::insert_one(collection, change).await
+}
+
+/// The audit trail of a recipe's status, newest first.
+pub async fn find_status_changes(
+    db: &Database,
+    recipe_id: ObjectId,
+    limit: i64,
+    page: i64,
+) -> Result<Vec<StatusChange>, DbError> {
+    let collection = get_status_changes_collection(&db);
+    let options = crud::page_options(limit, page, doc! { "created_at": -1 });
+    crud::find_many(collection, doc! { "recipe_id": recipe_id }, Some(options)).await
+}
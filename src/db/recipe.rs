@@ -1,16 +1,28 @@
 use crate::db;
 use crate::db::error::DbError;
 use crate::db::get_recipes_collection;
-use crate::models::recipe::Recipe;
+use crate::models::recipe::{Recipe, RecipeDocument, RecipeStatus};
 use crate::models::DocumentConvertable;
+use crate::services::{dietary, similarity};
 use db::crud;
+use futures::TryStreamExt;
 use mongodb::bson::oid::ObjectId;
-use mongodb::results::{DeleteResult, InsertOneResult};
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use mongodb::results::{InsertOneResult, UpdateResult};
 use mongodb::Database;
 
+/// Converts the recipe for storage, deriving the fields computed on write.
+fn to_stored_document(recipe: &Recipe) -> RecipeDocument {
+    let mut document = recipe.to_document();
+    document.dietary = dietary::infer(&document.ingredients, &document.dietary_overrides);
+    document
+}
+
 pub async fn insert_recipe(db: &Database, recipe: Recipe) -> Result<InsertOneResult, DbError> {
     let collection = get_recipes_collection(&db);
-    crud::insert_one(collection, recipe.to_document()).await
+    let result = crud::insert_one(collection, to_stored_document(&recipe)).await;
+    similarity::invalidate();
+    result
 }
 
 pub async fn find_one_recipe(db: &Database, id: ObjectId) -> Result<Option<Recipe>, DbError> {
@@ -18,21 +30,261 @@ pub async fn find_one_recipe(db: &Database, id: ObjectId) -> Result<Option<Recip
     crud::find_one(collection, id).await
 }
 
+/// The recipe if it is published, for callers that may not see drafts.
+pub async fn find_one_published_recipe(db: &Database, id: ObjectId) -> Result<Option<Recipe>, DbError> {
+    let collection = get_recipes_collection(&db);
+    let recipes: Vec<Recipe> = crud::find_many(collection, only_published(doc! { "_id": id }), None).await?;
+    Ok(recipes.into_iter().next())
+}
+
 pub async fn find_all_recipes(db: &Database) -> Result<Vec<Recipe>, DbError> {
     let collection = get_recipes_collection(&db);
     crud::find_all(collection).await
 }
 
+pub async fn find_recipes(db: &Database, filter: Document) -> Result<Vec<Recipe>, DbError> {
+    let collection = get_recipes_collection(&db);
+    crud::find_many(collection, filter, None).await
+}
+
+/// Like `find_recipes`, recipes in the trash included.
+pub async fn find_recipes_with_deleted(db: &Database, filter: Document) -> Result<Vec<Recipe>, DbError> {
+    let collection = get_recipes_collection(&db);
+    crud::find_many_with_deleted(collection, filter, None).await
+}
+
+/// A published recipe showing the image or step `id`, `field` being
+/// `images` or `steps`.
+pub async fn find_published_recipe_with_media(
+    db: &Database,
+    field: &str,
+    id: &str,
+) -> Result<Option<Recipe>, DbError> {
+    let filter = only_published(doc! { format!("{}._id", field): id });
+    Ok(find_recipes(db, filter).await?.into_iter().next())
+}
+
+/// Narrows `filter` to published recipes, including those stored before
+/// recipes had a status.
+pub fn only_published(mut filter: Document) -> Document {
+    let published = RecipeStatus::published.to_string();
+    filter.insert("status", doc! { "$in": [published, Bson::Null] });
+    filter
+}
+
+/// Counts the recipes matching `filter` per assigned taxonomy term.
+pub async fn count_recipes_by_term(
+    db: &Database,
+    filter: Document,
+) -> Result<Vec<(ObjectId, i64)>, DbError> {
+    let pipeline = vec![
+        doc! { "$match": crud::not_deleted(filter) },
+        doc! { "$unwind": "$term_ids" },
+        doc! { "$group": { "_id": "$term_ids", "count": { "$sum": 1 } } },
+    ];
+    let counts: Vec<Document> = get_recipes_collection(&db)
+        .aggregate(pipeline, None)
+        .await
+        .map_err(|_err| DbError::new("Failed to count_recipes_by_term.".to_string()))?
+        .try_collect()
+        .await
+        .map_err(|_err| DbError::new("Failed to count_recipes_by_term.".to_string()))?;
+
+    Ok(counts
+        .into_iter()
+        .filter_map(|count| {
+            let id = count.get_object_id("_id").ok()?;
+            let count = count
+                .get_i32("count")
+                .map(i64::from)
+                .or_else(|_| count.get_i64("count"))
+                .ok()?;
+            Some((id, count))
+        })
+        .collect())
+}
+
+pub async fn assign_term(db: &Database, id: ObjectId, term_id: ObjectId) -> Result<bool, DbError> {
+    let result = get_recipes_collection(&db)
+        .update_one(crud::not_deleted(doc! { "_id": id }), doc! { "$addToSet": { "term_ids": term_id } }, None)
+        .await
+        .map(|result| result.matched_count == 1)
+        .map_err(|_err| DbError::new("Failed to assign_term.".to_string()));
+    similarity::invalidate();
+    result
+}
+
+pub async fn unassign_term(db: &Database, id: ObjectId, term_id: ObjectId) -> Result<bool, DbError> {
+    let result = get_recipes_collection(&db)
+        .update_one(crud::not_deleted(doc! { "_id": id }), doc! { "$pull": { "term_ids": term_id } }, None)
+        .await
+        .map(|result| result.matched_count == 1)
+        .map_err(|_err| DbError::new("Failed to unassign_term.".to_string()));
+    similarity::invalidate();
+    result
+}
+
 pub async fn update_recipe(
     db: &Database,
     id: ObjectId,
     recipe: Recipe,
 ) -> Result<Option<Recipe>, DbError> {
     let collection = get_recipes_collection(&db);
-    crud::update_one(collection, id, recipe.to_document()).await
+    let result = crud::update_one(collection, id, to_stored_document(&recipe)).await;
+    similarity::invalidate();
+    result
+}
+
+/// Moves the recipe from `from` to `to`, dropping any scheduled publishing.
+/// Returns false when the recipe isn't in `from` (anymore).
+pub async fn change_recipe_status(
+    db: &Database,
+    id: ObjectId,
+    from: RecipeStatus,
+    to: RecipeStatus,
+) -> Result<bool, DbError> {
+    let mut filter = doc! { "_id": id, "status": from.to_string() };
+    if from == RecipeStatus::published {
+        filter = only_published(doc! { "_id": id });
+    }
+    let update = doc! { "$set": { "status": to.to_string(), "publish_at": Bson::Null } };
+    let result = get_recipes_collection(&db)
+        .update_one(crud::not_deleted(filter), update, None)
+        .await
+        .map(|result| result.matched_count == 1)
+        .map_err(|_err| DbError::new("Failed to change_recipe_status.".to_string()));
+    similarity::invalidate();
+    result
+}
+
+/// Sets when the recipe in review is to be published. Returns false when
+/// the recipe isn't in review.
+pub async fn schedule_recipe(
+    db: &Database,
+    id: ObjectId,
+    publish_at: DateTime,
+) -> Result<bool, DbError> {
+    let filter = crud::not_deleted(doc! { "_id": id, "status": RecipeStatus::in_review.to_string() });
+    get_recipes_collection(&db)
+        .update_one(filter, doc! { "$set": { "publish_at": publish_at } }, None)
+        .await
+        .map(|result| result.matched_count == 1)
+        .map_err(|_err| DbError::new("Failed to schedule_recipe.".to_string()))
+}
+
+/// Recipes in review whose `publish_at` is not after `now`.
+pub async fn find_due_recipes(db: &Database, now: DateTime) -> Result<Vec<Recipe>, DbError> {
+    let collection = get_recipes_collection(&db);
+    let filter = doc! {
+        "status": RecipeStatus::in_review.to_string(),
+        "publish_at": { "$lte": now },
+    };
+    crud::find_many(collection, filter, None).await
+}
+
+/// Publishes the recipe if it is still in review and due at `now`, so a
+/// recipe sent back or rescheduled in the meantime is left alone.
+pub async fn publish_due_recipe(db: &Database, id: ObjectId, now: DateTime) -> Result<bool, DbError> {
+    let filter = crud::not_deleted(doc! {
+        "_id": id,
+        "status": RecipeStatus::in_review.to_string(),
+        "publish_at": { "$lte": now },
+    });
+    let update = doc! {
+        "$set": { "status": RecipeStatus::published.to_string(), "publish_at": Bson::Null }
+    };
+    let result = get_recipes_collection(&db)
+        .update_one(filter, update, None)
+        .await
+        .map(|result| result.matched_count == 1)
+        .map_err(|_err| DbError::new("Failed to publish_due_recipe.".to_string()));
+    similarity::invalidate();
+    result
+}
+
+/// Matches recipes with a name, ingredient or step lacking a translation to
+/// any of `locales`, or `None` when there are no locales to look for.
+pub fn missing_translations_filter(locales: &[String]) -> Option<Document> {
+    let text = doc! { "$regex": "\\S" };
+    let lacking: Vec<Document> = locales
+        .iter()
+        .flat_map(|locale| {
+            let untranslated = doc! { "$exists": false };
+            [
+                doc! { "name": text.clone(), format!("name_translations.{}", locale): untranslated.clone() },
+                doc! { "ingredients": { "$elemMatch": {
+                    "name": text.clone(), format!("name_translations.{}", locale): untranslated.clone()
+                } } },
+                doc! { "steps": { "$elemMatch": {
+                    "description": text.clone(), format!("description_translations.{}", locale): untranslated
+                } } },
+            ]
+        })
+        .collect();
+    match lacking.is_empty() {
+        true => None,
+        false => Some(doc! { "$or": lacking }),
+    }
+}
+
+/// Recipes with a name, ingredient or step lacking a translation to any of
+/// `locales`, oldest first. Without locales nothing can be missing.
+pub async fn find_recipes_missing_translations(
+    db: &Database,
+    locales: &[String],
+    limit: i64,
+    page: i64,
+) -> Result<Vec<Recipe>, DbError> {
+    let filter = match missing_translations_filter(locales) {
+        Some(filter) => filter,
+        None => return Ok(vec![]),
+    };
+    let collection = get_recipes_collection(&db);
+    let options = crud::page_options(limit, page, doc! { "created_at": 1, "_id": 1 });
+    crud::find_many(collection, filter, Some(options)).await
+}
+
+/// Lists the recipes forked from the recipe, newest first. Drafts and
+/// recipes in review are left out unless `with_drafts`.
+pub async fn find_forks(
+    db: &Database,
+    id: ObjectId,
+    with_drafts: bool,
+    limit: i64,
+    page: i64,
+) -> Result<Vec<Recipe>, DbError> {
+    let collection = get_recipes_collection(&db);
+    let options = crud::page_options(limit, page, doc! { "created_at": -1 });
+    let mut filter = doc! { "forked_from": id };
+    if !with_drafts {
+        filter = only_published(filter);
+    }
+    crud::find_many(collection, filter, Some(options)).await
+}
+
+/// Stores the recipe under `id`, replacing the recipe with that id if any.
+pub async fn replace_recipe(
+    db: &Database,
+    id: ObjectId,
+    recipe: Recipe,
+) -> Result<UpdateResult, DbError> {
+    let collection = get_recipes_collection(&db);
+    let result = crud::replace_or_insert(collection, id, to_stored_document(&recipe)).await;
+    similarity::invalidate();
+    result
+}
+
+/// Deletes recipes for good, e.g. those of an import that failed halfway.
+pub async fn purge_recipes(db: &Database, ids: Vec<ObjectId>) -> Result<(), DbError> {
+    let collection = get_recipes_collection(&db);
+    let result = crud::purge_many(collection, doc! { "_id": { "$in": ids } }).await;
+    similarity::invalidate();
+    result
 }
 
-pub async fn delete_one_recipe(db: &Database, id: ObjectId) -> Result<DeleteResult, DbError> {
+pub async fn delete_one_recipe(db: &Database, id: ObjectId) -> Result<UpdateResult, DbError> {
     let collection = get_recipes_collection(&db);
-    crud::delete_one(collection, id).await
+    let result = crud::delete_one(collection, id).await;
+    similarity::invalidate();
+    result
 }
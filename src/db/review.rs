@@ -0,0 +1,139 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_recipes_collection, get_reviews_collection};
+use crate::models::review::{Review, ReviewDocument, ReviewFlag};
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, to_bson, DateTime, Document};
+use mongodb::results::{InsertOneResult, UpdateResult};
+use mongodb::Database;
+
+pub async fn insert_review(
+    db: &Database,
+    review: ReviewDocument,
+) -> Result<InsertOneResult, DbError> {
+    let collection = get_reviews_collection(&db);
+    crud::insert_one(collection, review).await
+}
+
+pub async fn find_one_review(db: &Database, id: ObjectId) -> Result<Option<Review>, DbError> {
+    let collection = get_reviews_collection(&db);
+    crud::find_one(collection, id).await
+}
+
+pub async fn find_review_by_user(
+    db: &Database,
+    recipe_id: ObjectId,
+    user_id: &str,
+) -> Result<Option<Review>, DbError> {
+    let collection = get_reviews_collection(&db);
+    let filter = doc! { "recipe_id": recipe_id, "user_id": user_id };
+    Ok(crud::find_many(collection, filter, None).await?.pop())
+}
+
+/// Lists visible reviews of a recipe, newest first.
+pub async fn find_recipe_reviews(
+    db: &Database,
+    recipe_id: ObjectId,
+    limit: i64,
+    page: i64,
+) -> Result<Vec<Review>, DbError> {
+    let collection = get_reviews_collection(&db);
+    let filter = doc! { "recipe_id": recipe_id, "hidden": false };
+    let options = crud::page_options(limit, page, doc! { "created_at": -1 });
+    crud::find_many(collection, filter, Some(options)).await
+}
+
+/// Lists reviews that were flagged at least once, including hidden ones.
+pub async fn find_flagged_reviews(
+    db: &Database,
+    limit: i64,
+    page: i64,
+) -> Result<Vec<Review>, DbError> {
+    let collection = get_reviews_collection(&db);
+    let filter = doc! { "flags.0": { "$exists": true } };
+    let options = crud::page_options(limit, page, doc! { "updated_at": -1 });
+    crud::find_many(collection, filter, Some(options)).await
+}
+
+pub async fn update_review_content(
+    db: &Database,
+    id: ObjectId,
+    rating: i32,
+    text: String,
+) -> Result<(), DbError> {
+    let collection = get_reviews_collection(&db);
+    crud::set_fields(
+        collection,
+        id,
+        doc! { "rating": rating, "text": text, "updated_at": DateTime::now() },
+    )
+    .await
+    .map(|_| ())
+}
+
+pub async fn set_review_hidden(db: &Database, id: ObjectId, hidden: bool) -> Result<(), DbError> {
+    let collection = get_reviews_collection(&db);
+    crud::set_fields(
+        collection,
+        id,
+        doc! { "hidden": hidden, "updated_at": DateTime::now() },
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Adds a moderation flag, one per user and review. Returns false if the
+/// review doesn't exist or the user flagged it already.
+pub async fn flag_review(db: &Database, id: ObjectId, flag: ReviewFlag) -> Result<bool, DbError> {
+    let collection = get_reviews_collection(&db);
+    let filter = crud::not_deleted(doc! { "_id": id, "flags.user_id": { "$ne": flag.user_id.clone() } });
+    let flag = to_bson(&flag).map_err(|err| DbError::new(err.to_string()))?;
+    collection
+        .update_one(filter, doc! { "$push": { "flags": flag } }, None)
+        .await
+        .map(|result| result.matched_count == 1)
+        .map_err(|_err| DbError::new("Failed to flag_review.".to_string()))
+}
+
+pub async fn delete_one_review(db: &Database, id: ObjectId) -> Result<UpdateResult, DbError> {
+    let collection = get_reviews_collection(&db);
+    crud::delete_one(collection, id).await
+}
+
+/// Deletes for good the reviews of a purged recipe.
+pub async fn purge_recipe_reviews(db: &Database, recipe_id: ObjectId) -> Result<(), DbError> {
+    let collection = get_reviews_collection(&db);
+    crud::purge_many(collection, doc! { "recipe_id": recipe_id }).await
+}
+
+/// Recomputes the recipe's rating average and count from its visible reviews
+/// and stores them on the recipe document.
+pub async fn refresh_recipe_rating(db: &Database, recipe_id: ObjectId) -> Result<(), DbError> {
+    let pipeline = vec![
+        doc! { "$match": { "recipe_id": recipe_id, "hidden": false, "deleted_at": null } },
+        doc! { "$group": { "_id": null, "average": { "$avg": "$rating" }, "count": { "$sum": 1 } } },
+    ];
+    let summary: Option<Document> = get_reviews_collection(&db)
+        .aggregate(pipeline, None)
+        .await
+        .map_err(|_err| DbError::new("Failed to aggregate reviews.".to_string()))?
+        .try_next()
+        .await
+        .map_err(|_err| DbError::new("Failed to aggregate reviews.".to_string()))?;
+
+    let (average, count) = match summary {
+        Some(summary) => (
+            summary.get_f64("average").unwrap_or(0.0),
+            summary.get_i32("count").unwrap_or(0),
+        ),
+        None => (0.0, 0),
+    };
+
+    crud::set_fields(
+        get_recipes_collection(&db),
+        recipe_id,
+        doc! { "rating_average": average, "rating_count": count },
+    )
+    .await
+    .map(|_| ())
+}
@@ -0,0 +1,62 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_shopping_lists_collection};
+use crate::models::shopping_list::{ShoppingList, ShoppingListDocument};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, DateTime};
+use mongodb::results::{InsertOneResult, UpdateResult};
+use mongodb::Database;
+
+pub async fn insert_shopping_list(
+    db: &Database,
+    list: ShoppingListDocument,
+) -> Result<InsertOneResult, DbError> {
+    let collection = get_shopping_lists_collection(&db);
+    crud::insert_one(collection, list).await
+}
+
+pub async fn find_one_shopping_list(
+    db: &Database,
+    id: ObjectId,
+) -> Result<Option<ShoppingList>, DbError> {
+    let collection = get_shopping_lists_collection(&db);
+    crud::find_one(collection, id).await
+}
+
+/// Lists the user's shopping lists, newest first.
+pub async fn find_user_shopping_lists(
+    db: &Database,
+    user_id: &str,
+    limit: i64,
+    page: i64,
+) -> Result<Vec<ShoppingList>, DbError> {
+    let collection = get_shopping_lists_collection(&db);
+    let options = crud::page_options(limit, page, doc! { "created_at": -1 });
+    crud::find_many(collection, doc! { "user_id": user_id }, Some(options)).await
+}
+
+/// Checks an item off, or back on. Returns false when the list has no such item.
+pub async fn set_item_checked(
+    db: &Database,
+    id: ObjectId,
+    item_id: &str,
+    checked: bool,
+) -> Result<bool, DbError> {
+    let collection = get_shopping_lists_collection(&db);
+    let filter = crud::not_deleted(doc! { "_id": id, "items.id": item_id });
+    let update = doc! {
+        "$set": { "items.$.checked": checked, "updated_at": DateTime::now() }
+    };
+    collection
+        .update_one(filter, update, None)
+        .await
+        .map(|result| result.matched_count == 1)
+        .map_err(|_err| DbError::new("Failed to set_item_checked.".to_string()))
+}
+
+pub async fn delete_one_shopping_list(
+    db: &Database,
+    id: ObjectId,
+) -> Result<UpdateResult, DbError> {
+    let collection = get_shopping_lists_collection(&db);
+    crud::delete_one(collection, id).await
+}
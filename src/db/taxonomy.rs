@@ -0,0 +1,104 @@
+use crate::db::error::DbError;
+use crate::db::{crud, get_recipes_collection, get_taxonomy_collection};
+use crate::models::taxonomy::{TaxonomyTerm, TaxonomyTermDocument, TaxonomyTermInput, TermKind};
+use crate::services::similarity;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::results::{InsertOneResult, UpdateResult};
+use mongodb::Database;
+
+pub async fn insert_term(
+    db: &Database,
+    input: TaxonomyTermInput,
+) -> Result<InsertOneResult, DbError> {
+    let collection = get_taxonomy_collection(&db);
+    let document = TaxonomyTermDocument {
+        _id: None,
+        kind: input.kind,
+        slug: input.slug(),
+        name: input.name,
+        created_at: DateTime::now(),
+    };
+    crud::insert_one(collection, document).await
+}
+
+pub async fn find_one_term(db: &Database, id: ObjectId) -> Result<Option<TaxonomyTerm>, DbError> {
+    let collection = get_taxonomy_collection(&db);
+    crud::find_one(collection, id).await
+}
+
+pub async fn find_term_by_slug(
+    db: &Database,
+    kind: TermKind,
+    slug: &str,
+) -> Result<Option<TaxonomyTerm>, DbError> {
+    let collection = get_taxonomy_collection(&db);
+    let filter = doc! { "kind": kind.to_string(), "slug": slug };
+    Ok(crud::find_many(collection, filter, None).await?.pop())
+}
+
+/// Lists terms sorted by name, optionally only those of one kind.
+pub async fn find_terms(db: &Database, kind: Option<TermKind>) -> Result<Vec<TaxonomyTerm>, DbError> {
+    let collection = get_taxonomy_collection(&db);
+    let filter = match kind {
+        Some(kind) => doc! { "kind": kind.to_string() },
+        None => Document::new(),
+    };
+    let options = FindOptions::builder().sort(doc! { "kind": 1, "name": 1 }).build();
+    crud::find_many(collection, filter, Some(options)).await
+}
+
+pub async fn find_terms_by_ids(
+    db: &Database,
+    ids: Vec<ObjectId>,
+) -> Result<Vec<TaxonomyTerm>, DbError> {
+    let collection = get_taxonomy_collection(&db);
+    crud::find_many(collection, doc! { "_id": { "$in": ids } }, None).await
+}
+
+/// The ids among `ids` of terms in the trash. Recipes keep those until the
+/// term is purged, so it can be restored with its assignments.
+pub async fn find_trashed_term_ids(
+    db: &Database,
+    ids: Vec<ObjectId>,
+) -> Result<Vec<ObjectId>, DbError> {
+    let collection = get_taxonomy_collection(&db);
+    let trashed = crud::find_deleted(collection).await?;
+    Ok(trashed
+        .iter()
+        .filter_map(|term| term.get_object_id("_id").ok())
+        .filter(|id| ids.contains(id))
+        .collect())
+}
+
+pub async fn update_term(
+    db: &Database,
+    id: ObjectId,
+    input: TaxonomyTermInput,
+) -> Result<Option<TaxonomyTerm>, DbError> {
+    let collection = get_taxonomy_collection(&db);
+    crud::set_fields(
+        collection,
+        id,
+        doc! { "kind": input.kind.to_string(), "slug": input.slug(), "name": input.name },
+    )
+    .await?;
+    find_one_term(db, id).await
+}
+
+pub async fn delete_one_term(db: &Database, id: ObjectId) -> Result<UpdateResult, DbError> {
+    let collection = get_taxonomy_collection(&db);
+    crud::delete_one(collection, id).await
+}
+
+/// Unassigns a purged term from every recipe.
+pub async fn unassign_everywhere(db: &Database, id: ObjectId) -> Result<(), DbError> {
+    get_recipes_collection(&db)
+        .update_many(doc! { "term_ids": id }, doc! { "$pull": { "term_ids": id } }, None)
+        .await
+        .map(|_| ())
+        .map_err(|_err| DbError::new("Failed to unassign term.".to_string()))?;
+    similarity::invalidate();
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::db::error::DbError;
+use crate::db::{
+    collection, comment, crud, daily, get_collections_collection, get_comments_collection,
+    get_daily_recipes_collection, get_images_collection, get_meal_plan_collection,
+    get_recipe_steps_collection, get_recipes_collection, get_reviews_collection,
+    get_shopping_lists_collection, get_taxonomy_collection, gif, image, review, taxonomy,
+};
+use crate::models::trash::TrashKind;
+use crate::services::similarity;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, Document};
+use mongodb::{Collection, Database};
+
+/// The collection holding resources of the kind, whatever their type.
+fn get_trash_collection(db: &Database, kind: TrashKind) -> Collection<Document> {
+    match kind {
+        TrashKind::recipe => get_recipes_collection(db).clone_with_type(),
+        TrashKind::image => get_images_collection(db).clone_with_type(),
+        TrashKind::gif => get_recipe_steps_collection(db).clone_with_type(),
+        TrashKind::collection => get_collections_collection(db).clone_with_type(),
+        TrashKind::shopping_list => get_shopping_lists_collection(db).clone_with_type(),
+        TrashKind::meal_plan => get_meal_plan_collection(db).clone_with_type(),
+        TrashKind::review => get_reviews_collection(db).clone_with_type(),
+        TrashKind::taxonomy => get_taxonomy_collection(db).clone_with_type(),
+        TrashKind::comment => get_comments_collection(db).clone_with_type(),
+        TrashKind::daily => get_daily_recipes_collection(db).clone_with_type(),
+    }
+}
+
+/// Trashed resources of the kind as stored, most recently deleted first.
+pub async fn find_trash(db: &Database, kind: TrashKind) -> Result<Vec<Document>, DbError> {
+    crud::find_deleted(get_trash_collection(db, kind)).await
+}
+
+/// Takes the resource out of the trash. Returns false if it isn't there.
+pub async fn restore(db: &Database, kind: TrashKind, id: ObjectId) -> Result<bool, DbError> {
+    if kind == TrashKind::daily {
+        return daily::restore_pick(db, id).await;
+    }
+    let restored = crud::restore_one(get_trash_collection(db, kind), id)
+        .await
+        .map(|result| result.modified_count == 1)?;
+    if kind == TrashKind::recipe {
+        similarity::invalidate();
+    }
+    Ok(restored)
+}
+
+/// Ids of the images or steps embedded in a recipe, stored as strings.
+fn embedded_ids(recipe: &Document, field: &str) -> Vec<ObjectId> {
+    recipe
+        .get_array(field)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_document()?.get_str("_id").ok())
+                .filter_map(|id| ObjectId::parse_str(id).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Deletes for good what was trashed of the kind before `before`, along
+/// with the references other documents hold to purged recipes and terms.
+/// A purged recipe takes its reviews, comments, images and steps with it,
+/// purged comments stay as tombstones. Returns how many were purged.
+pub async fn purge(db: &Database, kind: TrashKind, before: DateTime) -> Result<usize, DbError> {
+    if kind == TrashKind::comment {
+        return comment::purge_deleted_comments(db, before).await;
+    }
+    let purged = crud::purge_deleted(get_trash_collection(db, kind), before).await?;
+    for document in purged.iter() {
+        let id = match document.get_object_id("_id") {
+            Ok(id) => id,
+            Err(_) => continue,
+        };
+        match kind {
+            TrashKind::recipe => {
+                collection::remove_recipe_everywhere(db, id).await?;
+                review::purge_recipe_reviews(db, id).await?;
+                comment::purge_recipe_comments(db, id).await?;
+                image::purge_images(db, embedded_ids(document, "images")).await?;
+                gif::purge_recipe_steps(db, embedded_ids(document, "steps")).await?;
+            }
+            TrashKind::taxonomy => taxonomy::unassign_everywhere(db, id).await?,
+            _ => {}
+        }
+    }
+    Ok(purged.len())
+}
@@ -3,7 +3,9 @@ use rocket_okapi::okapi;
 use rocket_okapi::okapi::openapi3::{MediaType, Responses};
 use rocket_okapi::response::OpenApiResponderInner;
 use rocket_okapi::OpenApiError;
+use crate::models::recipe::Recipe;
 use crate::routes::gifs::FileResponse;
+use crate::routes::recipes::RecipeResponse;
 
 /// error type
 #[derive(Debug, serde::Serialize, schemars::JsonSchema)]
@@ -29,6 +31,9 @@ impl MyError {
         match code {
             400 => reason = "Bad Request".to_string(),
             401 => reason = "Unauthorized".to_string(),
+            403 => reason = "Forbidden".to_string(),
+            404 => reason = "Not Found".to_string(),
+            409 => reason = "Conflict".to_string(),
             _ => reason = "Error".to_string(),
         }
         MyError {
@@ -127,4 +132,52 @@ impl OpenApiResponderInner for FileResponse {
             ..Default::default()
         })
     }
-}
\ No newline at end of file
+}
+
+
+impl<'r> rocket::response::Responder<'r, 'static> for RecipeResponse {
+    fn respond_to(self, req: &'r rocket::Request<'_>) -> rocket::response::Result<'static> {
+        use rocket::http::ContentType;
+        use rocket::response::Responder;
+        let response = match self {
+            RecipeResponse::Json(recipe) => rocket::serde::json::Json(recipe).respond_to(req)?,
+            RecipeResponse::JsonLd(body) => {
+                (ContentType::new("application", "ld+json"), body).respond_to(req)?
+            }
+            RecipeResponse::Html(body) => (ContentType::HTML, body).respond_to(req)?,
+            RecipeResponse::Markdown(body) => {
+                (ContentType::new("text", "markdown"), body).respond_to(req)?
+            }
+        };
+        // The body depends on the Accept header, caches must tell them apart.
+        rocket::response::Response::build_from(response)
+            .raw_header("Vary", "Accept")
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for RecipeResponse {
+    fn responses(gen: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
+        use rocket_okapi::okapi::openapi3::RefOr;
+        let schema = gen.json_schema::<Recipe>();
+        Ok(Responses {
+            responses: okapi::map! {
+                "200".to_owned() => RefOr::Object(okapi::openapi3::Response {
+                    description: "The recipe, as JSON-LD, HTML or Markdown when the Accept header asks for it.".to_owned(),
+                    content: okapi::map! {
+                        "application/json".to_owned() => MediaType {
+                            schema: Some(schema),
+                            ..Default::default()
+                        },
+                        "application/ld+json".to_owned() => MediaType::default(),
+                        "text/html".to_owned() => MediaType::default(),
+                        "text/markdown".to_owned() => MediaType::default()
+                    },
+                    ..Default::default()
+                }),
+                "400".to_owned() => RefOr::Object(bad_request_response(gen))
+            },
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use mongodb::bson::DateTime;
+use mongodb::Database;
+use rocket::fairing::AdHoc;
+
+use crate::db::cooking;
+use crate::db::error::DbError;
+use crate::models::cooking::{CookingEvent, CookingEventKind};
+use crate::models::ObjectConvertable;
+use crate::services::cooking::{due_timers, CookingChannel};
+
+/// How often timers are checked, and so how late an expiry may be pushed.
+const TIMER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Pushes a `timer_expired` event for each timer that ran out.
+async fn expire_due(db: &Database, channel: &CookingChannel) -> Result<(), DbError> {
+    let now = DateTime::now();
+    for session in cooking::find_sessions_with_due_timers(db, now).await? {
+        let id = match session._id {
+            Some(id) => id,
+            None => continue,
+        };
+        for timer in due_timers(&session.timers, now) {
+            if !cooking::expire_timer(db, id, &timer.id).await? {
+                continue;
+            }
+            let mut timer = timer.to_object();
+            timer.expired = true;
+            channel.send(CookingEvent {
+                kind: CookingEventKind::timer_expired,
+                session_id: id.to_string(),
+                user_id: session.user_id.clone(),
+                step_index: timer.step_index,
+                timer: Some(timer),
+                created_at: now.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Starts the background task expiring the timers of cooking sessions once
+/// the server is up.
+pub fn cooking_timers() -> AdHoc {
+    AdHoc::on_liftoff("Cooking timers", |rocket| {
+        Box::pin(async move {
+            let (db, channel) = match (rocket.state::<Database>(), rocket.state::<CookingChannel>()) {
+                (Some(db), Some(channel)) => (db.clone(), channel.clone()),
+                _ => return,
+            };
+            rocket::tokio::spawn(async move {
+                let mut ticks = rocket::tokio::time::interval(TIMER_CHECK_INTERVAL);
+                loop {
+                    ticks.tick().await;
+                    if let Err(error) = expire_due(&db, &channel).await {
+                        println!("{:?}", error);
+                    }
+                }
+            });
+        })
+    })
+}
@@ -1,2 +1,6 @@
+pub mod cooking;
 pub mod cors;
 pub mod counter;
+pub mod nutrition;
+pub mod publishing;
+pub mod trash;
@@ -0,0 +1,18 @@
+use rocket::fairing::AdHoc;
+
+use crate::services::nutrition;
+
+/// Loads the food composition dataset before launch, so a broken
+/// `FOOD_COMPOSITION_CSV` stops the server instead of failing the first
+/// request that needs nutrition.
+pub fn food_composition() -> AdHoc {
+    AdHoc::try_on_ignite("Food composition dataset", |rocket| async {
+        match nutrition::load_dataset() {
+            Ok(()) => Ok(rocket),
+            Err(error) => {
+                eprintln!("Cannot load the food composition dataset: {}", error);
+                Err(rocket)
+            }
+        }
+    })
+}
@@ -0,0 +1,57 @@
+use mongodb::bson::DateTime;
+use mongodb::Database;
+use rocket::fairing::AdHoc;
+
+use crate::db::error::DbError;
+use crate::db::{parse_id, publishing, recipe};
+use crate::models::publishing::StatusChangeDocument;
+use crate::models::recipe::RecipeStatus;
+use crate::services::publishing::interval;
+
+/// Publishes the recipes in review whose `publish_at` has come, recording
+/// each in the audit trail.
+async fn publish_due(db: &Database) -> Result<(), DbError> {
+    let now = DateTime::now();
+    for due in recipe::find_due_recipes(db, now).await? {
+        let id = parse_id(&due._id)?;
+        if !recipe::publish_due_recipe(db, id, now).await? {
+            continue;
+        }
+        let change = StatusChangeDocument {
+            _id: None,
+            recipe_id: id,
+            from: RecipeStatus::in_review,
+            to: RecipeStatus::published,
+            user_id: None,
+            admin: false,
+            scheduled: true,
+            publish_at: None,
+            note: format!("Scheduled for {}", due.publish_at.unwrap_or_default()),
+            created_at: DateTime::now(),
+        };
+        publishing::insert_status_change(db, change).await?;
+    }
+    Ok(())
+}
+
+/// Starts the background task publishing scheduled recipes once the server
+/// is up, looking for due recipes every `services::publishing::interval()`.
+pub fn scheduled_publishing() -> AdHoc {
+    AdHoc::on_liftoff("Scheduled publishing", |rocket| {
+        Box::pin(async move {
+            let db = match rocket.state::<Database>() {
+                Some(db) => db.clone(),
+                None => return,
+            };
+            rocket::tokio::spawn(async move {
+                let mut ticks = rocket::tokio::time::interval(interval());
+                loop {
+                    ticks.tick().await;
+                    if let Err(error) = publish_due(&db).await {
+                        println!("{:?}", error);
+                    }
+                }
+            });
+        })
+    })
+}
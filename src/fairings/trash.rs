@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use mongodb::bson::DateTime;
+use mongodb::Database;
+use rocket::fairing::AdHoc;
+
+use crate::db::error::DbError;
+use crate::db::trash;
+use crate::models::trash::TRASH_KINDS;
+use crate::services::trash::{purge_before, retention_days};
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Deletes for good whatever has been in the trash for longer than the
+/// retention period.
+async fn purge_expired(db: &Database) -> Result<(), DbError> {
+    let before = purge_before(DateTime::now(), retention_days());
+    for kind in TRASH_KINDS {
+        let purged = trash::purge(db, kind, before).await?;
+        if purged > 0 {
+            println!("Purged {} {} from the trash.", purged, kind);
+        }
+    }
+    Ok(())
+}
+
+/// Starts the background task emptying the trash once the server is up,
+/// every hour.
+pub fn trash_purge() -> AdHoc {
+    AdHoc::on_liftoff("Trash purge", |rocket| {
+        Box::pin(async move {
+            let db = match rocket.state::<Database>() {
+                Some(db) => db.clone(),
+                None => return,
+            };
+            rocket::tokio::spawn(async move {
+                let mut ticks = rocket::tokio::time::interval(PURGE_INTERVAL);
+                loop {
+                    ticks.tick().await;
+                    if let Err(error) = purge_expired(&db).await {
+                        println!("{:?}", error);
+                    }
+                }
+            });
+        })
+    })
+}
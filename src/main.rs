@@ -5,21 +5,47 @@ use dotenv::dotenv;
 use rocket_okapi::openapi_get_routes;
 use rocket_okapi::swagger_ui::{make_swagger_ui, SwaggerUIConfig};
 
+mod cli;
 mod db;
 mod errors;
 mod fairings;
 mod models;
 mod request_guards;
 mod routes;
+mod services;
 
-#[launch]
-fn rocket() -> _ {
+/// Serves the API, or runs a maintenance command such as
+/// `threesixfive-server catalog export <archive.tar>`.
+#[rocket::main]
+async fn main() {
+    dotenv().ok();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("catalog") {
+        if let Err(message) = cli::catalog(&args[1..]).await {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+    let _ = rocket().launch().await;
+}
+
+fn rocket() -> rocket::Rocket<rocket::Build> {
     dotenv().ok();
     rocket::build()
         .attach(db::init())
+        .attach(fairings::nutrition::food_composition())
         .attach(fairings::cors::CORS)
+        .attach(fairings::publishing::scheduled_publishing())
+        .attach(fairings::trash::trash_purge())
+        .attach(fairings::cooking::cooking_timers())
+        .manage(services::cooking::CookingChannel::new())
         .mount("/", routes![routes::images::post_image])
         .mount("/", routes![routes::gifs::post_gif])
+        .mount("/", routes![routes::recipes::get_recipe_pdf])
+        .mount("/", routes![routes::meal_plan::get_meal_plan_week_pdf])
+        .mount("/", routes![routes::catalog::import_catalog_archive])
+        .mount("/", routes![routes::cooking::get_cooking_events])
         .mount(
             "/",
             openapi_get_routes![
@@ -35,12 +61,94 @@ fn rocket() -> _ {
                 routes::recipes::update_recipe,
                 routes::recipes::delete_recipe,
                 routes::recipes::get_all_recipes,
+                routes::recipes::search_recipes,
+                routes::recipes::get_daily_recipe,
+                routes::recipes::get_similar_recipes,
+                routes::recipes::fork_recipe,
+                routes::recipes::get_recipe_forks,
+                routes::recipes::match_pantry,
+
+                routes::publishing::change_recipe_status,
+                routes::publishing::get_recipe_status_history,
 
                 routes::images::get_image,
+                routes::images::get_published_image,
                 routes::images::delete_image,
 
                 routes::gifs::get_gif,
-                routes::gifs::delete_gif
+                routes::gifs::get_published_gif,
+                routes::gifs::delete_gif,
+
+                routes::reviews::post_review,
+                routes::reviews::get_recipe_reviews,
+                routes::reviews::update_review,
+                routes::reviews::delete_review,
+                routes::reviews::flag_review,
+                routes::reviews::get_flagged_reviews,
+                routes::reviews::moderate_review,
+
+                routes::comments::post_comment,
+                routes::comments::get_recipe_comments,
+                routes::comments::update_comment,
+                routes::comments::delete_comment,
+
+                routes::taxonomy::post_term,
+                routes::taxonomy::get_terms,
+                routes::taxonomy::get_term,
+                routes::taxonomy::update_term,
+                routes::taxonomy::delete_term,
+                routes::taxonomy::assign_term,
+                routes::taxonomy::unassign_term,
+
+                routes::nutrition::get_recipe_nutrition,
+                routes::nutrition::calculate_nutrition,
+
+                routes::units::convert_units,
+
+                routes::ingredients::parse_ingredients,
+
+                routes::shopping_lists::post_shopping_list,
+                routes::shopping_lists::get_shopping_lists,
+                routes::shopping_lists::get_shopping_list,
+                routes::shopping_lists::check_shopping_list_item,
+                routes::shopping_lists::delete_shopping_list,
+
+                routes::meal_plan::post_meal_plan_entry,
+                routes::meal_plan::get_meal_plan_week,
+                routes::meal_plan::get_meal_plan_month,
+                routes::meal_plan::update_meal_plan_entry,
+                routes::meal_plan::delete_meal_plan_entry,
+                routes::meal_plan::copy_meal_plan_week,
+                routes::meal_plan::generate_meal_plan,
+
+                routes::collections::post_collection,
+                routes::collections::get_collections,
+                routes::collections::get_public_collections,
+                routes::collections::get_collection,
+                routes::collections::get_collection_recipes,
+                routes::collections::update_collection,
+                routes::collections::delete_collection,
+                routes::collections::add_collection_recipe,
+                routes::collections::remove_collection_recipe,
+                routes::collections::reorder_collection,
+
+                routes::imports::import_jsonld,
+                routes::imports::import_markdown,
+
+                routes::catalog::export_catalog_archive,
+
+                routes::trash::get_trash,
+                routes::trash::restore_from_trash,
+
+                routes::translations::get_recipe_translations,
+                routes::translations::get_missing_translations,
+
+                routes::cooking::post_cooking_session,
+                routes::cooking::get_cooking_session,
+                routes::cooking::put_cooking_step,
+                routes::cooking::post_cooking_timer,
+                routes::cooking::delete_cooking_timer,
+                routes::cooking::delete_cooking_session
             ],
         )
         .mount(
@@ -0,0 +1,80 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use strum_macros::EnumString;
+
+/// Identifies catalog archives, stored in their manifest.
+pub const CATALOG_FORMAT: &str = "threesixfive-catalog";
+/// Version of the archive layout written by this server. Archives of a
+/// newer version are refused.
+pub const CATALOG_VERSION: u32 = 1;
+/// Largest archive `POST /catalog/import` takes, in bytes, unless the
+/// `catalog` limit is set in Rocket.toml.
+pub const CATALOG_LIMIT: u64 = 512 * 1024 * 1024;
+
+/// `manifest.json` of a catalog archive.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CatalogManifest {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub recipes: usize,
+    pub steps: usize,
+    pub images: usize,
+    /// Number of media files under `media/`
+    pub media: usize,
+}
+
+/// What to do with an archived recipe whose id is already taken.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy, FromFormField)]
+#[allow(non_camel_case_types)]
+pub enum ConflictPolicy {
+    /// Keep the stored recipe
+    skip,
+    /// Replace the stored recipe with the archived one
+    overwrite,
+    /// Import the archived recipe under a new id
+    duplicate,
+}
+
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum CatalogAction {
+    create,
+    skip,
+    overwrite,
+    duplicate,
+    /// Overwrite a recipe in the trash, taking it out of the trash
+    restore,
+}
+
+/// What happens, or would happen on a dry run, to one archived recipe.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CatalogImportItem {
+    /// Id of the recipe in the archive
+    pub source_id: String,
+    /// Id of the recipe after the import
+    pub target_id: String,
+    pub name: String,
+    pub action: CatalogAction,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CatalogImportReport {
+    pub dry_run: bool,
+    pub version: u32,
+    pub policy: ConflictPolicy,
+    pub recipes: Vec<CatalogImportItem>,
+    pub created: usize,
+    pub skipped: usize,
+    pub overwritten: usize,
+    pub duplicated: usize,
+    pub restored: usize,
+    /// Image and step records written for the imported recipes
+    pub images: usize,
+    pub steps: usize,
+    /// Media files written for the imported recipes
+    pub media: usize,
+    /// Media the imported recipes refer to that the archive lacks
+    pub missing_media: Vec<String>,
+}
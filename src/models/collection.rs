@@ -0,0 +1,114 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use strum_macros::EnumString;
+use crate::models::image::Image;
+use crate::models::ObjectConvertable;
+
+/// Who can see a collection. Unlisted collections can be opened by anyone
+/// with the link but are never listed.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum Visibility {
+    private,
+    unlisted,
+    public,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CollectionDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    pub user_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub cover_image: Option<Image>,
+    pub visibility: Visibility,
+    /// Recipes in the order the owner arranged them
+    pub recipe_ids: Vec<ObjectId>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl ObjectConvertable<Collection> for CollectionDocument {
+    fn to_object(&self) -> Collection {
+        Collection {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            user_id: self.user_id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            cover_image: self.cover_image.clone(),
+            visibility: self.visibility,
+            recipe_ids: self.recipe_ids.iter().map(|id| id.to_string()).collect(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct Collection {
+    pub _id: String,
+    pub user_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub cover_image: Option<Image>,
+    pub visibility: Visibility,
+    /// Recipes in the order the owner arranged them
+    pub recipe_ids: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Collection {
+    /// Whether `user_id` may open the collection.
+    pub fn is_visible_to(&self, user_id: Option<&str>) -> bool {
+        self.visibility != Visibility::private || user_id == Some(self.user_id.as_str())
+    }
+}
+
+/// A collection as shown on the recipes it contains.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+pub struct CollectionMembership {
+    pub _id: String,
+    pub name: String,
+    pub user_id: String,
+    pub visibility: Visibility,
+}
+
+impl From<&Collection> for CollectionMembership {
+    fn from(collection: &Collection) -> CollectionMembership {
+        CollectionMembership {
+            _id: collection._id.clone(),
+            name: collection.name.clone(),
+            user_id: collection.user_id.clone(),
+            visibility: collection.visibility,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CollectionInput {
+    pub name: String,
+    pub description: Option<String>,
+    /// Id of an uploaded image, such as one of the images of its recipes
+    pub cover_image_id: Option<String>,
+    /// Private by default
+    pub visibility: Option<Visibility>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CollectionRecipeInput {
+    pub recipe_id: String,
+    /// Zero-based position to insert at, the end by default. A recipe
+    /// already in the collection is moved there.
+    pub position: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CollectionOrderInput {
+    /// Every recipe of the collection, in the new order
+    pub recipe_ids: Vec<String>,
+}
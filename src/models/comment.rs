@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::ObjectConvertable;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommentDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    pub recipe_id: ObjectId,
+    /// Set when the comment is about a single step of the recipe
+    pub step_id: Option<ObjectId>,
+    /// Comment this one replies to
+    pub parent_id: Option<ObjectId>,
+    /// Top level comment of the thread, `None` for top level comments themselves
+    pub thread_id: Option<ObjectId>,
+    pub user_id: String,
+    pub text: String,
+    /// Deleted comments stay as tombstones so their replies keep their place
+    pub deleted: bool,
+    /// Set while the comment is in the trash, its text is kept until purged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl ObjectConvertable<Comment> for CommentDocument {
+    fn to_object(&self) -> Comment {
+        let deleted = self.deleted || self.deleted_at.is_some();
+        Comment {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            recipe_id: self.recipe_id.to_string(),
+            step_id: self.step_id.map(|id| id.to_string()),
+            parent_id: self.parent_id.map(|id| id.to_string()),
+            thread_id: self.thread_id.map(|id| id.to_string()),
+            user_id: self.user_id.clone(),
+            text: if deleted { String::new() } else { self.text.clone() },
+            deleted,
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct Comment {
+    pub _id: String,
+    pub recipe_id: String,
+    pub step_id: Option<String>,
+    pub parent_id: Option<String>,
+    pub thread_id: Option<String>,
+    pub user_id: String,
+    pub text: String,
+    pub deleted: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CommentInput {
+    pub text: String,
+    /// Step of the recipe the comment is about
+    pub step_id: Option<String>,
+    /// Comment being replied to
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CommentEditInput {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CommentThread {
+    pub comment: Comment,
+    pub replies: Vec<CommentThread>,
+}
+
+impl CommentThread {
+    /// Nests `replies` under their parents, keeping the order of `roots`
+    /// and sorting replies oldest first.
+    pub fn build(roots: Vec<Comment>, mut replies: Vec<CommentDocument>) -> Vec<CommentThread> {
+        replies.sort_by_key(|reply| reply.created_at);
+        let mut children: HashMap<String, Vec<Comment>> = HashMap::new();
+        for reply in replies {
+            if let Some(parent_id) = reply.parent_id {
+                children.entry(parent_id.to_string()).or_default().push(reply.to_object());
+            }
+        }
+        roots
+            .into_iter()
+            .map(|root| CommentThread::attach(root, &mut children))
+            .collect()
+    }
+
+    fn attach(comment: Comment, children: &mut HashMap<String, Vec<Comment>>) -> CommentThread {
+        let replies = children
+            .remove(&comment._id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|reply| CommentThread::attach(reply, children))
+            .collect();
+        CommentThread { comment, replies }
+    }
+}
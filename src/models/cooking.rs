@@ -0,0 +1,140 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use crate::models::gif::Gif;
+use crate::models::ObjectConvertable;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CookingTimerDocument {
+    pub id: String,
+    pub step_index: i32,
+    pub label: String,
+    pub duration_in_seconds: i32,
+    pub started_at: DateTime,
+    pub ends_at: DateTime,
+    /// Set once its expiry has been pushed
+    pub expired: bool,
+}
+
+impl ObjectConvertable<CookingTimer> for CookingTimerDocument {
+    fn to_object(&self) -> CookingTimer {
+        CookingTimer {
+            id: self.id.clone(),
+            step_index: self.step_index,
+            label: self.label.clone(),
+            duration_in_seconds: self.duration_in_seconds,
+            started_at: self.started_at.to_string(),
+            ends_at: self.ends_at.to_string(),
+            expired: self.expired,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+pub struct CookingTimer {
+    pub id: String,
+    /// The step it was started for
+    pub step_index: i32,
+    pub label: String,
+    pub duration_in_seconds: i32,
+    pub started_at: String,
+    pub ends_at: String,
+    pub expired: bool,
+}
+
+/// A user cooking a recipe, one step at a time.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CookingSessionDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    pub recipe_id: ObjectId,
+    pub user_id: String,
+    pub step_index: i32,
+    pub timers: Vec<CookingTimerDocument>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl ObjectConvertable<CookingSession> for CookingSessionDocument {
+    fn to_object(&self) -> CookingSession {
+        CookingSession {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            recipe_id: self.recipe_id.to_string(),
+            user_id: self.user_id.clone(),
+            step_index: self.step_index,
+            timers: self.timers.iter().map(|timer| timer.to_object()).collect(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CookingSession {
+    pub _id: String,
+    pub recipe_id: String,
+    pub user_id: String,
+    /// Index of the step on screen, from 0
+    pub step_index: i32,
+    pub timers: Vec<CookingTimer>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// The step on screen while cooking.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CookingStep {
+    pub index: i32,
+    pub step_count: i32,
+    pub description: String,
+    pub gif: Gif,
+    /// From the step or detected in its description, `None` if it has no timer
+    pub duration_in_seconds: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CookingSessionResponse {
+    pub session: CookingSession,
+    pub step: CookingStep,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CookingSessionInput {
+    pub recipe_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CookingStepInput {
+    pub step_index: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CookingTimerInput {
+    /// The current step if not given
+    pub step_index: Option<i32>,
+    /// The duration of the step if not given
+    pub duration_in_seconds: Option<i32>,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum CookingEventKind {
+    step_changed,
+    timer_started,
+    timer_cancelled,
+    timer_expired,
+}
+
+/// Pushed to the devices following a cooking session.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct CookingEvent {
+    pub kind: CookingEventKind,
+    pub session_id: String,
+    pub user_id: String,
+    pub step_index: i32,
+    pub timer: Option<CookingTimer>,
+    pub created_at: String,
+}
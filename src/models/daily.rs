@@ -0,0 +1,43 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::recipe::Recipe;
+use crate::models::ObjectConvertable;
+
+/// Recipe picked for a day. Stored so the pick stays the same all day and
+/// later picks can avoid it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DailyRecipeDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    /// Day as `YYYY-MM-DD`, one pick per day
+    pub date: String,
+    pub recipe_id: ObjectId,
+    pub created_at: DateTime,
+}
+
+impl ObjectConvertable<DailyRecipePick> for DailyRecipeDocument {
+    fn to_object(&self) -> DailyRecipePick {
+        DailyRecipePick {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            date: self.date.clone(),
+            recipe_id: self.recipe_id.to_string(),
+            created_at: self.created_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct DailyRecipePick {
+    pub _id: String,
+    pub date: String,
+    pub recipe_id: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct DailyRecipe {
+    pub date: String,
+    pub recipe: Recipe,
+}
@@ -0,0 +1,73 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+/// The 14 allergens that must be declared under EU food labelling rules.
+#[derive(Debug, Display, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum Allergen {
+    gluten,
+    crustaceans,
+    eggs,
+    fish,
+    peanuts,
+    soy,
+    milk,
+    tree_nuts,
+    celery,
+    mustard,
+    sesame,
+    sulphites,
+    lupin,
+    molluscs,
+}
+
+/// Where an ingredient comes from, which decides the diets it fits.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum IngredientOrigin {
+    plant,
+    dairy,
+    egg,
+    honey,
+    meat,
+    fish,
+    shellfish,
+}
+
+impl IngredientOrigin {
+    pub fn is_vegan(&self) -> bool {
+        *self == IngredientOrigin::plant
+    }
+
+    pub fn is_vegetarian(&self) -> bool {
+        !matches!(
+            self,
+            IngredientOrigin::meat | IngredientOrigin::fish | IngredientOrigin::shellfish
+        )
+    }
+}
+
+/// Allergens and diets derived from the ingredient list.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default, PartialEq)]
+pub struct DietaryInfo {
+    pub allergens: Vec<Allergen>,
+    pub vegan: bool,
+    pub vegetarian: bool,
+    pub gluten_free: bool,
+    /// Ingredients missing from the knowledge table. While there are any,
+    /// diets are only claimed when an editor overrides them.
+    pub unknown_ingredients: Vec<String>,
+}
+
+/// Corrections made by editors, applied on top of the inferred values.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default, PartialEq)]
+pub struct DietaryOverrides {
+    #[serde(default)]
+    pub add_allergens: Vec<Allergen>,
+    #[serde(default)]
+    pub remove_allergens: Vec<Allergen>,
+    pub vegan: Option<bool>,
+    pub vegetarian: Option<bool>,
+    pub gluten_free: Option<bool>,
+}
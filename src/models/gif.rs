@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use mongodb::bson::DateTime;
 use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
@@ -11,6 +12,10 @@ pub struct RecipeStepDocument {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub _id: Option<ObjectId>,
     pub description: String,
+    #[serde(default)]
+    pub description_translations: BTreeMap<String, String>,
+    #[serde(default)]
+    pub duration_in_seconds: Option<i32>,
     pub gif: Gif,
     pub created_at: DateTime,
 }
@@ -19,7 +24,9 @@ impl ObjectConvertable<RecipeStep> for RecipeStepDocument {
     fn to_object(&self) -> RecipeStep {
         RecipeStep{
             _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
-            description: "".to_string(),
+            description: self.description.clone(),
+            description_translations: self.description_translations.clone(),
+            duration_in_seconds: self.duration_in_seconds,
             gif: Gif {
                 path: self.gif.path.clone(),
                 width: self.gif.width.clone(),
@@ -34,7 +41,15 @@ impl ObjectConvertable<RecipeStep> for RecipeStepDocument {
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct RecipeStep {
     pub _id: String,
+    /// In the default locale
     pub description: String,
+    /// The description in other locales, keyed by language tag
+    #[serde(default)]
+    pub description_translations: BTreeMap<String, String>,
+    /// How long the step takes, for its timer when cooking. Detected in the
+    /// description when not given
+    #[serde(default)]
+    pub duration_in_seconds: Option<i32>,
     pub gif: Gif,
     pub created_at: String,
 }
@@ -44,6 +59,8 @@ impl DocumentConvertable<RecipeStepDocument> for RecipeStep {
         RecipeStepDocument {
             _id: None,
             description: self.description.clone(),
+            description_translations: self.description_translations.clone(),
+            duration_in_seconds: self.duration_in_seconds,
             gif: Gif {
                 path: self.gif.path.clone(),
                 width: self.gif.width.clone(),
@@ -0,0 +1,17 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::recipe::Recipe;
+
+/// Largest document the import routes take, in bytes, unless the `import`
+/// limit is set in Rocket.toml.
+pub const IMPORT_LIMIT: u64 = 2 * 1024 * 1024;
+
+/// A recipe read from another format.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ImportedRecipe {
+    pub recipe: Recipe,
+    /// Fields of the source that were left out because the recipe has no
+    /// place for them or they couldn't be read, e.g. `keywords` or
+    /// `recipeIngredient: salt to taste`
+    pub unmapped: Vec<String>,
+}
@@ -0,0 +1,38 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::recipe::{Ingredient, IngredientUnit};
+
+/// Ingredient lines as pasted by an editor, one per line. Blank lines are skipped.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct IngredientParseRequest {
+    pub text: String,
+}
+
+/// One free-text line split into its parts.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ParsedIngredient {
+    pub input: String,
+    pub name: String,
+    /// `None` for lines without a quantity, e.g. "salt to taste"
+    pub amount: Option<f64>,
+    /// Upper end of a range like "2-3 cloves garlic"
+    pub amount_max: Option<f64>,
+    /// `None` for counted ingredients like "2 eggs"
+    pub unit: Option<IngredientUnit>,
+    /// Preparation and remarks, e.g. "sifted" or "about 2 cups"
+    pub notes: Option<String>,
+    /// The line as a recipe ingredient, when it has a quantity. Counted
+    /// ingredients use pieces, ranges the lower amount.
+    pub ingredient: Option<Ingredient>,
+}
+
+impl ParsedIngredient {
+    pub fn to_ingredient(&self) -> Option<Ingredient> {
+        Some(Ingredient {
+            name: self.name.clone(),
+            amount: self.amount?,
+            unit: self.unit.unwrap_or(IngredientUnit::piece),
+            name_translations: Default::default(),
+        })
+    }
+}
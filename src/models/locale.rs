@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The text of a recipe in the locales asked for, each field falling back
+/// to the next locale and then to the default one.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+pub struct LocalizedRecipe {
+    /// The locale the name was picked in
+    pub locale: String,
+    pub name: String,
+    /// In the order of the recipe's ingredients
+    pub ingredient_names: Vec<String>,
+    /// In the order of the recipe's steps
+    pub step_descriptions: Vec<String>,
+}
+
+/// What an editor still has to translate of a recipe.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+pub struct MissingTranslations {
+    pub recipe_id: String,
+    /// In the default locale
+    pub name: String,
+    pub locale: String,
+    /// Paths of the untranslated fields, like "name" or "steps[0].description"
+    pub missing: Vec<String>,
+}
@@ -0,0 +1,132 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use strum_macros::EnumString;
+use crate::models::dietary::Allergen;
+use crate::models::recipe::Nutrition;
+use crate::models::ObjectConvertable;
+
+/// Meals of a day, in the order they are eaten.
+#[derive(Debug, Display, PartialEq, Eq, PartialOrd, Ord, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy, FromFormField)]
+#[allow(non_camel_case_types)]
+pub enum MealSlot {
+    breakfast,
+    lunch,
+    dinner,
+    snack,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MealPlanEntryDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    pub user_id: String,
+    /// Day as `YYYY-MM-DD`, which sorts and compares like the date itself
+    pub date: String,
+    pub slot: MealSlot,
+    pub recipe_id: ObjectId,
+    /// Portions planned for the meal
+    pub servings: i32,
+    pub note: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl ObjectConvertable<MealPlanEntry> for MealPlanEntryDocument {
+    fn to_object(&self) -> MealPlanEntry {
+        MealPlanEntry {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            user_id: self.user_id.clone(),
+            date: self.date.clone(),
+            slot: self.slot,
+            recipe_id: self.recipe_id.to_string(),
+            servings: self.servings,
+            note: self.note.clone(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct MealPlanEntry {
+    pub _id: String,
+    pub user_id: String,
+    pub date: String,
+    pub slot: MealSlot,
+    pub recipe_id: String,
+    pub servings: i32,
+    pub note: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct MealPlanEntryInput {
+    /// Day as `YYYY-MM-DD`
+    pub date: String,
+    pub slot: MealSlot,
+    pub recipe_id: String,
+    /// Portions planned for the meal, one by default
+    pub servings: Option<i32>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct MealPlanDay {
+    pub date: String,
+    /// Entries of the day, by slot
+    pub entries: Vec<MealPlanEntry>,
+    /// Nutrition of the planned portions of every entry
+    pub nutrition: Nutrition,
+}
+
+/// The plan for a range of days, every day included even when empty.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct MealPlanRange {
+    pub from: String,
+    pub to: String,
+    pub days: Vec<MealPlanDay>,
+}
+
+/// Constraints for generating a plan. Nutrition targets are per day and
+/// person, every generated meal is one serving.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct MealPlanGenerateInput {
+    /// First day as `YYYY-MM-DD`
+    pub start: String,
+    /// Number of days, 7 by default
+    pub days: Option<i64>,
+    /// Meals to plan each day, breakfast, lunch and dinner by default
+    pub slots: Option<Vec<MealSlot>>,
+    pub calories: Option<i32>,
+    pub protein: Option<i32>,
+    pub carbs: Option<i32>,
+    pub fat: Option<i32>,
+    pub max_preparation_minutes: Option<i32>,
+    #[serde(default)]
+    pub vegan: bool,
+    #[serde(default)]
+    pub vegetarian: bool,
+    #[serde(default)]
+    pub gluten_free: bool,
+    #[serde(default)]
+    pub exclude_allergens: Vec<Allergen>,
+    /// Days before a recipe may be planned again, 7 by default
+    pub no_repeat_days: Option<i64>,
+    /// Same seed, same constraints and same recipes give the same plan.
+    /// Derived from the user and start date when missing.
+    pub seed: Option<u64>,
+    /// Adds the generated meals to the user's plan instead of only previewing them
+    #[serde(default)]
+    pub save: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct MealPlanGeneration {
+    pub seed: u64,
+    pub saved: bool,
+    pub plan: MealPlanRange,
+}
@@ -3,6 +3,24 @@ pub mod response;
 pub mod recipe;
 pub mod image;
 pub mod gif;
+pub mod review;
+pub mod shopping_list;
+pub mod comment;
+pub mod taxonomy;
+pub mod dietary;
+pub mod nutrition;
+pub mod ingredient_parser;
+pub mod meal_plan;
+pub mod daily;
+pub mod pantry;
+pub mod collection;
+pub mod import;
+pub mod catalog;
+pub mod print;
+pub mod publishing;
+pub mod trash;
+pub mod locale;
+pub mod cooking;
 
 
 pub trait DocumentConvertable<T> {
@@ -0,0 +1,30 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::recipe::{Ingredient, Nutrition};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct NutritionRequest {
+    pub ingredients: Vec<Ingredient>,
+    pub servings: Option<i32>,
+}
+
+/// Nutrition computed from the ingredient list and the food composition dataset.
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct NutritionReport {
+    pub total: Nutrition,
+    pub per_serving: Nutrition,
+    pub servings: i32,
+    pub ingredients: Vec<IngredientNutrition>,
+    /// Ingredients without a match in the dataset, or whose amount couldn't be
+    /// converted to grams, not included in the totals
+    pub unmatched_ingredients: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct IngredientNutrition {
+    pub ingredient: String,
+    /// Dataset entry the ingredient was matched to
+    pub food: String,
+    pub grams: f64,
+    pub nutrition: Nutrition,
+}
@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::recipe::Recipe;
+
+/// Most matches a pantry request returns.
+pub const MAX_PANTRY_MATCHES: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct PantryRequest {
+    /// Ingredients at hand, e.g. "scallions" or "eggs"
+    pub ingredients: Vec<String>,
+    /// Smallest fraction of a recipe's ingredients that must be at hand, 0 by default
+    pub min_coverage: Option<f64>,
+    /// 12 by default, at most 50
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct PantryMatch {
+    /// Fraction of the recipe's ingredients at hand, from 0 to 1
+    pub coverage: f64,
+    pub matched_ingredients: Vec<String>,
+    /// Ingredients still to buy
+    pub missing_ingredients: Vec<String>,
+    pub recipe: Recipe,
+}
@@ -0,0 +1,40 @@
+use rocket::request::FromParam;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use strum_macros::EnumString;
+
+/// Paper to print on. Index cards are 6 by 4 inches, landscape.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy, FromFormField)]
+#[allow(non_camel_case_types)]
+pub enum PageSize {
+    a4,
+    letter,
+    index_card,
+}
+
+impl PageSize {
+    /// Width and height in points.
+    pub fn dimensions(&self) -> (f64, f64) {
+        match self {
+            PageSize::a4 => (595.28, 841.89),
+            PageSize::letter => (612.0, 792.0),
+            PageSize::index_card => (432.0, 288.0),
+        }
+    }
+}
+
+/// A path segment naming a PDF, such as the `<id>.pdf` of
+/// `/recipe/<id>.pdf`. Other segments forward to the next route.
+pub struct PdfName(pub String);
+
+impl<'a> FromParam<'a> for PdfName {
+    type Error = &'a str;
+
+    fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+        match param.strip_suffix(".pdf") {
+            Some(name) if !name.is_empty() => Ok(PdfName(name.to_string())),
+            _ => Err(param),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::recipe::RecipeStatus;
+use crate::models::ObjectConvertable;
+
+/// An entry of the audit trail of a recipe's status.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatusChangeDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    pub recipe_id: ObjectId,
+    pub from: RecipeStatus,
+    pub to: RecipeStatus,
+    /// Who made the change, if the `x-user-id` header was sent
+    pub user_id: Option<String>,
+    pub admin: bool,
+    /// Made by the scheduler when `publish_at` came
+    pub scheduled: bool,
+    /// Set when the change only scheduled publishing for that time
+    #[serde(default)]
+    pub publish_at: Option<DateTime>,
+    pub note: String,
+    pub created_at: DateTime,
+}
+
+impl ObjectConvertable<StatusChange> for StatusChangeDocument {
+    fn to_object(&self) -> StatusChange {
+        StatusChange {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            recipe_id: self.recipe_id.to_string(),
+            from: self.from,
+            to: self.to,
+            user_id: self.user_id.clone(),
+            admin: self.admin,
+            scheduled: self.scheduled,
+            publish_at: self.publish_at.map(|publish_at| publish_at.to_string()),
+            note: self.note.clone(),
+            created_at: self.created_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct StatusChange {
+    pub _id: String,
+    pub recipe_id: String,
+    pub from: RecipeStatus,
+    pub to: RecipeStatus,
+    pub user_id: Option<String>,
+    /// Made with the admin key
+    pub admin: bool,
+    pub scheduled: bool,
+    /// When the recipe is due to be published, for changes that scheduled it
+    pub publish_at: Option<String>,
+    pub note: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct StatusChangeInput {
+    pub status: RecipeStatus,
+    /// RFC 3339 time to publish a recipe in review at instead of now
+    pub publish_at: Option<String>,
+    /// Why, kept in the audit trail
+    pub note: Option<String>,
+}
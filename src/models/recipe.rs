@@ -1,11 +1,15 @@
+use std::collections::BTreeMap;
 use mongodb::bson::oid::ObjectId;
 use mongodb::bson::DateTime;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 use strum_macros::EnumString;
+use crate::models::collection::CollectionMembership;
 use crate::models::image::Image;
 use crate::models::gif::RecipeStep;
+use crate::models::dietary::{DietaryInfo, DietaryOverrides};
+use crate::models::locale::LocalizedRecipe;
 use crate::models::{DocumentConvertable, ObjectConvertable};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,13 +17,41 @@ pub struct RecipeDocument {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub _id: Option<ObjectId>,
     pub name: String,
+    #[serde(default)]
+    pub name_translations: BTreeMap<String, String>,
     pub images: Vec<Image>,
     pub preparation_time_in_minutes: i32,
+    /// Number of servings the ingredients and nutrition are for
+    #[serde(default = "default_servings")]
+    pub servings: i32,
+    /// Nutrition of the whole recipe
     pub nutrition: Nutrition,
     pub num_of_likes: i32,
     pub num_of_views: i32,
+    /// Average star rating over visible reviews
+    #[serde(default)]
+    pub rating_average: f64,
+    /// Number of visible reviews
+    #[serde(default)]
+    pub rating_count: i32,
     pub ingredients: Vec<Ingredient>,
     pub steps: Vec<RecipeStep>,
+    /// Tags, cuisines, meal types and diets assigned to the recipe
+    #[serde(default)]
+    pub term_ids: Vec<ObjectId>,
+    /// Derived from the ingredients whenever the recipe is written
+    #[serde(default)]
+    pub dietary: DietaryInfo,
+    #[serde(default)]
+    pub dietary_overrides: DietaryOverrides,
+    /// The recipe this one was forked from
+    #[serde(default)]
+    pub forked_from: Option<ObjectId>,
+    #[serde(default = "default_status")]
+    pub status: RecipeStatus,
+    /// When the recipe in review is due to be published
+    #[serde(default)]
+    pub publish_at: Option<DateTime>,
     pub created_at: DateTime,
 }
 
@@ -28,8 +60,10 @@ impl ObjectConvertable<Recipe> for RecipeDocument{
         Recipe{
             _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
             name: self.name.clone(),
+            name_translations: self.name_translations.clone(),
             images: self.images.clone(),
             preparation_time_in_minutes: self.preparation_time_in_minutes,
+            servings: self.servings,
             nutrition: Nutrition {
                 calories: self.nutrition.calories,
                 fat: self.nutrition.fat,
@@ -37,10 +71,23 @@ impl ObjectConvertable<Recipe> for RecipeDocument{
                 fiber: self.nutrition.fiber,
                 protein: self.nutrition.protein,
             },
+            nutrition_per_serving: self.nutrition.per_serving(self.servings),
             num_of_likes: self.num_of_likes,
             num_of_views: self.num_of_views,
+            rating_average: self.rating_average,
+            rating_count: self.rating_count,
             ingredients: self.ingredients.clone(),
             steps: self.steps.clone(),
+            term_ids: self.term_ids.iter().map(|id| id.to_string()).collect(),
+            dietary: self.dietary.clone(),
+            dietary_overrides: self.dietary_overrides.clone(),
+            collections: vec![],
+            forked_from: self.forked_from.map(|id| id.to_string()),
+            localized: None,
+            status: self.status,
+            publish_at: self
+                .publish_at
+                .and_then(|publish_at| publish_at.try_to_rfc3339_string().ok()),
             created_at: self.created_at.to_string()
         }
     }
@@ -50,14 +97,60 @@ impl ObjectConvertable<Recipe> for RecipeDocument{
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct Recipe {
     pub _id: String,
+    /// In the default locale, `DEFAULT_LOCALE`
     pub name: String,
+    /// The name in other locales, keyed by language tag like "de" or "pt-BR"
+    #[serde(default)]
+    pub name_translations: BTreeMap<String, String>,
     pub images: Vec<Image>,
     pub preparation_time_in_minutes: i32,
+    /// Number of servings the ingredients and nutrition are for
+    #[serde(default = "default_servings")]
+    pub servings: i32,
+    /// Nutrition of the whole recipe
     pub nutrition: Nutrition,
+    /// Derived from `nutrition` and `servings`, ignored on input
+    #[serde(default)]
+    pub nutrition_per_serving: Nutrition,
     pub num_of_likes: i32,
     pub num_of_views: i32,
+    /// Average star rating over visible reviews, ignored on input
+    #[serde(default)]
+    pub rating_average: f64,
+    /// Number of visible reviews, ignored on input
+    #[serde(default)]
+    pub rating_count: i32,
     pub ingredients: Vec<Ingredient>,
     pub steps: Vec<RecipeStep>,
+    /// Tags, cuisines, meal types and diets assigned to the recipe
+    #[serde(default)]
+    pub term_ids: Vec<String>,
+    /// Derived from the ingredients whenever the recipe is written
+    #[serde(default)]
+    pub dietary: DietaryInfo,
+    #[serde(default)]
+    pub dietary_overrides: DietaryOverrides,
+    /// Collections holding the recipe that the caller can see, only filled
+    /// in by `GET /recipe/<id>` and ignored on input
+    #[serde(default)]
+    pub collections: Vec<CollectionMembership>,
+    /// The recipe this one was forked from. Only set by
+    /// `POST /recipe/<id>/fork` and kept on updates.
+    #[serde(default)]
+    pub forked_from: Option<String>,
+    /// The name, ingredients and steps in the locales asked for with
+    /// `Accept-Language` or `?lang=`, the fields above stay in the default
+    /// locale. Only set on output.
+    #[serde(default)]
+    pub localized: Option<LocalizedRecipe>,
+    /// Only published recipes are listed for regular keys. New recipes are
+    /// drafts and move on through `PUT /recipe/<id>/status`, which is also
+    /// the only way to change this and `publish_at`.
+    #[serde(default = "default_status")]
+    pub status: RecipeStatus,
+    /// RFC 3339 time the recipe in review is scheduled to be published at
+    #[serde(default)]
+    pub publish_at: Option<String>,
     pub created_at: String,
 }
 
@@ -67,8 +160,10 @@ impl DocumentConvertable<RecipeDocument> for Recipe{
         RecipeDocument {
             _id: None,
             name: self.name.clone(),
+            name_translations: self.name_translations.clone(),
             images: self.images.clone(),
             preparation_time_in_minutes: self.preparation_time_in_minutes,
+            servings: self.servings.max(1),
             nutrition: Nutrition {
                 calories: self.nutrition.calories,
                 fat: self.nutrition.fat,
@@ -78,14 +173,51 @@ impl DocumentConvertable<RecipeDocument> for Recipe{
             },
             num_of_likes: self.num_of_likes,
             num_of_views: self.num_of_views,
+            rating_average: self.rating_average,
+            rating_count: self.rating_count,
             ingredients: self.ingredients.clone(),
             steps: self.steps.clone(),
+            term_ids: self
+                .term_ids
+                .iter()
+                .filter_map(|id| ObjectId::parse_str(id).ok())
+                .collect(),
+            dietary: self.dietary.clone(),
+            dietary_overrides: self.dietary_overrides.clone(),
+            forked_from: self
+                .forked_from
+                .as_ref()
+                .and_then(|id| ObjectId::parse_str(id).ok()),
+            status: self.status,
+            publish_at: self
+                .publish_at
+                .as_ref()
+                .and_then(|publish_at| DateTime::parse_rfc3339_str(publish_at).ok()),
             created_at: DateTime::now()
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+fn default_servings() -> i32 {
+    1
+}
+
+/// Recipes stored before the publishing workflow were all live.
+fn default_status() -> RecipeStatus {
+    RecipeStatus::published
+}
+
+/// Where a recipe is in the editorial workflow.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy, FromFormField)]
+#[allow(non_camel_case_types)]
+pub enum RecipeStatus {
+    draft,
+    in_review,
+    published,
+    archived,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default, PartialEq)]
 pub struct Nutrition {
     pub calories: i32,
     pub fat: i32,
@@ -94,7 +226,33 @@ pub struct Nutrition {
     pub protein: i32,
 }
 
-#[derive(Debug, Display, PartialEq, EnumString, Serialize, Deserialize, JsonSchema, Clone)]
+impl Nutrition {
+    /// Multiplies every value by `factor`, rounding to whole units.
+    pub fn scaled(&self, factor: f64) -> Nutrition {
+        let scale = |value: i32| (value as f64 * factor).round() as i32;
+        Nutrition {
+            calories: scale(self.calories),
+            fat: scale(self.fat),
+            carbs: scale(self.carbs),
+            fiber: scale(self.fiber),
+            protein: scale(self.protein),
+        }
+    }
+
+    pub fn per_serving(&self, servings: i32) -> Nutrition {
+        self.scaled(1.0 / servings.max(1) as f64)
+    }
+
+    pub fn add(&mut self, other: &Nutrition) {
+        self.calories += other.calories;
+        self.fat += other.fat;
+        self.carbs += other.carbs;
+        self.fiber += other.fiber;
+        self.protein += other.protein;
+    }
+}
+
+#[derive(Debug, Display, PartialEq, Eq, Hash, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy, FromFormField)]
 #[allow(non_camel_case_types)]
 pub enum IngredientUnit{
     kg,
@@ -104,12 +262,39 @@ pub enum IngredientUnit{
     dcl,
     ml,
     tsp,
-    tbsp
+    tbsp,
+    cup,
+    fl_oz,
+    oz,
+    lb,
+    piece,
+    pinch,
+    clove
+}
+
+/// Measurement system recipes can be presented in.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy, FromFormField)]
+#[allow(non_camel_case_types)]
+pub enum UnitSystem {
+    metric,
+    imperial,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct Ingredient {
+    /// In the default locale
     pub name: String,
-    pub amount: i32,
+    /// Fractional amounts like 1.5 cups are allowed
+    pub amount: f64,
     pub unit: IngredientUnit,
-}
\ No newline at end of file
+    /// The name in other locales, keyed by language tag
+    #[serde(default)]
+    pub name_translations: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct SimilarRecipe {
+    /// From 0 to 1, higher is more similar
+    pub score: f64,
+    pub recipe: Recipe,
+}
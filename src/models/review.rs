@@ -0,0 +1,87 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::models::ObjectConvertable;
+
+pub const MIN_RATING: i32 = 1;
+pub const MAX_RATING: i32 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    pub recipe_id: ObjectId,
+    pub user_id: String,
+    pub rating: i32,
+    pub text: String,
+    pub flags: Vec<ReviewFlag>,
+    /// Hidden reviews are excluded from listings and from the recipe's rating.
+    pub hidden: bool,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl ObjectConvertable<Review> for ReviewDocument {
+    fn to_object(&self) -> Review {
+        Review {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            recipe_id: self.recipe_id.to_string(),
+            user_id: self.user_id.clone(),
+            rating: self.rating,
+            text: self.text.clone(),
+            flags: self.flags.clone(),
+            hidden: self.hidden,
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct Review {
+    pub _id: String,
+    pub recipe_id: String,
+    pub user_id: String,
+    pub rating: i32,
+    pub text: String,
+    pub flags: Vec<ReviewFlag>,
+    pub hidden: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ReviewInput {
+    /// Star rating from 1 to 5
+    pub rating: i32,
+    pub text: String,
+}
+
+impl ReviewInput {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rating < MIN_RATING || self.rating > MAX_RATING {
+            return Err(format!(
+                "Rating must be between {} and {}.",
+                MIN_RATING, MAX_RATING
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ReviewFlag {
+    pub user_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ReviewFlagInput {
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ReviewModerationInput {
+    pub hidden: bool,
+}
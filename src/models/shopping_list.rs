@@ -0,0 +1,133 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use strum_macros::EnumString;
+use crate::models::recipe::IngredientUnit;
+use crate::models::ObjectConvertable;
+
+/// Store aisles, in the order a list is presented.
+#[derive(Debug, Display, PartialEq, Eq, PartialOrd, Ord, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum Aisle {
+    produce,
+    bakery,
+    meat,
+    seafood,
+    dairy,
+    frozen,
+    baking,
+    pantry,
+    spices,
+    beverages,
+    other,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ShoppingListItem {
+    /// Stable id used to check the item off
+    pub id: String,
+    pub name: String,
+    pub amount: f64,
+    pub unit: IngredientUnit,
+    pub aisle: Aisle,
+    pub checked: bool,
+    /// Recipes the item is needed for
+    pub recipe_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShoppingListRecipeDocument {
+    pub recipe_id: ObjectId,
+    pub servings: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShoppingListDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    pub user_id: String,
+    pub name: String,
+    pub recipes: Vec<ShoppingListRecipeDocument>,
+    pub items: Vec<ShoppingListItem>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl ObjectConvertable<ShoppingList> for ShoppingListDocument {
+    fn to_object(&self) -> ShoppingList {
+        let mut items = self.items.clone();
+        items.sort_by(|a, b| a.aisle.cmp(&b.aisle).then(a.name.cmp(&b.name)));
+
+        let mut aisles: Vec<AisleGroup> = vec![];
+        for item in items {
+            match aisles.last_mut() {
+                Some(group) if group.aisle == item.aisle => group.items.push(item),
+                _ => aisles.push(AisleGroup {
+                    aisle: item.aisle,
+                    items: vec![item],
+                }),
+            }
+        }
+
+        ShoppingList {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            user_id: self.user_id.clone(),
+            name: self.name.clone(),
+            recipes: self
+                .recipes
+                .iter()
+                .map(|recipe| ShoppingListRecipe {
+                    recipe_id: recipe.recipe_id.to_string(),
+                    servings: recipe.servings,
+                })
+                .collect(),
+            aisles,
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ShoppingListRecipe {
+    pub recipe_id: String,
+    pub servings: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct AisleGroup {
+    pub aisle: Aisle,
+    pub items: Vec<ShoppingListItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ShoppingList {
+    pub _id: String,
+    pub user_id: String,
+    pub name: String,
+    pub recipes: Vec<ShoppingListRecipe>,
+    /// Items grouped by aisle, in store order
+    pub aisles: Vec<AisleGroup>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ShoppingListRecipeInput {
+    pub recipe_id: String,
+    /// Defaults to the recipe's own servings
+    pub servings: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ShoppingListInput {
+    pub name: Option<String>,
+    pub recipes: Vec<ShoppingListRecipeInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ShoppingListCheckInput {
+    pub checked: bool,
+}
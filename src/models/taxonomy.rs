@@ -0,0 +1,93 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use crate::models::recipe::Recipe;
+use crate::models::ObjectConvertable;
+
+#[derive(Debug, Display, PartialEq, Eq, Hash, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy, FromFormField)]
+#[allow(non_camel_case_types)]
+pub enum TermKind {
+    tag,
+    cuisine,
+    meal_type,
+    diet,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaxonomyTermDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _id: Option<ObjectId>,
+    pub kind: TermKind,
+    pub name: String,
+    /// Unique per kind, used in recipe filters
+    pub slug: String,
+    pub created_at: DateTime,
+}
+
+impl ObjectConvertable<TaxonomyTerm> for TaxonomyTermDocument {
+    fn to_object(&self) -> TaxonomyTerm {
+        TaxonomyTerm {
+            _id: self._id.clone().unwrap_or(ObjectId::new()).to_string(),
+            kind: self.kind,
+            name: self.name.clone(),
+            slug: self.slug.clone(),
+            created_at: self.created_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct TaxonomyTerm {
+    pub _id: String,
+    pub kind: TermKind,
+    pub name: String,
+    pub slug: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct TaxonomyTermInput {
+    pub kind: TermKind,
+    pub name: String,
+    /// Derived from `name` when missing
+    pub slug: Option<String>,
+}
+
+impl TaxonomyTermInput {
+    pub fn slug(&self) -> String {
+        slugify(self.slug.as_ref().unwrap_or(&self.name))
+    }
+}
+
+/// Lowercases and joins the alphanumeric runs of `text` with dashes,
+/// e.g. "Gluten Free!" becomes "gluten-free".
+pub fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct FacetCount {
+    pub term_id: String,
+    pub name: String,
+    pub slug: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct Facet {
+    pub kind: TermKind,
+    pub counts: Vec<FacetCount>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct RecipeSearchResponse {
+    pub recipes: Vec<Recipe>,
+    /// Number of matching recipes per term, grouped by kind
+    pub facets: Vec<Facet>,
+}
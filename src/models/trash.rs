@@ -0,0 +1,56 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+/// The kinds of resources that go to the trash when deleted, named like the
+/// first segment of their paths.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Serialize, Deserialize, JsonSchema, Clone, Copy, FromFormField)]
+#[allow(non_camel_case_types)]
+pub enum TrashKind {
+    recipe,
+    image,
+    /// A recipe step with its GIF
+    gif,
+    collection,
+    #[serde(rename = "shopping-list")]
+    #[strum(serialize = "shopping-list")]
+    #[field(value = "shopping-list")]
+    shopping_list,
+    /// A meal plan entry
+    #[serde(rename = "meal-plan")]
+    #[strum(serialize = "meal-plan")]
+    #[field(value = "meal-plan")]
+    meal_plan,
+    review,
+    /// A taxonomy term
+    taxonomy,
+    comment,
+    /// A recipe of the day pick
+    daily,
+}
+
+pub const TRASH_KINDS: [TrashKind; 10] = [
+    TrashKind::recipe,
+    TrashKind::image,
+    TrashKind::gif,
+    TrashKind::collection,
+    TrashKind::shopping_list,
+    TrashKind::meal_plan,
+    TrashKind::review,
+    TrashKind::taxonomy,
+    TrashKind::comment,
+    TrashKind::daily,
+];
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct TrashItem {
+    pub kind: TrashKind,
+    pub _id: String,
+    /// The name, title or text of the resource
+    pub label: String,
+    /// Owner of the resource, for those belonging to a user
+    pub user_id: Option<String>,
+    pub deleted_at: String,
+    /// When it will be purged for good
+    pub purge_at: String,
+}
@@ -0,0 +1,71 @@
+use crate::services::locale;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+
+/// How the client asked a resource to be represented, from the preferred
+/// type of the `Accept` header. Anything unknown gets plain JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Representation {
+    Json,
+    JsonLd,
+    Html,
+    Markdown,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Representation {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let preferred = req.accept().map(|accept| accept.preferred().media_type());
+        let representation = match preferred {
+            Some(media) if media.top() == "application" && media.sub() == "ld+json" => {
+                Representation::JsonLd
+            }
+            Some(media) if media.top() == "text" && media.sub() == "html" => Representation::Html,
+            Some(media) if media.top() == "text" && media.sub() == "markdown" => {
+                Representation::Markdown
+            }
+            _ => Representation::Json,
+        };
+        Outcome::Success(representation)
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for Representation {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
+
+/// Language tags from the `Accept-Language` header, most preferred first.
+/// Empty when the header is missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcceptLanguage(pub Vec<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AcceptLanguage {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let header = req.headers().get_one("Accept-Language").unwrap_or_default();
+        Outcome::Success(AcceptLanguage(locale::parse_accept_language(header)))
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for AcceptLanguage {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
@@ -77,3 +77,59 @@ impl<'a> OpenApiFromRequest<'a> for ApiKey {
         })
     }
 }
+
+/// Key for moderation and other administrative endpoints.
+pub struct AdminKey;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminKey {
+    type Error = ApiKeyError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        fn is_valid(key: &str) -> bool {
+            let admin_key = env::var("ADMIN_API_KEY").expect("env.ADMIN_API_KEY is not found.");
+            return key == admin_key;
+        }
+
+        match req.headers().get_one("x-admin-key") {
+            None => Outcome::Failure((Status::Unauthorized, ApiKeyError::Missing)),
+            Some(key) if is_valid(key) => Outcome::Success(AdminKey),
+            Some(_) => Outcome::Failure((Status::Unauthorized, ApiKeyError::Invalid)),
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for AdminKey {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        let security_scheme = SecurityScheme {
+            description: Some("Requires an admin API key to access".to_owned()),
+
+            data: SecuritySchemeData::ApiKey {
+                name: "x-admin-key".to_owned(),
+                location: "header".to_owned(),
+            },
+            extensions: Object::default(),
+        };
+        let mut security_req = SecurityRequirement::new();
+        security_req.insert("AdminKey".to_owned(), Vec::new());
+        Ok(RequestHeaderInput::Security(
+            "AdminKey".to_owned(),
+            security_scheme,
+            security_req,
+        ))
+    }
+
+    fn get_responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        use rocket_okapi::okapi::openapi3::RefOr;
+        Ok(Responses {
+            responses: okapi::map! {
+                "401".to_owned() => RefOr::Object(unauthorized_response(gen)),
+            },
+            ..Default::default()
+        })
+    }
+}
@@ -1 +1,3 @@
+pub mod accept;
 pub mod basic;
+pub mod user;
@@ -0,0 +1,73 @@
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket_okapi::okapi;
+use rocket_okapi::okapi::openapi3::{
+    Object, Responses, SecurityRequirement, SecurityScheme, SecuritySchemeData,
+};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+
+use crate::errors::response::unauthorized_response;
+
+/// Identity of the end user on whose behalf the client is calling.
+/// The API key authenticates the client app, this identifies the user.
+///
+/// The id is taken from the `x-user-id` header as it is: nothing checks
+/// it belongs to the caller. It is trusted-client identity, only as good as
+/// the apps holding the API key, which must authenticate their users and
+/// send their ids themselves. Don't hand the API key to end users.
+pub struct User(pub String);
+
+#[derive(Debug)]
+pub enum UserError {
+    Missing,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for User {
+    type Error = UserError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match req.headers().get_one("x-user-id") {
+            Some(id) if !id.trim().is_empty() => Outcome::Success(User(id.trim().to_owned())),
+            _ => Outcome::Failure((Status::Unauthorized, UserError::Missing)),
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for User {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        let security_scheme = SecurityScheme {
+            description: Some("Requires the id of the acting user".to_owned()),
+
+            data: SecuritySchemeData::ApiKey {
+                name: "x-user-id".to_owned(),
+                location: "header".to_owned(),
+            },
+            extensions: Object::default(),
+        };
+        let mut security_req = SecurityRequirement::new();
+        security_req.insert("User".to_owned(), Vec::new());
+        Ok(RequestHeaderInput::Security(
+            "User".to_owned(),
+            security_scheme,
+            security_req,
+        ))
+    }
+
+    fn get_responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        use rocket_okapi::okapi::openapi3::RefOr;
+        Ok(Responses {
+            responses: okapi::map! {
+                "401".to_owned() => RefOr::Object(unauthorized_response(gen)),
+            },
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use rocket::data::{Data, Limits, ToByteUnit};
+use rocket::http::{ContentType, Status};
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use uuid::Uuid;
+
+use crate::db::{gif, image, parse_id, recipe, trash};
+use crate::errors::response::MyError;
+use crate::models::catalog::{CatalogAction, CatalogImportReport, ConflictPolicy, CATALOG_LIMIT};
+use crate::models::trash::TrashKind;
+use crate::request_guards::basic::AdminKey;
+use crate::routes::gifs::FileResponse;
+use crate::routes::recipes::check_terms_exist;
+use crate::services::catalog::{self, CatalogArchive};
+use crate::services::uploads;
+
+fn internal_error(details: String) -> MyError {
+    MyError::build(Status::InternalServerError.code, Some(details))
+}
+
+/// Reads the whole catalog, with every uploaded media file it refers to,
+/// into an archive. Media files that can't be read or lie outside the upload
+/// directory are left out and keep their path.
+pub async fn export_catalog(db: &Database) -> Result<Vec<u8>, MyError> {
+    let recipes = recipe::find_all_recipes(db)
+        .await
+        .map_err(|err| internal_error(err.details))?;
+    let steps = gif::find_all_recipe_steps(db)
+        .await
+        .map_err(|err| internal_error(err.details))?;
+    let images = image::find_all_images(db)
+        .await
+        .map_err(|err| internal_error(err.details))?;
+
+    let now = Utc::now();
+    let mut archive = CatalogArchive::new(recipes, steps, images, now.to_rfc3339());
+    for path in archive.media_paths() {
+        let file = match uploads::resolve(&path) {
+            Some(file) => file,
+            None => {
+                println!("Leaving {} out of the catalog: not an upload", path);
+                continue;
+            }
+        };
+        match rocket::tokio::fs::read(&file).await {
+            Ok(data) => archive.add_media(&path, data),
+            Err(_error) => println!("Leaving {} out of the catalog: {:?}", path, _error),
+        }
+    }
+    archive.to_bytes(now.timestamp()).map_err(internal_error)
+}
+
+fn read_archive(path: &Path) -> Result<CatalogArchive, String> {
+    let file = File::open(path).map_err(|err| format!("Reading {} failed: {}", path.display(), err))?;
+    CatalogArchive::read(BufReader::new(file))
+}
+
+fn extract_media(path: &Path, paths: &HashMap<String, PathBuf>) -> Result<(), String> {
+    let file = File::open(path).map_err(|err| format!("Reading {} failed: {}", path.display(), err))?;
+    catalog::extract_media(BufReader::new(file), paths)
+}
+
+/// Imports the catalog archive at `path`, reading it from disk as it goes.
+/// Recipes keep their ids where they are free and `policy` decides about
+/// the others. The images and steps of the imported recipes are stored
+/// again under new ids along with their media files, and those of the
+/// recipes overwritten are deleted. A `dry_run` only reports what would
+/// happen.
+pub async fn import_catalog(
+    db: &Database,
+    path: &Path,
+    policy: ConflictPolicy,
+    dry_run: bool,
+) -> Result<CatalogImportReport, MyError> {
+    let archive_path = path.to_path_buf();
+    let mut archive = rocket::tokio::task::spawn_blocking(move || read_archive(&archive_path))
+        .await
+        .map_err(|err| internal_error(err.to_string()))?
+        .map_err(|err| MyError::build(Status::UnprocessableEntity.code, Some(err)))?;
+
+    let source_ids: Vec<ObjectId> = archive
+        .recipes
+        .iter()
+        .filter_map(|recipe| parse_id(&recipe._id).ok())
+        .collect();
+    let existing: HashSet<String> = recipe::find_recipes(db, doc! { "_id": { "$in": source_ids.clone() } })
+        .await
+        .map_err(|err| internal_error(err.details))?
+        .into_iter()
+        .map(|recipe| recipe._id)
+        .collect();
+    let trashed: HashSet<String> = trash::find_trash(db, TrashKind::recipe)
+        .await
+        .map_err(|err| internal_error(err.details))?
+        .iter()
+        .filter_map(|document| document.get_object_id("_id").ok())
+        .filter(|id| source_ids.contains(id))
+        .map(|id| id.to_string())
+        .collect();
+    let items = catalog::plan_import(&archive.recipes, &existing, &trashed, policy);
+    let written: Vec<usize> = (0..items.len())
+        .filter(|index| items[*index].action != CatalogAction::skip)
+        .collect();
+    for index in written.iter() {
+        check_terms_exist(db, &archive.recipes[*index]).await?;
+    }
+
+    let referenced =
+        catalog::referenced_media(written.iter().map(|index| &archive.recipes[*index]));
+    let (present, missing): (Vec<String>, Vec<String>) = referenced
+        .into_iter()
+        .partition(|entry| archive.has_media(entry));
+    let mut report = catalog::summarize(items.clone(), archive.manifest.version, policy, dry_run);
+    report.media = present.len();
+    report.missing_media = missing;
+    if dry_run {
+        return Ok(report);
+    }
+
+    let mut files: HashMap<String, PathBuf> = HashMap::new();
+    for entry in present.iter() {
+        let file_name = entry.rsplit('/').next().unwrap_or("");
+        let file = uploads::upload_path(file_name).map_err(internal_error)?;
+        files.insert(entry.clone(), file);
+    }
+    let (archive_path, media_files) = (path.to_path_buf(), files.clone());
+    rocket::tokio::task::spawn_blocking(move || extract_media(&archive_path, &media_files))
+        .await
+        .map_err(|err| internal_error(err.to_string()))?
+        .map_err(internal_error)?;
+    let paths: HashMap<String, String> = files
+        .into_iter()
+        .map(|(entry, file)| (entry, file.to_str().unwrap_or_default().to_string()))
+        .collect();
+    archive.relink_media(&paths);
+
+    // The recipes about to be overwritten, to delete their image and step
+    // records once the new ones are stored.
+    let overwritten: Vec<ObjectId> = written
+        .iter()
+        .map(|index| &items[*index])
+        .filter(|item| item.action == CatalogAction::overwrite || item.action == CatalogAction::restore)
+        .filter_map(|item| parse_id(&item.target_id).ok())
+        .collect();
+    let previous = recipe::find_recipes_with_deleted(db, doc! { "_id": { "$in": overwritten } })
+        .await
+        .map_err(|err| internal_error(err.details))?;
+
+    // Image and step records of the written recipes, stored once each under
+    // a new id. Recipes carry a copy of the record for those the archive
+    // lacks; media without an id never had a record.
+    let mut ids: HashMap<String, String> = HashMap::new();
+    for index in written.iter() {
+        for embedded in archive.recipes[*index].images.iter() {
+            if embedded._id.is_empty() || ids.contains_key(&embedded._id) {
+                continue;
+            }
+            let record = archive
+                .images
+                .iter()
+                .find(|image| image._id == embedded._id)
+                .unwrap_or(embedded);
+            let image_id = image::insert_image(db, record.clone())
+                .await
+                .map(|result| result.inserted_id.as_object_id())
+                .map_err(|err| internal_error(err.details))?
+                .ok_or(internal_error("No Object ID found!".to_string()))?;
+            ids.insert(embedded._id.clone(), image_id.to_string());
+            report.images += 1;
+        }
+        for embedded in archive.recipes[*index].steps.iter() {
+            if embedded._id.is_empty() || ids.contains_key(&embedded._id) {
+                continue;
+            }
+            let record = archive
+                .steps
+                .iter()
+                .find(|step| step._id == embedded._id)
+                .unwrap_or(embedded);
+            let step_id = gif::insert_recipe_step(db, record.clone())
+                .await
+                .map(|result| result.inserted_id.as_object_id())
+                .map_err(|err| internal_error(err.details))?
+                .ok_or(internal_error("No Object ID found!".to_string()))?;
+            ids.insert(embedded._id.clone(), step_id.to_string());
+            report.steps += 1;
+        }
+    }
+
+    for index in written {
+        let item = &items[index];
+        let target_id = parse_id(&item.target_id).map_err(|err| internal_error(err.details))?;
+        let recipe = catalog::remap_recipe(archive.recipes[index].clone(), &item.target_id, &ids);
+        recipe::replace_recipe(db, target_id, recipe)
+            .await
+            .map_err(|err| internal_error(err.details))?;
+    }
+
+    let image_ids: Vec<ObjectId> = previous
+        .iter()
+        .flat_map(|recipe| recipe.images.iter())
+        .filter_map(|image| parse_id(&image._id).ok())
+        .collect();
+    let step_ids: Vec<ObjectId> = previous
+        .iter()
+        .flat_map(|recipe| recipe.steps.iter())
+        .filter_map(|step| parse_id(&step._id).ok())
+        .collect();
+    image::purge_images(db, image_ids)
+        .await
+        .map_err(|err| internal_error(err.details))?;
+    gif::purge_recipe_steps(db, step_ids)
+        .await
+        .map_err(|err| internal_error(err.details))?;
+    Ok(report)
+}
+
+/// The whole recipe catalog as a tar archive: `manifest.json`, then
+/// `recipes.ndjson`, `steps.ndjson` and `images.ndjson` with one record per
+/// line, then the media files under `media/`.
+#[openapi(tag = "Catalog")]
+#[get("/catalog/export")]
+pub async fn export_catalog_archive(
+    db: &State<Database>,
+    _key: AdminKey,
+) -> Result<FileResponse, MyError> {
+    let archive = export_catalog(&db).await?;
+    Ok(FileResponse((ContentType::new("application", "x-tar"), archive)))
+}
+
+/// Imports an archive from `GET /catalog/export`. Recipes whose id is
+/// taken, also by a recipe in the trash, are skipped unless `policy` is
+/// `overwrite` or `duplicate`. Overwriting a trashed recipe restores it.
+/// `dry_run` only reports what would be imported. The archive is written
+/// to a temporary file as it arrives, up to the `catalog` limit.
+#[post("/catalog/import?<policy>&<dry_run>", data = "<body>")]
+pub async fn import_catalog_archive(
+    db: &State<Database>,
+    body: Data<'_>,
+    limits: &Limits,
+    policy: Option<ConflictPolicy>,
+    dry_run: Option<bool>,
+    _key: AdminKey,
+) -> Result<Json<CatalogImportReport>, MyError> {
+    let path = std::env::temp_dir().join(format!("{}-catalog.tar", Uuid::new_v4()));
+    let limit = limits.get("catalog").unwrap_or(CATALOG_LIMIT.bytes());
+    let upload = body
+        .open(limit)
+        .into_file(&path)
+        .await
+        .map_err(|err| internal_error(format!("Receiving the archive failed: {}", err)));
+    let result = match upload {
+        Ok(file) if file.is_complete() => {
+            import_catalog(
+                &db,
+                &path,
+                policy.unwrap_or(ConflictPolicy::skip),
+                dry_run.unwrap_or(false),
+            )
+            .await
+        }
+        Ok(_) => Err(MyError::build(
+            Status::PayloadTooLarge.code,
+            Some(format!("The archive is larger than {}.", limit)),
+        )),
+        Err(err) => Err(err),
+    };
+    let _ = rocket::tokio::fs::remove_file(&path).await;
+    result.map(Json)
+}
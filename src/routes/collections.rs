@@ -0,0 +1,400 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, DateTime};
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::{collection, image, parse_id, recipe};
+use crate::errors::response::MyError;
+use crate::models::collection::{
+    Collection, CollectionDocument, CollectionInput, CollectionOrderInput, CollectionRecipeInput,
+    Visibility,
+};
+use crate::models::image::Image;
+use crate::models::recipe::Recipe;
+use crate::request_guards::accept::AcceptLanguage;
+use crate::request_guards::basic::{AdminKey, ApiKey};
+use crate::request_guards::user::User;
+use crate::routes::recipes::locale_chain;
+use crate::services::{collections, locale};
+
+fn parse_ids(ids: &[String]) -> Result<Vec<ObjectId>, MyError> {
+    ids.iter()
+        .map(|id| {
+            parse_id(id).map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))
+        })
+        .collect()
+}
+
+/// Loads a collection `user` may open. Private collections of other users
+/// are reported as missing.
+async fn find_visible_collection(
+    db: &Database,
+    id: &String,
+    user: Option<&User>,
+) -> Result<Collection, MyError> {
+    let id = parse_id(id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match collection::find_one_collection(db, id).await {
+        Ok(Some(found)) if found.is_visible_to(user.map(|user| user.0.as_str())) => Ok(found),
+        Ok(_) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Collection not found with _id {}", &id)),
+        )),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Collection not found with _id {}", &id)),
+            ))
+        }
+    }
+}
+
+/// Loads a collection and checks that it belongs to `user`.
+async fn find_own_collection(
+    db: &Database,
+    id: &String,
+    user: &User,
+) -> Result<Collection, MyError> {
+    let found = find_visible_collection(db, id, Some(user)).await?;
+    if found.user_id != user.0 {
+        return Err(MyError::build(
+            Status::Forbidden.code,
+            Some("This collection belongs to another user.".to_string()),
+        ));
+    }
+    Ok(found)
+}
+
+async fn find_cover_image(db: &Database, id: &Option<String>) -> Result<Option<Image>, MyError> {
+    let id = match id {
+        Some(id) => parse_id(id)
+            .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?,
+        None => return Ok(None),
+    };
+    match image::find_one_image(db, id).await {
+        Ok(Some(image)) => Ok(Some(image)),
+        _ => Err(MyError::build(
+            Status::BadRequest.code,
+            Some(format!("Image not found with _id {}", &id)),
+        )),
+    }
+}
+
+fn check_name(input: &CollectionInput) -> Result<String, MyError> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some("A collection needs a name.".to_string()),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+async fn reload(db: &Database, id: ObjectId) -> Result<Json<Collection>, MyError> {
+    match collection::find_one_collection(db, id).await {
+        Ok(Some(found)) => Ok(Json(found)),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Collection not found with _id {}", &id)),
+        )),
+    }
+}
+
+#[openapi(tag = "Collection")]
+#[post("/collections", data = "<input>")]
+pub async fn post_collection(
+    db: &State<Database>,
+    input: Json<CollectionInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Collection>, MyError> {
+    let input = input.into_inner();
+    let name = check_name(&input)?;
+    let cover_image = find_cover_image(&db, &input.cover_image_id).await?;
+
+    let document = CollectionDocument {
+        _id: None,
+        user_id: user.0,
+        name,
+        description: input.description,
+        cover_image,
+        visibility: input.visibility.unwrap_or(Visibility::private),
+        recipe_ids: vec![],
+        created_at: DateTime::now(),
+        updated_at: DateTime::now(),
+    };
+
+    let collection_id = collection::insert_collection(&db, document)
+        .await
+        .map(|result| result.inserted_id.as_object_id())
+        .map_err(|_err| {
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("Collection not added.".to_string()),
+            )
+        })?
+        .ok_or({
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("No Object ID found!".to_string()),
+            )
+        })?;
+
+    reload(&db, collection_id).await
+}
+
+/// The caller's own collections, whatever their visibility.
+#[openapi(tag = "Collection")]
+#[get("/collections?<limit>&<page>")]
+pub async fn get_collections(
+    db: &State<Database>,
+    limit: Option<i64>,
+    page: Option<i64>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Vec<Collection>>, MyError> {
+    // Setting default values
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+    match collection::find_user_collections(&db, &user.0, limit, page).await {
+        Ok(found) => Ok(Json(found)),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
+
+#[openapi(tag = "Collection")]
+#[get("/collections/public?<limit>&<page>")]
+pub async fn get_public_collections(
+    db: &State<Database>,
+    limit: Option<i64>,
+    page: Option<i64>,
+    _key: ApiKey,
+) -> Result<Json<Vec<Collection>>, MyError> {
+    // Setting default values
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+    match collection::find_public_collections(&db, limit, page).await {
+        Ok(found) => Ok(Json(found)),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
+
+/// Unlisted and public collections can be opened by anyone, private ones
+/// only by their owner.
+#[openapi(tag = "Collection")]
+#[get("/collection/<id>")]
+pub async fn get_collection(
+    db: &State<Database>,
+    id: String,
+    user: Option<User>,
+    _key: ApiKey,
+) -> Result<Json<Collection>, MyError> {
+    find_visible_collection(&db, &id, user.as_ref()).await.map(Json)
+}
+
+/// The recipes of the collection, in order. Recipes that aren't published
+/// are only listed with the `x-admin-key` header. `lang` and
+/// `Accept-Language` pick translations as for a single recipe.
+#[openapi(tag = "Collection")]
+#[get("/collection/<id>/recipes?<lang>")]
+pub async fn get_collection_recipes(
+    db: &State<Database>,
+    id: String,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    user: Option<User>,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<Vec<Recipe>>, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    let found = find_visible_collection(&db, &id, user.as_ref()).await?;
+    let ids = parse_ids(&found.recipe_ids)?;
+    let mut filter = doc! { "_id": { "$in": ids } };
+    if admin.is_none() {
+        filter = recipe::only_published(filter);
+    }
+    match recipe::find_recipes(&db, filter).await {
+        Ok(mut recipes) => {
+            recipes.sort_by_key(|recipe| {
+                found
+                    .recipe_ids
+                    .iter()
+                    .position(|id| *id == recipe._id)
+                    .unwrap_or(usize::MAX)
+            });
+            let default = locale::default_locale();
+            Ok(Json(
+                recipes
+                    .into_iter()
+                    .map(|recipe| locale::with_localized_text(recipe, &chain, &default))
+                    .collect(),
+            ))
+        }
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
+
+/// Renames the collection, changes its cover or its visibility.
+#[openapi(tag = "Collection")]
+#[put("/collection/<id>", data = "<input>")]
+pub async fn update_collection(
+    db: &State<Database>,
+    id: String,
+    input: Json<CollectionInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Collection>, MyError> {
+    let existing = find_own_collection(&db, &id, &user).await?;
+    let collection_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let input = input.into_inner();
+    let name = check_name(&input)?;
+    let cover_image = find_cover_image(&db, &input.cover_image_id).await?;
+
+    collection::update_collection_details(
+        &db,
+        collection_id,
+        name,
+        input.description,
+        cover_image,
+        input.visibility.unwrap_or(existing.visibility),
+    )
+    .await
+    .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    reload(&db, collection_id).await
+}
+
+#[openapi(tag = "Collection")]
+#[delete("/collection/<id>")]
+pub async fn delete_collection(
+    db: &State<Database>,
+    id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let existing = find_own_collection(&db, &id, &user).await?;
+    let collection_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    match collection::delete_one_collection(&db, collection_id).await {
+        Ok(res) if res.modified_count == 1 => Ok(Json("Collection successfully deleted!")),
+        Ok(_) => Err(MyError::build(
+            Status::NotFound.code,
+            Some("Not Found.".to_string()),
+        )),
+        Err(error) => {
+            println!("{:?}", error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some("Collection not deleted.".to_string()),
+            ))
+        }
+    }
+}
+
+/// Adds a recipe to the collection, or moves it when it is already there.
+#[openapi(tag = "Collection")]
+#[post("/collection/<id>/recipes", data = "<input>")]
+pub async fn add_collection_recipe(
+    db: &State<Database>,
+    id: String,
+    input: Json<CollectionRecipeInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Collection>, MyError> {
+    let existing = find_own_collection(&db, &id, &user).await?;
+    let collection_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let recipe_id = parse_id(&input.recipe_id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match recipe::find_one_recipe(&db, recipe_id).await {
+        Ok(Some(_)) => {}
+        _ => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Recipe not found with _id {}", &recipe_id)),
+            ))
+        }
+    }
+
+    let recipe_ids = collections::insert_at(
+        &parse_ids(&existing.recipe_ids)?,
+        recipe_id,
+        input.position,
+    );
+    collection::set_recipe_ids(&db, collection_id, recipe_ids)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    reload(&db, collection_id).await
+}
+
+#[openapi(tag = "Collection")]
+#[delete("/collection/<id>/recipes/<recipe_id>")]
+pub async fn remove_collection_recipe(
+    db: &State<Database>,
+    id: String,
+    recipe_id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Collection>, MyError> {
+    let existing = find_own_collection(&db, &id, &user).await?;
+    let collection_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    if !existing.recipe_ids.contains(&recipe_id) {
+        return Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe {} is not in the collection.", &recipe_id)),
+        ));
+    }
+
+    let remaining: Vec<String> = existing
+        .recipe_ids
+        .into_iter()
+        .filter(|id| *id != recipe_id)
+        .collect();
+    collection::set_recipe_ids(&db, collection_id, parse_ids(&remaining)?)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    reload(&db, collection_id).await
+}
+
+/// Rearranges the recipes. The order has to list every recipe of the
+/// collection exactly once.
+#[openapi(tag = "Collection")]
+#[put("/collection/<id>/order", data = "<input>")]
+pub async fn reorder_collection(
+    db: &State<Database>,
+    id: String,
+    input: Json<CollectionOrderInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Collection>, MyError> {
+    let existing = find_own_collection(&db, &id, &user).await?;
+    let collection_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    let ordered = collections::reorder(&existing.recipe_ids, &input.recipe_ids)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+    collection::set_recipe_ids(&db, collection_id, parse_ids(&ordered)?)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    reload(&db, collection_id).await
+}
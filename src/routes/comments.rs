@@ -0,0 +1,248 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::{comment, parse_id, recipe};
+use crate::errors::response::MyError;
+use crate::models::comment::{
+    Comment, CommentDocument, CommentEditInput, CommentInput, CommentThread,
+};
+use crate::request_guards::basic::ApiKey;
+use crate::request_guards::user::User;
+
+/// Loads a comment and checks that it was written by `user`.
+async fn find_own_comment(db: &Database, id: &String, user: &User) -> Result<Comment, MyError> {
+    let id = parse_id(id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match comment::find_one_comment(db, id).await {
+        Ok(Some(comment)) if comment.deleted => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Comment with _id {} was deleted.", &id)),
+        )),
+        Ok(Some(comment)) if comment.user_id == user.0 => Ok(comment),
+        Ok(Some(_)) => Err(MyError::build(
+            Status::Forbidden.code,
+            Some("Only the author can change a comment.".to_string()),
+        )),
+        Ok(None) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Comment not found with _id {}", &id)),
+        )),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Comment not found with _id {}", &id)),
+            ))
+        }
+    }
+}
+
+/// Comments on a recipe or, with `step_id`, on one of its steps.
+/// Replies are given as `parent_id` and inherit the step of their parent.
+#[openapi(tag = "Comment")]
+#[post("/recipe/<id>/comments", data = "<input>")]
+pub async fn post_comment(
+    db: &State<Database>,
+    id: String,
+    input: Json<CommentInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Comment>, MyError> {
+    let recipe_id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let input = input.into_inner();
+    if input.text.trim().is_empty() {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some("Comment text must not be empty.".to_string()),
+        ));
+    }
+
+    let recipe = match recipe::find_one_recipe(&db, recipe_id).await {
+        Ok(Some(recipe)) => recipe,
+        _ => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Recipe not found with _id {}", &recipe_id)),
+            ))
+        }
+    };
+
+    let mut step_id: Option<ObjectId> = None;
+    if let Some(id) = input.step_id.as_ref() {
+        if !recipe.steps.iter().any(|step| &step._id == id) {
+            return Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Recipe has no step with _id {}", id)),
+            ));
+        }
+        step_id = Some(
+            parse_id(id).map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?,
+        );
+    }
+
+    let mut parent_id: Option<ObjectId> = None;
+    let mut thread_id: Option<ObjectId> = None;
+    if let Some(id) = input.parent_id.as_ref() {
+        let id =
+            parse_id(id).map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+        let parent = match comment::find_one_comment(&db, id).await {
+            Ok(Some(parent)) if parent.recipe_id == recipe_id.to_string() && !parent.deleted => {
+                parent
+            }
+            _ => {
+                return Err(MyError::build(
+                    Status::BadRequest.code,
+                    Some(format!("Cannot reply to comment {}", &id)),
+                ))
+            }
+        };
+        step_id = parent.step_id.as_ref().and_then(|id| parse_id(id).ok());
+        parent_id = Some(id);
+        // Replies share the thread of their parent, top level comments start one.
+        thread_id = match parent.thread_id.as_ref() {
+            Some(thread) => parse_id(thread).ok(),
+            None => Some(id),
+        };
+    }
+
+    let document = CommentDocument {
+        _id: None,
+        recipe_id,
+        step_id,
+        parent_id,
+        thread_id,
+        user_id: user.0,
+        text: input.text,
+        deleted: false,
+        deleted_at: None,
+        created_at: DateTime::now(),
+        updated_at: DateTime::now(),
+    };
+
+    let comment_id = comment::insert_comment(&db, document)
+        .await
+        .map(|result| result.inserted_id.as_object_id())
+        .map_err(|_err| {
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("Comment not added.".to_string()),
+            )
+        })?
+        .ok_or({
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("No Object ID found!".to_string()),
+            )
+        })?;
+
+    match comment::find_one_comment(&db, comment_id).await {
+        Ok(Some(comment)) => Ok(Json(comment)),
+        _ => Err(MyError::build(
+            Status::InternalServerError.code,
+            Some("Comment not added.".to_string()),
+        )),
+    }
+}
+
+/// Paginates top level comments, newest first, each with its nested replies.
+#[openapi(tag = "Comment")]
+#[get("/recipe/<id>/comments?<step_id>&<limit>&<page>")]
+pub async fn get_recipe_comments(
+    db: &State<Database>,
+    id: String,
+    step_id: Option<String>,
+    limit: Option<i64>,
+    page: Option<i64>,
+    _key: ApiKey,
+) -> Result<Json<Vec<CommentThread>>, MyError> {
+    let recipe_id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let step_id = match step_id {
+        Some(step_id) => Some(
+            parse_id(&step_id)
+                .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?,
+        ),
+        None => None,
+    };
+    // Setting default values
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+
+    let roots = comment::find_root_comments(&db, recipe_id, step_id, limit, page)
+        .await
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let thread_ids = roots
+        .iter()
+        .filter_map(|root| parse_id(&root._id).ok())
+        .collect();
+    let replies = comment::find_thread_replies(&db, thread_ids)
+        .await
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    Ok(Json(CommentThread::build(roots, replies)))
+}
+
+#[openapi(tag = "Comment")]
+#[put("/comment/<id>", data = "<input>")]
+pub async fn update_comment(
+    db: &State<Database>,
+    id: String,
+    input: Json<CommentEditInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Comment>, MyError> {
+    let input = input.into_inner();
+    if input.text.trim().is_empty() {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some("Comment text must not be empty.".to_string()),
+        ));
+    }
+    let existing = find_own_comment(&db, &id, &user).await?;
+    let comment_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    comment::update_comment_text(&db, comment_id, input.text)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    match comment::find_one_comment(&db, comment_id).await {
+        Ok(Some(comment)) => Ok(Json(comment)),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Comment not found with _id {}", &comment_id)),
+        )),
+    }
+}
+
+/// Moves the comment to the trash. It stays in its thread as a tombstone so
+/// its replies remain visible.
+#[openapi(tag = "Comment")]
+#[delete("/comment/<id>")]
+pub async fn delete_comment(
+    db: &State<Database>,
+    id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let existing = find_own_comment(&db, &id, &user).await?;
+    let comment_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    match comment::delete_one_comment(&db, comment_id).await {
+        Ok(_) => Ok(Json("Comment successfully deleted!")),
+        Err(error) => {
+            println!("{:?}", error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Comment not found with _id {}", &comment_id)),
+            ))
+        }
+    }
+}
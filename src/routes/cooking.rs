@@ -0,0 +1,343 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::{Shutdown, State};
+use rocket_okapi::openapi;
+use uuid::Uuid;
+
+use crate::db::{cooking, parse_id, recipe};
+use crate::errors::response::MyError;
+use crate::models::cooking::{
+    CookingEvent, CookingEventKind, CookingSession, CookingSessionDocument, CookingSessionInput,
+    CookingSessionResponse, CookingStep, CookingStepInput, CookingTimer, CookingTimerInput,
+};
+use crate::models::recipe::Recipe;
+use crate::models::ObjectConvertable;
+use crate::request_guards::accept::AcceptLanguage;
+use crate::request_guards::basic::{AdminKey, ApiKey};
+use crate::request_guards::user::User;
+use crate::routes::recipes::locale_chain;
+use crate::services::cooking::{cooking_step, new_timer, step_duration, CookingChannel};
+use crate::services::locale;
+
+/// Loads a cooking session and checks that it belongs to `user`.
+async fn find_own_session(
+    db: &Database,
+    id: &String,
+    user: &User,
+) -> Result<(ObjectId, CookingSession), MyError> {
+    let id = parse_id(id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match cooking::find_one_cooking_session(db, id).await {
+        Ok(Some(session)) if session.user_id == user.0 => Ok((id, session)),
+        Ok(Some(_)) => Err(MyError::build(
+            Status::Forbidden.code,
+            Some("This cooking session belongs to another user.".to_string()),
+        )),
+        Ok(None) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Cooking session not found with _id {}", &id)),
+        )),
+        Err(err) => Err(MyError::build(Status::InternalServerError.code, Some(err.details))),
+    }
+}
+
+/// Loads the recipe to cook. Only published recipes can be cooked, unless
+/// with the `x-admin-key` header.
+async fn find_recipe(db: &Database, recipe_id: &String, admin: &Option<AdminKey>) -> Result<Recipe, MyError> {
+    let recipe_id = parse_id(recipe_id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let found = match admin {
+        Some(_) => recipe::find_one_recipe(db, recipe_id).await,
+        None => recipe::find_one_published_recipe(db, recipe_id).await,
+    };
+    match found {
+        Ok(Some(recipe)) => Ok(recipe),
+        Ok(None) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe not found with _id {}", &recipe_id)),
+        )),
+        Err(err) => Err(MyError::build(Status::InternalServerError.code, Some(err.details))),
+    }
+}
+
+/// The step at `index` in the locales of `chain`. Its duration is the one
+/// of the untranslated step.
+fn localized_step(recipe: &Recipe, index: i32, chain: &[String]) -> Result<CookingStep, MyError> {
+    let mut step = cooking_step(recipe, index).ok_or(MyError::build(
+        Status::BadRequest.code,
+        Some(format!("The recipe has no step {}.", index)),
+    ))?;
+    let localized = locale::localize_recipe(recipe.clone(), chain, &locale::default_locale());
+    step.description = localized.steps[index as usize].description.clone();
+    Ok(step)
+}
+
+fn event(kind: CookingEventKind, session: &CookingSession, step_index: i32, timer: Option<CookingTimer>) -> CookingEvent {
+    CookingEvent {
+        kind,
+        session_id: session._id.clone(),
+        user_id: session.user_id.clone(),
+        step_index,
+        timer,
+        created_at: DateTime::now().to_string(),
+    }
+}
+
+/// Starts cooking a published recipe, or resumes the session the user
+/// already has for it. Steps are translated as for `GET /recipe/<id>`.
+#[openapi(tag = "Cooking")]
+#[post("/cooking?<lang>", data = "<input>")]
+pub async fn post_cooking_session(
+    db: &State<Database>,
+    input: Json<CookingSessionInput>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    user: User,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<CookingSessionResponse>, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    let recipe = find_recipe(&db, &input.recipe_id, &admin).await?;
+    if recipe.steps.is_empty() {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some("The recipe has no steps to cook.".to_string()),
+        ));
+    }
+    let recipe_id = parse_id(&recipe._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    let existing = cooking::find_user_cooking_session(&db, &user.0, recipe_id)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+    let session = match existing {
+        Some(session) => session,
+        None => {
+            let document = CookingSessionDocument {
+                _id: None,
+                recipe_id,
+                user_id: user.0.clone(),
+                step_index: 0,
+                timers: vec![],
+                created_at: DateTime::now(),
+                updated_at: DateTime::now(),
+            };
+            let session_id = cooking::insert_cooking_session(&db, document)
+                .await
+                .map(|result| result.inserted_id.as_object_id())
+                .map_err(|_err| {
+                    MyError::build(
+                        Status::InternalServerError.code,
+                        Some("Cooking session not started.".to_string()),
+                    )
+                })?
+                .ok_or({
+                    MyError::build(
+                        Status::InternalServerError.code,
+                        Some("No Object ID found!".to_string()),
+                    )
+                })?;
+            find_own_session(&db, &session_id.to_string(), &user).await?.1
+        }
+    };
+
+    let index = session.step_index.min(recipe.steps.len() as i32 - 1);
+    let step = localized_step(&recipe, index, &chain)?;
+    Ok(Json(CookingSessionResponse { session, step }))
+}
+
+/// The session with the step on screen.
+#[openapi(tag = "Cooking")]
+#[get("/cooking/<id>?<lang>")]
+pub async fn get_cooking_session(
+    db: &State<Database>,
+    id: String,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    user: User,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<CookingSessionResponse>, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    let (_, session) = find_own_session(&db, &id, &user).await?;
+    let recipe = find_recipe(&db, &session.recipe_id, &admin).await?;
+    if recipe.steps.is_empty() {
+        return Err(MyError::build(
+            Status::Conflict.code,
+            Some("The recipe has no steps left to cook.".to_string()),
+        ));
+    }
+
+    let index = session.step_index.min(recipe.steps.len() as i32 - 1);
+    let step = localized_step(&recipe, index, &chain)?;
+    Ok(Json(CookingSessionResponse { session, step }))
+}
+
+/// Moves to another step, e.g. the next one. The devices following the
+/// session get a `step_changed` event.
+#[openapi(tag = "Cooking")]
+#[put("/cooking/<id>/step?<lang>", data = "<input>")]
+pub async fn put_cooking_step(
+    db: &State<Database>,
+    channel: &State<CookingChannel>,
+    id: String,
+    input: Json<CookingStepInput>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    user: User,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<CookingSessionResponse>, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    let (session_id, session) = find_own_session(&db, &id, &user).await?;
+    let recipe = find_recipe(&db, &session.recipe_id, &admin).await?;
+    let step = localized_step(&recipe, input.step_index, &chain)?;
+
+    match cooking::set_step_index(&db, session_id, input.step_index).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Cooking session not found with _id {}", &session_id)),
+            ))
+        }
+        Err(err) => return Err(MyError::build(Status::InternalServerError.code, Some(err.details))),
+    }
+    let (_, session) = find_own_session(&db, &id, &user).await?;
+    channel.send(event(CookingEventKind::step_changed, &session, step.index, None));
+    Ok(Json(CookingSessionResponse { session, step }))
+}
+
+/// Starts a timer, for the current step and as long as it takes unless
+/// told otherwise. A `timer_expired` event is pushed when it runs out.
+#[openapi(tag = "Cooking")]
+#[post("/cooking/<id>/timers", data = "<input>")]
+pub async fn post_cooking_timer(
+    db: &State<Database>,
+    channel: &State<CookingChannel>,
+    id: String,
+    input: Json<CookingTimerInput>,
+    user: User,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<CookingTimer>, MyError> {
+    let input = input.into_inner();
+    let (session_id, session) = find_own_session(&db, &id, &user).await?;
+    let recipe = find_recipe(&db, &session.recipe_id, &admin).await?;
+    let step_index = input.step_index.unwrap_or(session.step_index);
+    let step = usize::try_from(step_index)
+        .ok()
+        .and_then(|index| recipe.steps.get(index))
+        .ok_or(MyError::build(
+            Status::BadRequest.code,
+            Some(format!("The recipe has no step {}.", step_index)),
+        ))?;
+    let seconds = input
+        .duration_in_seconds
+        .or_else(|| step_duration(step))
+        .filter(|seconds| *seconds > 0)
+        .ok_or(MyError::build(
+            Status::BadRequest.code,
+            Some("The step has no duration, give one for the timer.".to_string()),
+        ))?;
+
+    let timer = new_timer(Uuid::new_v4().to_string(), step_index, input.label, seconds, DateTime::now());
+    match cooking::add_timer(&db, session_id, &timer).await {
+        Ok(true) => {
+            let timer = timer.to_object();
+            channel.send(event(CookingEventKind::timer_started, &session, step_index, Some(timer.clone())));
+            Ok(Json(timer))
+        }
+        Ok(false) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Cooking session not found with _id {}", &session_id)),
+        )),
+        Err(err) => Err(MyError::build(Status::InternalServerError.code, Some(err.details))),
+    }
+}
+
+#[openapi(tag = "Cooking")]
+#[delete("/cooking/<id>/timers/<timer_id>")]
+pub async fn delete_cooking_timer(
+    db: &State<Database>,
+    channel: &State<CookingChannel>,
+    id: String,
+    timer_id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<&'static str>, MyError> {
+    let (session_id, session) = find_own_session(&db, &id, &user).await?;
+    let timer = session.timers.iter().find(|timer| timer.id == timer_id).cloned();
+
+    match cooking::remove_timer(&db, session_id, &timer_id).await {
+        Ok(true) => {
+            let step_index = timer.as_ref().map_or(session.step_index, |timer| timer.step_index);
+            channel.send(event(CookingEventKind::timer_cancelled, &session, step_index, timer));
+            Ok(Json("Timer successfully cancelled!"))
+        }
+        Ok(false) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Timer not found with id {}", &timer_id)),
+        )),
+        Err(err) => Err(MyError::build(Status::InternalServerError.code, Some(err.details))),
+    }
+}
+
+/// Ends the session, with its timers.
+#[openapi(tag = "Cooking")]
+#[delete("/cooking/<id>")]
+pub async fn delete_cooking_session(
+    db: &State<Database>,
+    id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let (session_id, _) = find_own_session(&db, &id, &user).await?;
+
+    match cooking::delete_one_cooking_session(&db, session_id).await {
+        Ok(true) => Ok(Json("Cooking session successfully ended!")),
+        Ok(false) => Err(MyError::build(
+            Status::NotFound.code,
+            Some("Not Found.".to_string()),
+        )),
+        Err(err) => Err(MyError::build(Status::InternalServerError.code, Some(err.details))),
+    }
+}
+
+/// Server-sent events of the session: `step_changed`, `timer_started`,
+/// `timer_cancelled` and `timer_expired`, each with a `CookingEvent` as
+/// JSON data. The stream stays open until the client leaves.
+#[get("/cooking/<id>/events")]
+pub async fn get_cooking_events(
+    db: &State<Database>,
+    channel: &State<CookingChannel>,
+    id: String,
+    user: User,
+    _key: ApiKey,
+    mut shutdown: Shutdown,
+) -> Result<EventStream![], MyError> {
+    let (_, session) = find_own_session(&db, &id, &user).await?;
+    let mut receiver = channel.0.subscribe();
+
+    Ok(EventStream! {
+        loop {
+            let event = select! {
+                event = receiver.recv() => match event {
+                    Ok(event) => event,
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(_)) => continue,
+                },
+                _ = &mut shutdown => break,
+            };
+            if event.session_id == session._id {
+                yield Event::json(&event).event(event.kind.to_string());
+            }
+        }
+    })
+}
@@ -22,14 +22,29 @@ use crate::models::image::{Image, ImageFile};
 use crate::models::recipe::Recipe;
 use crate::models::gif::{Gif, RecipeStep};
 use crate::routes::gifs;
+use crate::services::uploads;
 
 pub struct FileResponse(pub (ContentType, Vec<u8>));
 
+/// Reads an uploaded media file, typed by its extension. Paths outside the
+/// upload directory are not found.
+pub async fn read_media(path: &str) -> Result<FileResponse, MyError> {
+    let not_found = || MyError::build(Status::NotFound.code, Some("Could not find the file.".to_string()));
+    let path = uploads::resolve(path).ok_or_else(not_found)?;
+    let data = rocket::tokio::fs::read(&path).await.map_err(|_err| not_found())?;
+    let content_type = path
+        .extension()
+        .and_then(|extension| ContentType::from_extension(&extension.to_string_lossy()))
+        .unwrap_or(ContentType::Binary);
+    Ok(FileResponse((content_type, data)))
+}
+
 
 #[derive(FromForm)]
 pub struct GifForm<'v> {
     pub title: String,
     pub description: String,
+    pub duration_in_seconds: Option<i32>,
     pub file:TempFile<'v>,
 }
 
@@ -48,7 +63,8 @@ pub async fn post_gif(
             ))?;
     match recipe::find_one_recipe(&db, id).await {
         Ok(Some(mut recipe)) => {
-            let temp_path = std::env::temp_dir().join(form.title.as_str());
+            let temp_path = uploads::upload_path(&form.title)
+                .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err)))?;
             form.file.persist_to(&temp_path).await.unwrap();
             let image_file = ImageFile::read(&temp_path).await;
             let mut gif = Gif {
@@ -61,6 +77,8 @@ pub async fn post_gif(
             let mut recipe_step = RecipeStep{
                 _id: "".to_string(),
                 description: form.description.clone(),
+                description_translations: Default::default(),
+                duration_in_seconds: form.duration_in_seconds.filter(|seconds| *seconds > 0),
                 gif,
                 created_at: DateTime::now().to_string()
             };
@@ -138,6 +156,24 @@ pub async fn get_gif(
     }
 }
 
+/// The GIF of a step of a published recipe. Needs no API key, so the URLs
+/// in exported recipes load in browsers and crawlers.
+#[openapi(tag = "GIF")]
+#[get("/media/gif/<id>")]
+pub async fn get_published_gif(db: &State<Database>, id: String) -> Result<FileResponse, MyError> {
+    let not_found = || MyError::build(Status::NotFound.code, Some("Could not find the GIF.".to_string()));
+    let recipe = recipe::find_published_recipe_with_media(&db, "steps", &id)
+        .await
+        .map_err(|_err| not_found())?
+        .ok_or_else(not_found)?;
+    let step = recipe
+        .steps
+        .into_iter()
+        .find(|step| step._id == id)
+        .ok_or_else(not_found)?;
+    read_media(&step.gif.path).await
+}
+
 #[openapi(tag = "GIF")]
 #[delete("/gif/<id>")]
 pub async fn delete_gif(
@@ -152,7 +188,7 @@ pub async fn delete_gif(
             )?;
     return match gif::delete_one_recipe_step(&db, id).await {
         Ok(res) => {
-            if res.deleted_count == 1 {
+            if res.modified_count == 1 {
                 Ok(Json("GIF successfully deleted!"))
             } else {
                 Err(MyError::build(
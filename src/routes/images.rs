@@ -22,7 +22,8 @@ use crate::models::image::{Image, ImageFile};
 use crate::models::recipe::Recipe;
 
 use uuid::Uuid;
-use crate::routes::gifs::FileResponse;
+use crate::routes::gifs::{read_media, FileResponse};
+use crate::services::uploads;
 
 #[derive(FromForm)]
 pub struct ImageForm<'v> {
@@ -47,7 +48,8 @@ pub async fn post_image(
 
     match recipe::find_one_recipe(&db, id).await {
         Ok(Some(mut recipe)) => {
-            let temp_path = std::env::temp_dir().join(form.title.as_str());
+            let temp_path = uploads::upload_path(&form.title)
+                .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err)))?;
             form.file.persist_to(&temp_path).await.unwrap();
             let image_file = ImageFile::read(&temp_path).await;
             let mut image = Image {
@@ -132,6 +134,24 @@ pub async fn get_image(
     }
 }
 
+/// An image of a published recipe. Needs no API key, so the URLs in
+/// exported recipes load in browsers and crawlers.
+#[openapi(tag = "Image")]
+#[get("/media/image/<id>")]
+pub async fn get_published_image(db: &State<Database>, id: String) -> Result<FileResponse, MyError> {
+    let not_found = || MyError::build(Status::NotFound.code, Some("Could not find the image.".to_string()));
+    let recipe = recipe::find_published_recipe_with_media(&db, "images", &id)
+        .await
+        .map_err(|_err| not_found())?
+        .ok_or_else(not_found)?;
+    let image = recipe
+        .images
+        .into_iter()
+        .find(|image| image._id == id)
+        .ok_or_else(not_found)?;
+    read_media(&image.path).await
+}
+
 #[openapi(tag = "Image")]
 #[delete("/image/<id>")]
 pub async fn delete_image(
@@ -143,7 +163,7 @@ pub async fn delete_image(
         parse_id(&id).map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
     return match image::delete_one_image(&db, id).await {
         Ok(res) => {
-            if res.deleted_count == 1 {
+            if res.modified_count == 1 {
                 Ok(Json("Image successfully deleted!"))
             } else {
                 Err(MyError::build(
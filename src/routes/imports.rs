@@ -0,0 +1,131 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use rocket::data::{Data, Limits, ToByteUnit};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::recipe;
+use crate::errors::response::MyError;
+use crate::models::import::{ImportedRecipe, IMPORT_LIMIT};
+use crate::request_guards::basic::ApiKey;
+use crate::services::{jsonld, markdown};
+
+fn not_imported() -> MyError {
+    MyError::build(
+        Status::InternalServerError.code,
+        Some("Recipe not imported.".to_string()),
+    )
+}
+
+/// Reads an imported document, up to the `import` limit.
+async fn read_body(body: Data<'_>, limits: &Limits) -> Result<String, MyError> {
+    let limit = limits.get("import").unwrap_or(IMPORT_LIMIT.bytes());
+    let text = body.open(limit).into_string().await.map_err(|err| {
+        MyError::build(Status::BadRequest.code, Some(format!("Reading the document failed: {}", err)))
+    })?;
+    if !text.is_complete() {
+        return Err(MyError::build(
+            Status::PayloadTooLarge.code,
+            Some(format!("The document is larger than {}.", limit)),
+        ));
+    }
+    Ok(text.into_inner())
+}
+
+/// Saves the imported recipes and returns them as stored. Either all of
+/// them are saved or, when one fails, none.
+async fn save_imported(db: &Database, imported: Vec<ImportedRecipe>) -> Result<Vec<ImportedRecipe>, MyError> {
+    let mut ids: Vec<ObjectId> = vec![];
+    let mut saved = vec![];
+    for item in imported {
+        let stored = match recipe::insert_recipe(db, item.recipe).await {
+            Ok(result) => match result.inserted_id.as_object_id() {
+                Some(recipe_id) => {
+                    ids.push(recipe_id);
+                    recipe::find_one_recipe(db, recipe_id).await.ok().flatten()
+                }
+                None => None,
+            },
+            Err(_error) => None,
+        };
+        match stored {
+            Some(recipe) => saved.push(ImportedRecipe {
+                recipe,
+                unmapped: item.unmapped,
+            }),
+            None => {
+                if let Err(_error) = recipe::purge_recipes(db, ids).await {
+                    println!("{:?}", _error);
+                }
+                return Err(not_imported());
+            }
+        }
+    }
+    Ok(saved)
+}
+
+/// Imports the schema.org `Recipe`s of a JSON-LD document, or of an HTML
+/// page embedding JSON-LD. Every recipe found is saved, along with the
+/// source fields that couldn't be mapped, or none if one can't be read or
+/// saved. `dry_run` only shows the result.
+#[openapi(tag = "Import")]
+#[post("/recipes/import/jsonld?<dry_run>", data = "<body>")]
+pub async fn import_jsonld(
+    db: &State<Database>,
+    body: Data<'_>,
+    limits: &Limits,
+    dry_run: Option<bool>,
+    _key: ApiKey,
+) -> Result<Json<Vec<ImportedRecipe>>, MyError> {
+    let body = read_body(body, limits).await?;
+    let documents = jsonld::parse_documents(&body)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+
+    let mut imported = vec![];
+    for document in documents.iter() {
+        for found in jsonld::find_recipes(document) {
+            imported.push(
+                jsonld::to_recipe(found)
+                    .map_err(|err| MyError::build(Status::UnprocessableEntity.code, Some(err)))?,
+            );
+        }
+    }
+    if imported.is_empty() {
+        return Err(MyError::build(
+            Status::UnprocessableEntity.code,
+            Some("The document has no schema.org Recipe.".to_string()),
+        ));
+    }
+    if dry_run.unwrap_or(false) {
+        return Ok(Json(imported));
+    }
+
+    save_imported(&db, imported).await.map(Json)
+}
+
+/// Imports a recipe written in the editorial Markdown format, which
+/// `GET /recipe/<id>` gives back with `Accept: text/markdown`. The recipe is
+/// saved along with what couldn't be read. `dry_run` only shows the result.
+#[openapi(tag = "Import")]
+#[post("/recipes/import/markdown?<dry_run>", data = "<body>")]
+pub async fn import_markdown(
+    db: &State<Database>,
+    body: Data<'_>,
+    limits: &Limits,
+    dry_run: Option<bool>,
+    _key: ApiKey,
+) -> Result<Json<ImportedRecipe>, MyError> {
+    let body = read_body(body, limits).await?;
+    let imported = markdown::to_recipe(&body)
+        .map_err(|err| MyError::build(Status::UnprocessableEntity.code, Some(err)))?;
+    if dry_run.unwrap_or(false) {
+        return Ok(Json(imported));
+    }
+    save_imported(&db, vec![imported])
+        .await?
+        .pop()
+        .map(Json)
+        .ok_or_else(not_imported)
+}
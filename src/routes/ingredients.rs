@@ -0,0 +1,18 @@
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+use crate::models::ingredient_parser::{IngredientParseRequest, ParsedIngredient};
+use crate::request_guards::basic::ApiKey;
+use crate::services::ingredient_parser;
+
+/// Turns a pasted ingredient list like "2 1/2 cups all-purpose flour, sifted"
+/// into structured ingredients, one per non-blank line. Lines without a
+/// quantity are returned without an `ingredient`.
+#[openapi(tag = "Ingredients")]
+#[post("/ingredients/parse", data = "<input>")]
+pub async fn parse_ingredients(
+    input: Json<IngredientParseRequest>,
+    _key: ApiKey,
+) -> Json<Vec<ParsedIngredient>> {
+    Json(ingredient_parser::parse_lines(&input.text))
+}
@@ -0,0 +1,454 @@
+use chrono::{Duration, NaiveDate};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::Database;
+use rocket::http::{ContentType, Status};
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::{meal_plan, parse_id, recipe, taxonomy};
+use crate::errors::response::MyError;
+use crate::models::meal_plan::{
+    MealPlanEntry, MealPlanEntryDocument, MealPlanEntryInput, MealPlanGenerateInput,
+    MealPlanGeneration, MealPlanRange, MealSlot,
+};
+use crate::models::print::{PageSize, PdfName};
+use crate::models::recipe::Recipe;
+use crate::models::taxonomy::TermKind;
+use crate::request_guards::basic::ApiKey;
+use crate::request_guards::user::User;
+use crate::routes::gifs::FileResponse;
+use crate::services::meal_plan as plan;
+use crate::services::meal_planner::{self as planner, Candidate, PlanConstraints};
+use crate::services::print;
+
+/// Loads an entry and checks that it belongs to `user`.
+async fn find_own_entry(db: &Database, id: &String, user: &User) -> Result<MealPlanEntry, MyError> {
+    let id = parse_id(id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match meal_plan::find_one_entry(db, id).await {
+        Ok(Some(entry)) if entry.user_id == user.0 => Ok(entry),
+        Ok(Some(_)) => Err(MyError::build(
+            Status::Forbidden.code,
+            Some("This meal plan entry belongs to another user.".to_string()),
+        )),
+        Ok(None) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Meal plan entry not found with _id {}", &id)),
+        )),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Meal plan entry not found with _id {}", &id)),
+            ))
+        }
+    }
+}
+
+/// Validates the input and returns the normalized date and the recipe id.
+async fn check_entry_input(
+    db: &Database,
+    input: &MealPlanEntryInput,
+) -> Result<(String, ObjectId), MyError> {
+    let date = plan::parse_date(&input.date)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+    let recipe_id = parse_id(&input.recipe_id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match recipe::find_one_recipe(db, recipe_id).await {
+        Ok(Some(_)) => Ok((plan::format_date(date), recipe_id)),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe not found with _id {}", &recipe_id)),
+        )),
+    }
+}
+
+/// Loads the user's entries between the dates along with their recipes.
+async fn load_range(
+    db: &Database,
+    user: &User,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<MealPlanRange, MyError> {
+    load_range_with_recipes(db, user, from, to)
+        .await
+        .map(|(range, _recipes)| range)
+}
+
+/// `load_range`, also returning the recipes the entries refer to.
+async fn load_range_with_recipes(
+    db: &Database,
+    user: &User,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<(MealPlanRange, Vec<Recipe>), MyError> {
+    let entries = meal_plan::find_entries(db, &user.0, &plan::format_date(from), &plan::format_date(to))
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    let mut recipe_ids: Vec<ObjectId> = vec![];
+    for entry in entries.iter() {
+        if let Ok(id) = parse_id(&entry.recipe_id) {
+            if !recipe_ids.contains(&id) {
+                recipe_ids.push(id);
+            }
+        }
+    }
+    let recipes = recipe::find_recipes(db, doc! { "_id": { "$in": recipe_ids } })
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    let range = plan::build_range(from, to, entries, &recipes);
+    Ok((range, recipes))
+}
+
+/// Plans a recipe for a meal of a day.
+#[openapi(tag = "Meal Plan")]
+#[post("/meal-plan", data = "<input>")]
+pub async fn post_meal_plan_entry(
+    db: &State<Database>,
+    input: Json<MealPlanEntryInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<MealPlanEntry>, MyError> {
+    let (date, recipe_id) = check_entry_input(&db, &input).await?;
+    let input = input.into_inner();
+    let document = MealPlanEntryDocument {
+        _id: None,
+        user_id: user.0,
+        date,
+        slot: input.slot,
+        recipe_id,
+        servings: input.servings.unwrap_or(1).max(1),
+        note: input.note,
+        created_at: DateTime::now(),
+        updated_at: DateTime::now(),
+    };
+
+    let entry_id = meal_plan::insert_entry(&db, document)
+        .await
+        .map(|result| result.inserted_id.as_object_id())
+        .map_err(|_err| {
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("Meal plan entry not added.".to_string()),
+            )
+        })?
+        .ok_or({
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("No Object ID found!".to_string()),
+            )
+        })?;
+
+    match meal_plan::find_one_entry(&db, entry_id).await {
+        Ok(Some(entry)) => Ok(Json(entry)),
+        _ => Err(MyError::build(
+            Status::InternalServerError.code,
+            Some("Meal plan entry not added.".to_string()),
+        )),
+    }
+}
+
+/// The plan for the week, Monday to Sunday, containing `date`.
+#[openapi(tag = "Meal Plan")]
+#[get("/meal-plan/week/<date>", rank = 2)]
+pub async fn get_meal_plan_week(
+    db: &State<Database>,
+    date: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<MealPlanRange>, MyError> {
+    let date = plan::parse_date(&date)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+    let (from, to) = plan::week_of(date);
+    load_range(&db, &user, from, to).await.map(Json)
+}
+
+/// `GET /meal-plan/week/<date>.pdf`, the week's plan to print, on A4 paper
+/// unless `size` says otherwise.
+#[get("/meal-plan/week/<file>?<size>", rank = 1)]
+pub async fn get_meal_plan_week_pdf(
+    db: &State<Database>,
+    file: PdfName,
+    size: Option<PageSize>,
+    user: User,
+    _key: ApiKey,
+) -> Result<FileResponse, MyError> {
+    let date = plan::parse_date(&file.0)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+    let (from, to) = plan::week_of(date);
+    let (range, recipes) = load_range_with_recipes(&db, &user, from, to).await?;
+    let pdf = print::meal_plan_pdf(&range, &recipes, size.unwrap_or(PageSize::a4));
+    Ok(FileResponse((ContentType::PDF, pdf)))
+}
+
+/// The plan for a month given as `YYYY-MM`.
+#[openapi(tag = "Meal Plan")]
+#[get("/meal-plan/month/<month>")]
+pub async fn get_meal_plan_month(
+    db: &State<Database>,
+    month: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<MealPlanRange>, MyError> {
+    let (from, to) = plan::month_of(&month)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+    load_range(&db, &user, from, to).await.map(Json)
+}
+
+#[openapi(tag = "Meal Plan")]
+#[put("/meal-plan/<id>", data = "<input>")]
+pub async fn update_meal_plan_entry(
+    db: &State<Database>,
+    id: String,
+    input: Json<MealPlanEntryInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<MealPlanEntry>, MyError> {
+    let existing = find_own_entry(&db, &id, &user).await?;
+    let entry_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let (date, recipe_id) = check_entry_input(&db, &input).await?;
+
+    let input = input.into_inner();
+    meal_plan::update_entry(
+        &db,
+        entry_id,
+        date,
+        input.slot,
+        recipe_id,
+        input.servings.unwrap_or(existing.servings).max(1),
+        input.note,
+    )
+    .await
+    .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    match meal_plan::find_one_entry(&db, entry_id).await {
+        Ok(Some(entry)) => Ok(Json(entry)),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Meal plan entry not found with _id {}", &entry_id)),
+        )),
+    }
+}
+
+#[openapi(tag = "Meal Plan")]
+#[delete("/meal-plan/<id>")]
+pub async fn delete_meal_plan_entry(
+    db: &State<Database>,
+    id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let existing = find_own_entry(&db, &id, &user).await?;
+    let entry_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    match meal_plan::delete_one_entry(&db, entry_id).await {
+        Ok(res) if res.modified_count == 1 => Ok(Json("Meal plan entry successfully deleted!")),
+        Ok(_) => Err(MyError::build(
+            Status::NotFound.code,
+            Some("Not Found.".to_string()),
+        )),
+        Err(error) => {
+            println!("{:?}", error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some("Meal plan entry not deleted.".to_string()),
+            ))
+        }
+    }
+}
+
+/// Copies every entry of the week containing `date` to the week containing
+/// `to`, by default the following week. Existing entries of the target week
+/// are kept. Returns the target week.
+#[openapi(tag = "Meal Plan")]
+#[post("/meal-plan/week/<date>/copy?<to>")]
+pub async fn copy_meal_plan_week(
+    db: &State<Database>,
+    date: String,
+    to: Option<String>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<MealPlanRange>, MyError> {
+    let date = plan::parse_date(&date)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+    let (from, until) = plan::week_of(date);
+    let target = match to {
+        Some(to) => plan::parse_date(&to)
+            .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?,
+        None => from + Duration::days(7),
+    };
+    let (target_from, target_until) = plan::week_of(target);
+    if target_from == from {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some("Cannot copy a week onto itself.".to_string()),
+        ));
+    }
+    let offset = (target_from - from).num_days();
+
+    let entries = meal_plan::find_entries(&db, &user.0, &plan::format_date(from), &plan::format_date(until))
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+    let mut copies = vec![];
+    for entry in entries {
+        let recipe_id = parse_id(&entry.recipe_id)
+            .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+        copies.push(MealPlanEntryDocument {
+            _id: None,
+            user_id: user.0.clone(),
+            date: plan::shift_date(&entry.date, offset)
+                .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err)))?,
+            slot: entry.slot,
+            recipe_id,
+            servings: entry.servings,
+            note: entry.note,
+            created_at: DateTime::now(),
+            updated_at: DateTime::now(),
+        });
+    }
+    meal_plan::insert_entries(&db, copies)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    load_range(&db, &user, target_from, target_until).await.map(Json)
+}
+
+/// Longest plan that can be generated at once.
+const MAX_GENERATED_DAYS: i64 = 31;
+
+/// Generates a plan from the recipe collection that keeps close to the
+/// daily nutrition targets and respects the preparation time, diet and
+/// no-repeat constraints. Recipes with a `meal_type` term whose slug is a
+/// slot name, e.g. `breakfast`, are only planned for that slot. The plan is
+/// only a preview unless `save` is set.
+#[openapi(tag = "Meal Plan")]
+#[post("/meal-plan/generate", data = "<input>")]
+pub async fn generate_meal_plan(
+    db: &State<Database>,
+    input: Json<MealPlanGenerateInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<MealPlanGeneration>, MyError> {
+    let input = input.into_inner();
+    let start = plan::parse_date(&input.start)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+    let days = input.days.unwrap_or(7);
+    if !(1..=MAX_GENERATED_DAYS).contains(&days) {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some(format!("Days must be between 1 and {}.", MAX_GENERATED_DAYS)),
+        ));
+    }
+    let mut slots = input
+        .slots
+        .clone()
+        .unwrap_or(vec![MealSlot::breakfast, MealSlot::lunch, MealSlot::dinner]);
+    slots.sort();
+    slots.dedup();
+    let end = start + Duration::days(days - 1);
+    let constraints = PlanConstraints {
+        calories: input.calories,
+        protein: input.protein,
+        carbs: input.carbs,
+        fat: input.fat,
+        max_preparation_minutes: input.max_preparation_minutes,
+        vegan: input.vegan,
+        vegetarian: input.vegetarian,
+        gluten_free: input.gluten_free,
+        exclude_allergens: input.exclude_allergens.clone(),
+        no_repeat_days: input.no_repeat_days.unwrap_or(7).max(0),
+    };
+    let seed = input
+        .seed
+        .unwrap_or(planner::seed_from(&format!("{}:{}", user.0, plan::format_date(start))));
+
+    let slot_terms: Vec<(String, MealSlot)> = taxonomy::find_terms(&db, Some(TermKind::meal_type))
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?
+        .into_iter()
+        .filter_map(|term| term.slug.parse::<MealSlot>().ok().map(|slot| (term._id, slot)))
+        .collect();
+    let candidates: Vec<Candidate> = recipe::find_recipes(&db, recipe::only_published(Document::new()))
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?
+        .into_iter()
+        .map(|recipe| Candidate {
+            slots: slot_terms
+                .iter()
+                .filter(|(term_id, _)| recipe.term_ids.contains(term_id))
+                .map(|(_, slot)| *slot)
+                .collect(),
+            recipe,
+        })
+        .collect();
+
+    let window_start = start - Duration::days(constraints.no_repeat_days);
+    let history: Vec<(NaiveDate, String)> = meal_plan::find_entries(
+        &db,
+        &user.0,
+        &plan::format_date(window_start),
+        &plan::format_date(start - Duration::days(1)),
+    )
+    .await
+    .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?
+    .into_iter()
+    .filter_map(|entry| plan::parse_date(&entry.date).ok().map(|date| (date, entry.recipe_id)))
+    .collect();
+
+    let meals = planner::generate(&candidates, &constraints, start, days, &slots, &history, seed);
+
+    if input.save {
+        let mut documents = vec![];
+        for meal in meals {
+            let recipe_id = parse_id(&meal.recipe_id)
+                .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+            documents.push(MealPlanEntryDocument {
+                _id: None,
+                user_id: user.0.clone(),
+                date: meal.date,
+                slot: meal.slot,
+                recipe_id,
+                servings: 1,
+                note: None,
+                created_at: DateTime::now(),
+                updated_at: DateTime::now(),
+            });
+        }
+        meal_plan::insert_entries(&db, documents)
+            .await
+            .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+        let range = load_range(&db, &user, start, end).await?;
+        return Ok(Json(MealPlanGeneration {
+            seed,
+            saved: true,
+            plan: range,
+        }));
+    }
+
+    let entries = meals
+        .into_iter()
+        .map(|meal| MealPlanEntry {
+            _id: String::new(),
+            user_id: user.0.clone(),
+            date: meal.date,
+            slot: meal.slot,
+            recipe_id: meal.recipe_id,
+            servings: 1,
+            note: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        })
+        .collect();
+    let recipes: Vec<Recipe> = candidates.into_iter().map(|candidate| candidate.recipe).collect();
+    Ok(Json(MealPlanGeneration {
+        seed,
+        saved: false,
+        plan: plan::build_range(start, end, entries, &recipes),
+    }))
+}
@@ -7,6 +7,21 @@ pub mod customer;
 pub mod recipes;
 pub mod images;
 pub mod gifs;
+pub mod reviews;
+pub mod comments;
+pub mod taxonomy;
+pub mod nutrition;
+pub mod units;
+pub mod ingredients;
+pub mod shopping_lists;
+pub mod meal_plan;
+pub mod collections;
+pub mod imports;
+pub mod catalog;
+pub mod publishing;
+pub mod trash;
+pub mod translations;
+pub mod cooking;
 
 /// This is a description. <br />You can do simple html <br /> like <b>this<b/>
 #[openapi(tag = "Hello World")]
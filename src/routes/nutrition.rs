@@ -0,0 +1,48 @@
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::{parse_id, recipe};
+use crate::errors::response::MyError;
+use crate::models::nutrition::{NutritionReport, NutritionRequest};
+use crate::request_guards::basic::ApiKey;
+use crate::services::nutrition;
+
+/// Computes a recipe's nutrition from its ingredients, in total and per serving.
+/// `servings` defaults to the recipe's own.
+#[openapi(tag = "Nutrition")]
+#[get("/recipe/<id>/nutrition?<servings>")]
+pub async fn get_recipe_nutrition(
+    db: &State<Database>,
+    id: String,
+    servings: Option<i32>,
+    _key: ApiKey,
+) -> Result<Json<NutritionReport>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match recipe::find_one_recipe(&db, id).await {
+        Ok(Some(recipe)) => Ok(Json(nutrition::calculate(
+            &recipe.ingredients,
+            servings.unwrap_or(recipe.servings),
+        ))),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe not found with _id {}", &id)),
+        )),
+    }
+}
+
+/// Computes nutrition for an ingredient list that isn't saved yet.
+#[openapi(tag = "Nutrition")]
+#[post("/nutrition", data = "<input>")]
+pub async fn calculate_nutrition(
+    input: Json<NutritionRequest>,
+    _key: ApiKey,
+) -> Json<NutritionReport> {
+    Json(nutrition::calculate(
+        &input.ingredients,
+        input.servings.unwrap_or(1),
+    ))
+}
@@ -0,0 +1,135 @@
+use mongodb::bson::DateTime;
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::error::DbError;
+use crate::db::{parse_id, publishing, recipe};
+use crate::errors::response::MyError;
+use crate::models::publishing::{StatusChange, StatusChangeDocument, StatusChangeInput};
+use crate::models::recipe::{Recipe, RecipeStatus};
+use crate::request_guards::basic::{AdminKey, ApiKey};
+use crate::request_guards::user::User;
+use crate::services::publishing::{check_transition, needs_admin, parse_publish_at};
+
+/// Moves a recipe along the workflow: draft → in_review → published →
+/// archived, or back to draft from review or the archive. Publishing,
+/// archiving and restoring take the `x-admin-key` header as well. A
+/// `publish_at` in the future schedules a recipe in review to be published
+/// then instead, and a later change of status drops the schedule. Every
+/// change is kept in the recipe's status history, schedules with their
+/// `publish_at`.
+#[openapi(tag = "Publishing")]
+#[put("/recipe/<id>/status", data = "<input>")]
+pub async fn change_recipe_status(
+    db: &State<Database>,
+    id: String,
+    input: Json<StatusChangeInput>,
+    user: Option<User>,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<Recipe>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let input = input.into_inner();
+    let not_found = || {
+        MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe not found with _id {}", &id)),
+        )
+    };
+    let internal = |err: DbError| MyError::build(Status::InternalServerError.code, Some(err.details));
+
+    let stored = recipe::find_one_recipe(&db, id)
+        .await
+        .map_err(internal)?
+        .ok_or_else(not_found)?;
+    let from = stored.status;
+    check_transition(from, input.status)
+        .map_err(|err| MyError::build(Status::Conflict.code, Some(err)))?;
+    if needs_admin(from, input.status) && admin.is_none() {
+        return Err(MyError::build(
+            Status::Forbidden.code,
+            Some(format!("Only admins can move a recipe to {}.", input.status)),
+        ));
+    }
+    let publish_at = match input.publish_at.as_ref().filter(|text| !text.trim().is_empty()) {
+        Some(text) if input.status == RecipeStatus::published => {
+            parse_publish_at(text, DateTime::now())
+                .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?
+        }
+        Some(_) => {
+            return Err(MyError::build(
+                Status::BadRequest.code,
+                Some("Only publishing can be scheduled.".to_string()),
+            ))
+        }
+        None => None,
+    };
+    let changed = |changed: bool| {
+        if changed {
+            Ok(())
+        } else {
+            Err(MyError::build(
+                Status::Conflict.code,
+                Some("The recipe's status was changed in the meantime.".to_string()),
+            ))
+        }
+    };
+
+    if let Some(publish_at) = publish_at {
+        changed(recipe::schedule_recipe(&db, id, publish_at).await.map_err(internal)?)?;
+    } else {
+        changed(
+            recipe::change_recipe_status(&db, id, from, input.status)
+                .await
+                .map_err(internal)?,
+        )?;
+    }
+    let change = StatusChangeDocument {
+        _id: None,
+        recipe_id: id,
+        from,
+        to: input.status,
+        user_id: user.map(|user| user.0),
+        admin: admin.is_some(),
+        scheduled: false,
+        publish_at,
+        note: input.note.unwrap_or_default(),
+        created_at: DateTime::now(),
+    };
+    if let Err(_error) = publishing::insert_status_change(&db, change).await {
+        println!("{:?}", _error);
+    }
+
+    match recipe::find_one_recipe(&db, id).await {
+        Ok(Some(recipe)) => Ok(Json(recipe)),
+        _ => Err(not_found()),
+    }
+}
+
+/// The status changes of a recipe, newest first, including the ones made
+/// by the scheduler.
+#[openapi(tag = "Publishing")]
+#[get("/recipe/<id>/status/history?<limit>&<page>")]
+pub async fn get_recipe_status_history(
+    db: &State<Database>,
+    id: String,
+    limit: Option<i64>,
+    page: Option<i64>,
+    _key: ApiKey,
+) -> Result<Json<Vec<StatusChange>>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+    match publishing::find_status_changes(&db, id, limit, page).await {
+        Ok(changes) => Ok(Json(changes)),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
@@ -1,15 +1,86 @@
-use mongodb::bson::doc;
+use std::collections::HashMap;
+use chrono::{Duration, Utc};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document};
 use mongodb::Database;
-use rocket::http::Status;
+use rocket::http::{ContentType, Status};
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::openapi;
 
-use crate::models::recipe::Recipe;
-use crate::request_guards::basic::ApiKey;
-use crate::db::{parse_id, recipe};
+use crate::models::collection::CollectionMembership;
+use crate::models::daily::DailyRecipe;
+use crate::models::pantry::{PantryMatch, PantryRequest, MAX_PANTRY_MATCHES};
+use crate::models::print::{PageSize, PdfName};
+use crate::models::recipe::{Recipe, RecipeStatus, SimilarRecipe, UnitSystem};
+use crate::models::taxonomy::{RecipeSearchResponse, TermKind};
+use crate::request_guards::accept::{AcceptLanguage, Representation};
+use crate::request_guards::basic::{AdminKey, ApiKey};
+use crate::request_guards::user::User;
+use crate::db::{collection, daily, gif, image, parse_id, recipe, taxonomy};
+use crate::db::error::DbError;
 
 use crate::errors::response::MyError;
+use crate::services::daily::{self as recipe_of_the_day, DailyCandidate, Season};
+use crate::services::meal_plan::{format_date, parse_date};
+use crate::services::similarity::{self, SimilarityIndex};
+use crate::routes::gifs::FileResponse;
+use crate::services::pdf::Jpeg;
+use crate::services::{forks, html, jsonld, locale, markdown, pantry, print, scaling, search, units, uploads};
+
+/// Rejects recipes referencing taxonomy terms that don't exist. Terms in
+/// the trash still count, recipes keep them until they are purged.
+pub async fn check_terms_exist(db: &Database, recipe: &Recipe) -> Result<(), MyError> {
+    let mut ids: Vec<ObjectId> = vec![];
+    for id in recipe.term_ids.iter() {
+        ids.push(parse_id(id).map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?);
+    }
+    ids.sort();
+    ids.dedup();
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let unknown = || {
+        MyError::build(
+            Status::BadRequest.code,
+            Some("Recipe references unknown taxonomy terms.".to_string()),
+        )
+    };
+    let terms = taxonomy::find_terms_by_ids(db, ids.clone())
+        .await
+        .map_err(|_err| unknown())?;
+    if terms.len() == ids.len() {
+        return Ok(());
+    }
+    let missing: Vec<ObjectId> = ids
+        .into_iter()
+        .filter(|id| !terms.iter().any(|term| term._id == id.to_string()))
+        .collect();
+    match taxonomy::find_trashed_term_ids(db, missing.clone()).await {
+        Ok(trashed) if trashed.len() == missing.len() => Ok(()),
+        _ => Err(unknown()),
+    }
+}
+
+/// The locales to pick translations from, `lang` first, then those of the
+/// `Accept-Language` header, then the default locale.
+pub fn locale_chain(lang: Option<String>, languages: AcceptLanguage) -> Result<Vec<String>, MyError> {
+    let mut asked = languages.0;
+    if let Some(lang) = lang {
+        let tag = locale::normalize_tag(&lang).ok_or(MyError::build(
+            Status::BadRequest.code,
+            Some(format!("\"{}\" is not a language tag.", lang)),
+        ))?;
+        asked.insert(0, tag);
+    }
+    Ok(locale::fallback_chain(&asked, &locale::default_locale()))
+}
+
+/// Checks and normalizes the translations of a recipe to be stored.
+fn normalize_translations(recipe: Recipe) -> Result<Recipe, MyError> {
+    locale::normalize_translations(recipe, &locale::default_locale())
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))
+}
 
 #[openapi(tag = "Recipe")]
 #[post("/recipe", data = "<recipe>")]
@@ -18,7 +89,17 @@ pub async fn post_recipe(
     recipe: Json<Recipe>,
     _key: ApiKey,
 ) -> Result<Json<String>, MyError> {
-    return match recipe::insert_recipe(&db, recipe.into_inner()).await {
+    check_terms_exist(&db, &recipe).await?;
+    let recipe = uploads::keep_stored_media(recipe.into_inner(), None);
+    let mut recipe = normalize_translations(recipe)?;
+    // Lineage only comes from forking, ratings from reviews, and new
+    // recipes start as drafts.
+    recipe.forked_from = None;
+    recipe.rating_average = 0.0;
+    recipe.rating_count = 0;
+    recipe.status = RecipeStatus::draft;
+    recipe.publish_at = None;
+    return match recipe::insert_recipe(&db, recipe).await {
         Ok(result) => {
             Ok(Json(result.inserted_id.to_string()))
         }
@@ -32,20 +113,58 @@ pub async fn post_recipe(
     }
 }
 
+/// A recipe in the representation the client asked for.
+pub enum RecipeResponse {
+    Json(Recipe),
+    /// schema.org `Recipe`
+    JsonLd(String),
+    Html(String),
+    Markdown(String),
+}
+
+/// Gets a recipe, optionally scaled to `servings` and with the ingredients
+/// converted to the `units` measurement system. Lists the public
+/// collections holding it, and the caller's own when `x-user-id` is sent.
+/// `Accept: application/ld+json` gives schema.org JSON-LD instead and
+/// `Accept: text/html` a page embedding it, `Accept: text/markdown` the
+/// editorial Markdown format. Names and steps are translated to `lang` or
+/// the `Accept-Language` locales where there are translations: in
+/// `localized` for JSON, which keeps the fields in the default locale so
+/// the recipe can be sent back as it is, and in place for JSON-LD and HTML.
+/// Markdown stays in the default locale, it is imported back. Recipes that
+/// aren't published are only found with the `x-admin-key` header.
 #[openapi(tag = "Recipe")]
-#[get("/recipe/<id>")]
+#[get("/recipe/<id>?<servings>&<units>&<lang>", rank = 2)]
 pub async fn get_recipe(
     db: &State<Database>,
     id: String,
+    servings: Option<i32>,
+    units: Option<UnitSystem>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    user: Option<User>,
+    representation: Representation,
+    admin: Option<AdminKey>,
     _key: ApiKey,
-) -> Result<Json<Recipe>, MyError> {
+) -> Result<RecipeResponse, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    if let Some(servings) = servings.filter(|servings| *servings < 1) {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some(format!("Cannot scale a recipe to {} servings.", servings)),
+        ));
+    }
     let id = parse_id(&id)
         .map_err(|err|MyError::build(
             Status::BadRequest.code,
             Some(err.details))
         )?;
 
-    match recipe::find_one_recipe(&db, id).await {
+    let found = match admin {
+        Some(_) => recipe::find_one_recipe(&db, id).await,
+        None => recipe::find_one_published_recipe(&db, id).await,
+    };
+    match found {
         Ok(recipe) => {
             if recipe.is_none() {
                 return Err(MyError::build(
@@ -53,7 +172,40 @@ pub async fn get_recipe(
                     Some(format!("Recipe not found with _id {}", &id)),
                 ));
             }
-            Ok(Json(recipe.unwrap()))
+            let mut recipe = recipe.unwrap();
+            if let Some(servings) = servings {
+                recipe = scaling::scale_recipe(recipe, servings);
+            }
+            if let Some(system) = units {
+                recipe = units::express_recipe(recipe, system);
+            }
+            let default = locale::default_locale();
+            let user_id = user.as_ref().map(|user| user.0.as_str());
+            match collection::find_recipe_collections(&db, id, user_id).await {
+                Ok(collections) => {
+                    recipe.collections = collections.iter().map(CollectionMembership::from).collect()
+                }
+                Err(_error) => println!("{:?}", _error),
+            }
+            Ok(match representation {
+                Representation::Json => {
+                    RecipeResponse::Json(locale::with_localized_text(recipe, &chain, &default))
+                }
+                Representation::JsonLd => {
+                    let recipe = locale::localize_recipe(recipe, &chain, &default);
+                    RecipeResponse::JsonLd(jsonld::from_recipe(&recipe, &jsonld::public_base_url()).to_string())
+                }
+                Representation::Html => {
+                    let recipe = locale::localize_recipe(recipe, &chain, &default);
+                    RecipeResponse::Html(html::render_recipe(
+                        &recipe,
+                        &jsonld::from_recipe(&recipe, &jsonld::public_base_url()),
+                    ))
+                }
+                Representation::Markdown => {
+                    RecipeResponse::Markdown(markdown::from_recipe(&recipe, &jsonld::public_base_url()))
+                }
+            })
         }
         Err(_error) => {
             println!("{:?}", _error);
@@ -66,12 +218,82 @@ pub async fn get_recipe(
 }
 
 
+/// Reads an uploaded image or GIF for printing. Imported media only has a
+/// URL and is left out, and so is anything outside the upload directory.
+async fn read_print_image(path: &str) -> Option<Jpeg> {
+    let path = uploads::resolve(path)?;
+    let bytes = rocket::tokio::fs::read(path).await.ok()?;
+    Jpeg::from_image_bytes(&bytes)
+}
+
+/// `GET /recipe/<id>.pdf`, the recipe as a printable card on A4 paper
+/// unless `size` asks for letter or index cards. `servings` and `units`
+/// work as for the recipe itself, and so do `lang` and `Accept-Language`.
+/// Recipes that aren't published need the `x-admin-key` header.
+#[get("/recipe/<file>?<size>&<servings>&<units>&<lang>", rank = 1)]
+pub async fn get_recipe_pdf(
+    db: &State<Database>,
+    file: PdfName,
+    size: Option<PageSize>,
+    servings: Option<i32>,
+    units: Option<UnitSystem>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<FileResponse, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    if let Some(servings) = servings.filter(|servings| *servings < 1) {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some(format!("Cannot scale a recipe to {} servings.", servings)),
+        ));
+    }
+    let id = parse_id(&file.0)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let found = match admin {
+        Some(_) => recipe::find_one_recipe(&db, id).await,
+        None => recipe::find_one_published_recipe(&db, id).await,
+    };
+    let mut recipe = match found {
+        Ok(Some(recipe)) => recipe,
+        _ => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Recipe not found with _id {}", &id)),
+            ))
+        }
+    };
+    if let Some(servings) = servings {
+        recipe = scaling::scale_recipe(recipe, servings);
+    }
+    if let Some(system) = units {
+        recipe = units::express_recipe(recipe, system);
+    }
+    recipe = locale::localize_recipe(recipe, &chain, &locale::default_locale());
+
+    let hero = match recipe.images.first() {
+        Some(image) => read_print_image(&image.path).await,
+        None => None,
+    };
+    let mut frames = vec![];
+    for step in recipe.steps.iter() {
+        frames.push(read_print_image(&step.gif.path).await);
+    }
+    let pdf = print::recipe_pdf(&recipe, size.unwrap_or(PageSize::a4), hero, frames);
+    Ok(FileResponse((ContentType::PDF, pdf)))
+}
+
+/// Replaces a recipe, keeping its status, forks and ratings. Published and
+/// archived recipes can only be edited with the `x-admin-key` header, so
+/// changes go through review before they are public.
 #[openapi(tag = "Recipe")]
 #[put("/recipe/<id>", data = "<recipe>")]
 pub async fn update_recipe(
     db: &State<Database>,
     id: String,
     recipe: Json<Recipe>,
+    admin: Option<AdminKey>,
     _key: ApiKey,
 ) -> Result<Json<Recipe>, MyError> {
     let id = parse_id(&id)
@@ -79,7 +301,26 @@ pub async fn update_recipe(
             Status::BadRequest.code,
             Some(err.details))
         )?;
-    match recipe::update_recipe(&db, id, recipe.into_inner()).await {
+    check_terms_exist(&db, &recipe).await?;
+    let stored = recipe::find_one_recipe(&db, id).await.ok().flatten();
+    if let Some(status) = stored.as_ref().map(|stored| stored.status) {
+        if matches!(status, RecipeStatus::published | RecipeStatus::archived) && admin.is_none() {
+            return Err(MyError::build(
+                Status::Forbidden.code,
+                Some(format!("Only admins can edit {} recipes.", status)),
+            ));
+        }
+    }
+    let recipe = uploads::keep_stored_media(recipe.into_inner(), stored.as_ref());
+    let mut recipe = normalize_translations(recipe)?;
+    if let Some(stored) = stored {
+        recipe.forked_from = stored.forked_from;
+        recipe.rating_average = stored.rating_average;
+        recipe.rating_count = stored.rating_count;
+        recipe.status = stored.status;
+        recipe.publish_at = stored.publish_at;
+    }
+    match recipe::update_recipe(&db, id, recipe).await {
         Ok(recipe) => {
             if recipe.is_none() {
                 return Err(MyError::build(
@@ -99,6 +340,8 @@ pub async fn update_recipe(
     }
 }
 
+/// Moves the recipe to the trash, see `GET /trash`. Collections keep it
+/// until it is purged.
 #[openapi(tag = "Recipe")]
 #[delete("/recipe/<id>")]
 pub async fn delete_recipe(
@@ -113,7 +356,7 @@ pub async fn delete_recipe(
         )?;
     return match recipe::delete_one_recipe(&db, id).await {
         Ok(res) => {
-            if res.deleted_count == 1 {
+            if res.modified_count == 1 {
                 Ok(Json("recipe successfully deleted!"))
             } else {
                 Err(MyError::build(
@@ -132,17 +375,424 @@ pub async fn delete_recipe(
     }
 }
 
+/// Copies a recipe to be changed without touching the original. The copy
+/// gets its own image and step records, sharing the media files, and names
+/// the original in `forked_from`. Only published recipes can be forked
+/// without the `x-admin-key` header.
+#[openapi(tag = "Recipe")]
+#[post("/recipe/<id>/fork")]
+pub async fn fork_recipe(
+    db: &State<Database>,
+    id: String,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<Recipe>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let found = match admin {
+        Some(_) => recipe::find_one_recipe(&db, id).await,
+        None => recipe::find_one_published_recipe(&db, id).await,
+    };
+    let original = match found {
+        Ok(Some(original)) => original,
+        _ => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Recipe not found with _id {}", &id)),
+            ))
+        }
+    };
+    let not_forked = || {
+        MyError::build(
+            Status::InternalServerError.code,
+            Some("Recipe not forked.".to_string()),
+        )
+    };
+
+    // Imported media has no record to copy and keeps its empty id.
+    let mut ids: HashMap<String, String> = HashMap::new();
+    for embedded in original.images.iter().filter(|image| !image._id.is_empty()) {
+        let image_id = image::insert_image(&db, embedded.clone())
+            .await
+            .map(|result| result.inserted_id.as_object_id())
+            .map_err(|_err| not_forked())?
+            .ok_or(not_forked())?;
+        ids.insert(embedded._id.clone(), image_id.to_string());
+    }
+    for step in original.steps.iter().filter(|step| !step._id.is_empty()) {
+        let step_id = gif::insert_recipe_step(&db, step.clone())
+            .await
+            .map(|result| result.inserted_id.as_object_id())
+            .map_err(|_err| not_forked())?
+            .ok_or(not_forked())?;
+        ids.insert(step._id.clone(), step_id.to_string());
+    }
+
+    let fork_id = recipe::insert_recipe(&db, forks::fork_recipe(&original, &ids))
+        .await
+        .map(|result| result.inserted_id.as_object_id())
+        .map_err(|_err| not_forked())?
+        .ok_or(not_forked())?;
+    match recipe::find_one_recipe(&db, fork_id).await {
+        Ok(Some(fork)) => Ok(Json(fork)),
+        _ => Err(not_forked()),
+    }
+}
+
+/// Recipes forked from the recipe, newest first. Forks that aren't
+/// published are only listed with the `x-admin-key` header.
+#[openapi(tag = "Recipe")]
+#[get("/recipe/<id>/forks?<limit>&<page>")]
+pub async fn get_recipe_forks(
+    db: &State<Database>,
+    id: String,
+    limit: Option<i64>,
+    page: Option<i64>,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<Vec<Recipe>>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+    match recipe::find_forks(&db, id, admin.is_some(), limit, page).await {
+        Ok(forks) => Ok(Json(forks)),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
+
+/// Builds the recipe filter for the taxonomy slugs given in a query.
+/// A recipe must carry every requested term to match.
+async fn build_recipe_filter(
+    db: &Database,
+    tag: Vec<String>,
+    cuisine: Vec<String>,
+    meal_type: Vec<String>,
+    diet: Vec<String>,
+) -> Result<Document, MyError> {
+    let requested = [
+        (TermKind::tag, tag),
+        (TermKind::cuisine, cuisine),
+        (TermKind::meal_type, meal_type),
+        (TermKind::diet, diet),
+    ];
+    let mut term_ids: Vec<ObjectId> = vec![];
+    for (kind, slugs) in requested {
+        for slug in slugs {
+            match taxonomy::find_term_by_slug(db, kind, &slug).await {
+                Ok(Some(term)) => term_ids.push(parse_id(&term._id).map_err(|err| {
+                    MyError::build(Status::InternalServerError.code, Some(err.details))
+                })?),
+                _ => {
+                    return Err(MyError::build(
+                        Status::BadRequest.code,
+                        Some(format!("Unknown {} '{}'.", kind, slug)),
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(search::term_filter(term_ids))
+}
+
+/// Lists the published recipes. With the `x-admin-key` header recipes of
+/// every status are listed, or those with `status`. `lang` and
+/// `Accept-Language` pick translations as for a single recipe.
 #[openapi(tag = "Recipe")]
-#[get("/recipes")]
+#[get("/recipes?<tag>&<cuisine>&<meal_type>&<diet>&<units>&<status>&<lang>")]
 pub async fn get_all_recipes(
     db: &State<Database>,
+    tag: Vec<String>,
+    cuisine: Vec<String>,
+    meal_type: Vec<String>,
+    diet: Vec<String>,
+    units: Option<UnitSystem>,
+    status: Option<RecipeStatus>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    admin: Option<AdminKey>,
     _key: ApiKey
 ) -> Result<Json<Vec<Recipe>>, MyError> {
-    match recipe::find_all_recipes(&db).await {
-        Ok(_docs) => Ok(Json(_docs)),
+    let chain = locale_chain(lang, languages)?;
+    let default = locale::default_locale();
+    let mut filter = build_recipe_filter(&db, tag, cuisine, meal_type, diet).await?;
+    match (status, admin) {
+        (Some(RecipeStatus::published), _) | (None, None) => filter = recipe::only_published(filter),
+        (Some(status), Some(_)) => {
+            filter.insert("status", status.to_string());
+        }
+        (None, Some(_)) => {}
+        (Some(status), None) => {
+            return Err(MyError::build(
+                Status::Forbidden.code,
+                Some(format!("Only admins can list {} recipes.", status)),
+            ))
+        }
+    }
+    match recipe::find_recipes(&db, filter).await {
+        Ok(_docs) => Ok(Json(
+            _docs
+                .into_iter()
+                .map(|recipe| match units {
+                    Some(system) => units::express_recipe(recipe, system),
+                    None => recipe,
+                })
+                .map(|recipe| locale::with_localized_text(recipe, &chain, &default))
+                .collect(),
+        )),
         Err(_error) => {
             println!("{:?}", _error);
             return Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())));
         }
     }
-}
\ No newline at end of file
+}
+
+/// Searches recipes by name and taxonomy, with per-term counts of the matches.
+/// Only published recipes are found unless the `x-admin-key` header is sent.
+/// Names are matched in the default locale and in those of `lang` and
+/// `Accept-Language`, the recipes' `localized` text is in those too.
+#[openapi(tag = "Recipe")]
+#[get("/recipes/search?<q>&<tag>&<cuisine>&<meal_type>&<diet>&<lang>")]
+pub async fn search_recipes(
+    db: &State<Database>,
+    q: Option<String>,
+    tag: Vec<String>,
+    cuisine: Vec<String>,
+    meal_type: Vec<String>,
+    diet: Vec<String>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    admin: Option<AdminKey>,
+    _key: ApiKey
+) -> Result<Json<RecipeSearchResponse>, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    let default = locale::default_locale();
+    let mut filter = build_recipe_filter(&db, tag, cuisine, meal_type, diet).await?;
+    if admin.is_none() {
+        filter = recipe::only_published(filter);
+    }
+    if let Some(names) = q.and_then(|q| search::name_filter(&q, &chain, &default)) {
+        filter.extend(names);
+    }
+
+    let recipes: Vec<Recipe> = recipe::find_recipes(&db, filter.clone())
+        .await
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?
+        .into_iter()
+        .map(|recipe| locale::with_localized_text(recipe, &chain, &default))
+        .collect();
+    let counts = recipe::count_recipes_by_term(&db, filter)
+        .await
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let terms = taxonomy::find_terms_by_ids(&db, counts.iter().map(|(id, _)| *id).collect())
+        .await
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    let counts: HashMap<String, i64> = counts
+        .into_iter()
+        .map(|(id, count)| (id.to_string(), count))
+        .collect();
+    let facets = search::facets(&terms, &counts);
+
+    Ok(Json(RecipeSearchResponse { recipes, facets }))
+}
+
+/// The recipe of the day, the same for everyone on a given date, today by
+/// default. Recipes picked within the last `DAILY_RECIPE_WINDOW` days are
+/// skipped, recipes tagged with a season are only picked in that season, and
+/// `DAILY_RECIPE_TAG` limits the pick to recipes with that tag. Only today's
+/// pick is stored, the first time it is requested; other days show their
+/// stored pick, else what would be picked from the current recipes.
+/// `lang` and `Accept-Language` pick translations as for a single recipe.
+#[openapi(tag = "Recipe")]
+#[get("/recipes/daily?<date>&<lang>")]
+pub async fn get_daily_recipe(
+    db: &State<Database>,
+    date: Option<String>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    _key: ApiKey
+) -> Result<Json<DailyRecipe>, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    let default = locale::default_locale();
+    let localized = |recipe: Recipe| locale::with_localized_text(recipe, &chain, &default);
+    let date = match date {
+        Some(date) => parse_date(&date).map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?,
+        None => Utc::now().date_naive(),
+    };
+    let day = format_date(date);
+    let today = date == Utc::now().date_naive();
+    let internal = |err: DbError| MyError::build(Status::InternalServerError.code, Some(err.details));
+
+    if let Some(pick) = daily::find_picks(&db, &day, &day).await.map_err(internal)?.pop() {
+        let recipe_id = parse_id(&pick.recipe_id).map_err(internal)?;
+        match recipe::find_one_recipe(&db, recipe_id).await {
+            Ok(Some(recipe)) if recipe.status == RecipeStatus::published => {
+                return Ok(Json(DailyRecipe { date: day, recipe: localized(recipe) }))
+            }
+            // The recipe was deleted or unpublished since, pick another one.
+            _ if today => daily::delete_pick(&db, &day).await.map_err(internal)?,
+            _ => {}
+        }
+    }
+
+    let tags = recipe_of_the_day::required_tag().into_iter().collect();
+    let filter = build_recipe_filter(&db, tags, vec![], vec![], vec![]).await?;
+    let seasons: Vec<(String, Season)> = taxonomy::find_terms(&db, Some(TermKind::tag))
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .filter_map(|term| term.slug.parse::<Season>().ok().map(|season| (term._id, season)))
+        .collect();
+    let candidates: Vec<DailyCandidate> = recipe::find_recipes(&db, recipe::only_published(filter))
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(|recipe| DailyCandidate {
+            seasons: seasons
+                .iter()
+                .filter(|(term_id, _)| recipe.term_ids.contains(term_id))
+                .map(|(_, season)| *season)
+                .collect(),
+            recipe,
+        })
+        .collect();
+
+    let window = recipe_of_the_day::window();
+    let recent: Vec<String> = if window > 0 {
+        daily::find_picks(
+            &db,
+            &format_date(date - Duration::days(window)),
+            &format_date(date - Duration::days(1)),
+        )
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .map(|pick| pick.recipe_id)
+        .collect()
+    } else {
+        vec![]
+    };
+
+    let picked = match recipe_of_the_day::pick(date, &candidates, &recent) {
+        Some(recipe) => recipe,
+        None => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("No recipe can be picked for {}.", day)),
+            ))
+        }
+    };
+    if !today {
+        return Ok(Json(DailyRecipe { date: day, recipe: localized(picked) }));
+    }
+    let recipe_id = parse_id(&picked._id).map_err(internal)?;
+
+    // Another request may have stored a pick in the meantime; it wins.
+    match daily::save_pick(&db, &day, recipe_id).await.map_err(internal)? {
+        Some(pick) if pick.recipe_id != picked._id => {
+            let stored_id = parse_id(&pick.recipe_id).map_err(internal)?;
+            match recipe::find_one_recipe(&db, stored_id).await {
+                Ok(Some(recipe)) => Ok(Json(DailyRecipe { date: day, recipe: localized(recipe) })),
+                _ => Err(MyError::build(
+                    Status::NotFound.code,
+                    Some(format!("Recipe not found with _id {}", &stored_id)),
+                )),
+            }
+        }
+        _ => Ok(Json(DailyRecipe { date: day, recipe: localized(picked) })),
+    }
+}
+
+/// Recipes similar to this one, by shared ingredients, shared taxonomy terms,
+/// preparation time and nutrition per serving, among the published recipes.
+/// The similarity index is kept in memory and rebuilt after recipes change.
+/// `lang` and `Accept-Language` pick translations as for a single recipe.
+#[openapi(tag = "Recipe")]
+#[get("/recipe/<id>/similar?<limit>&<lang>")]
+pub async fn get_similar_recipes(
+    db: &State<Database>,
+    id: String,
+    limit: Option<usize>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    _key: ApiKey
+) -> Result<Json<Vec<SimilarRecipe>>, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    let recipe_id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let limit = limit.unwrap_or(6);
+
+    let index = match similarity::cached() {
+        Some(index) => index,
+        None => {
+            let generation = similarity::generation();
+            let recipes = recipe::find_recipes(&db, recipe::only_published(Document::new()))
+                .await
+                .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+            similarity::store(SimilarityIndex::build(&recipes), generation)
+        }
+    };
+    if !index.contains(&recipe_id.to_string()) {
+        return Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe not found with _id {}", &recipe_id)),
+        ));
+    }
+
+    let scores = index.similar(&recipe_id.to_string(), limit);
+    let mut ids: Vec<ObjectId> = vec![];
+    for (id, _) in scores.iter() {
+        ids.push(parse_id(id).map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?);
+    }
+    let mut recipes = recipe::find_recipes(&db, doc! { "_id": { "$in": ids } })
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    let mut similar = vec![];
+    for (id, score) in scores {
+        if let Some(position) = recipes.iter().position(|recipe| recipe._id == id) {
+            similar.push(SimilarRecipe {
+                score: (score * 1000.0).round() / 1000.0,
+                recipe: locale::with_localized_text(recipes.swap_remove(position), &chain, &locale::default_locale()),
+            });
+        }
+    }
+    Ok(Json(similar))
+}
+
+/// "What can I cook": published recipes ranked by how many of their
+/// ingredients are in the pantry, with what is still missing. Synonyms like
+/// "scallion" and "green onion" are matched through the ingredient alias table.
+/// `lang` and `Accept-Language` pick translations as for a single recipe.
+#[openapi(tag = "Recipe")]
+#[post("/recipes/pantry?<lang>", data = "<input>")]
+pub async fn match_pantry(
+    db: &State<Database>,
+    input: Json<PantryRequest>,
+    lang: Option<String>,
+    languages: AcceptLanguage,
+    _key: ApiKey
+) -> Result<Json<Vec<PantryMatch>>, MyError> {
+    let chain = locale_chain(lang, languages)?;
+    let input = input.into_inner();
+    if input.ingredients.iter().all(|item| item.trim().is_empty()) {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some("The pantry must list at least one ingredient.".to_string()),
+        ));
+    }
+    let recipes = recipe::find_recipes(&db, recipe::only_published(Document::new()))
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+    let mut matches = pantry::rank(&recipes, &input.ingredients, input.min_coverage.unwrap_or(0.0));
+    matches.truncate(input.limit.unwrap_or(12).min(MAX_PANTRY_MATCHES));
+    for found in matches.iter_mut() {
+        found.recipe = locale::with_localized_text(found.recipe.clone(), &chain, &locale::default_locale());
+    }
+    Ok(Json(matches))
+}
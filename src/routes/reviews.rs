@@ -0,0 +1,287 @@
+use mongodb::bson::DateTime;
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::{parse_id, recipe, review};
+use crate::errors::response::MyError;
+use crate::models::review::{
+    Review, ReviewDocument, ReviewFlag, ReviewFlagInput, ReviewInput, ReviewModerationInput,
+};
+use crate::request_guards::basic::{AdminKey, ApiKey};
+use crate::request_guards::user::User;
+
+/// Loads a review and checks that it was written by `user`.
+async fn find_own_review(db: &Database, id: &String, user: &User) -> Result<Review, MyError> {
+    let id = parse_id(id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match review::find_one_review(db, id).await {
+        Ok(Some(review)) if review.user_id == user.0 => Ok(review),
+        Ok(Some(_)) => Err(MyError::build(
+            Status::Forbidden.code,
+            Some("Only the author can change a review.".to_string()),
+        )),
+        Ok(None) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Review not found with _id {}", &id)),
+        )),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Review not found with _id {}", &id)),
+            ))
+        }
+    }
+}
+
+pub async fn refresh_rating(db: &Database, recipe_id: &String) -> Result<(), MyError> {
+    let recipe_id = parse_id(recipe_id)
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+    review::refresh_recipe_rating(db, recipe_id)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))
+}
+
+#[openapi(tag = "Review")]
+#[post("/recipe/<id>/reviews", data = "<input>")]
+pub async fn post_review(
+    db: &State<Database>,
+    id: String,
+    input: Json<ReviewInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Review>, MyError> {
+    let recipe_id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    input
+        .validate()
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+
+    match recipe::find_one_recipe(&db, recipe_id).await {
+        Ok(Some(_)) => {}
+        _ => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Recipe not found with _id {}", &recipe_id)),
+            ))
+        }
+    }
+
+    if let Ok(Some(_)) = review::find_review_by_user(&db, recipe_id, &user.0).await {
+        return Err(MyError::build(
+            Status::Conflict.code,
+            Some("You have already reviewed this recipe.".to_string()),
+        ));
+    }
+
+    let input = input.into_inner();
+    let document = ReviewDocument {
+        _id: None,
+        recipe_id,
+        user_id: user.0,
+        rating: input.rating,
+        text: input.text,
+        flags: vec![],
+        hidden: false,
+        created_at: DateTime::now(),
+        updated_at: DateTime::now(),
+    };
+
+    let review_id = review::insert_review(&db, document)
+        .await
+        .map(|result| result.inserted_id.as_object_id())
+        .map_err(|_err| {
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("Review not added.".to_string()),
+            )
+        })?
+        .ok_or({
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("No Object ID found!".to_string()),
+            )
+        })?;
+
+    review::refresh_recipe_rating(&db, recipe_id)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    match review::find_one_review(&db, review_id).await {
+        Ok(Some(review)) => Ok(Json(review)),
+        _ => Err(MyError::build(
+            Status::InternalServerError.code,
+            Some("Review not added.".to_string()),
+        )),
+    }
+}
+
+#[openapi(tag = "Review")]
+#[get("/recipe/<id>/reviews?<limit>&<page>")]
+pub async fn get_recipe_reviews(
+    db: &State<Database>,
+    id: String,
+    limit: Option<i64>,
+    page: Option<i64>,
+    _key: ApiKey,
+) -> Result<Json<Vec<Review>>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    // Setting default values
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+    match review::find_recipe_reviews(&db, id, limit, page).await {
+        Ok(reviews) => Ok(Json(reviews)),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
+
+#[openapi(tag = "Review")]
+#[put("/review/<id>", data = "<input>")]
+pub async fn update_review(
+    db: &State<Database>,
+    id: String,
+    input: Json<ReviewInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Review>, MyError> {
+    input
+        .validate()
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err)))?;
+    let existing = find_own_review(&db, &id, &user).await?;
+    let review_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    let input = input.into_inner();
+    review::update_review_content(&db, review_id, input.rating, input.text)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+    refresh_rating(&db, &existing.recipe_id).await?;
+
+    match review::find_one_review(&db, review_id).await {
+        Ok(Some(review)) => Ok(Json(review)),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Review not found with _id {}", &review_id)),
+        )),
+    }
+}
+
+#[openapi(tag = "Review")]
+#[delete("/review/<id>")]
+pub async fn delete_review(
+    db: &State<Database>,
+    id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let existing = find_own_review(&db, &id, &user).await?;
+    let review_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    match review::delete_one_review(&db, review_id).await {
+        Ok(res) if res.modified_count == 1 => {
+            refresh_rating(&db, &existing.recipe_id).await?;
+            Ok(Json("Review successfully deleted!"))
+        }
+        Ok(_) => Err(MyError::build(
+            Status::NotFound.code,
+            Some("Not Found.".to_string()),
+        )),
+        Err(error) => {
+            println!("{:?}", error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Review not found with _id {}", &review_id)),
+            ))
+        }
+    }
+}
+
+/// Reports a review to the moderators.
+#[openapi(tag = "Review")]
+#[post("/review/<id>/flag", data = "<input>")]
+pub async fn flag_review(
+    db: &State<Database>,
+    id: String,
+    input: Json<ReviewFlagInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let flag = ReviewFlag {
+        user_id: user.0,
+        reason: input.into_inner().reason,
+    };
+    match review::flag_review(&db, id, flag).await {
+        Ok(true) => Ok(Json("Review flagged for moderation.")),
+        // Nothing changed: flagged by the user already, or no such review.
+        Ok(false) => match review::find_one_review(&db, id).await {
+            Ok(Some(_)) => Ok(Json("Review flagged for moderation.")),
+            _ => Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Review not found with _id {}", &id)),
+            )),
+        },
+        Err(error) => {
+            println!("{:?}", error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Review not found with _id {}", &id)),
+            ))
+        }
+    }
+}
+
+#[openapi(tag = "Review")]
+#[get("/reviews/flagged?<limit>&<page>")]
+pub async fn get_flagged_reviews(
+    db: &State<Database>,
+    limit: Option<i64>,
+    page: Option<i64>,
+    _key: AdminKey,
+) -> Result<Json<Vec<Review>>, MyError> {
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+    match review::find_flagged_reviews(&db, limit, page).await {
+        Ok(reviews) => Ok(Json(reviews)),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
+
+/// Hides or restores a review. Hidden reviews don't count towards the recipe rating.
+#[openapi(tag = "Review")]
+#[put("/review/<id>/moderation", data = "<input>")]
+pub async fn moderate_review(
+    db: &State<Database>,
+    id: String,
+    input: Json<ReviewModerationInput>,
+    _key: AdminKey,
+) -> Result<Json<Review>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    review::set_review_hidden(&db, id, input.hidden)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+
+    match review::find_one_review(&db, id).await {
+        Ok(Some(review)) => {
+            refresh_rating(&db, &review.recipe_id).await?;
+            Ok(Json(review))
+        }
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Review not found with _id {}", &id)),
+        )),
+    }
+}
@@ -0,0 +1,221 @@
+use mongodb::bson::DateTime;
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::{parse_id, recipe, shopping_list};
+use crate::errors::response::MyError;
+use crate::models::shopping_list::{
+    ShoppingList, ShoppingListCheckInput, ShoppingListDocument, ShoppingListInput,
+    ShoppingListRecipeDocument,
+};
+use crate::request_guards::basic::ApiKey;
+use crate::request_guards::user::User;
+use crate::services::{scaling, shopping};
+
+/// Loads a shopping list and checks that it belongs to `user`.
+async fn find_own_list(db: &Database, id: &String, user: &User) -> Result<ShoppingList, MyError> {
+    let id = parse_id(id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match shopping_list::find_one_shopping_list(db, id).await {
+        Ok(Some(list)) if list.user_id == user.0 => Ok(list),
+        Ok(Some(_)) => Err(MyError::build(
+            Status::Forbidden.code,
+            Some("This shopping list belongs to another user.".to_string()),
+        )),
+        Ok(None) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Shopping list not found with _id {}", &id)),
+        )),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Shopping list not found with _id {}", &id)),
+            ))
+        }
+    }
+}
+
+/// Makes a shopping list for the recipes, each scaled to the requested
+/// servings. Ingredients shared by several recipes are added up and the
+/// items are grouped by store aisle.
+#[openapi(tag = "Shopping List")]
+#[post("/shopping-lists", data = "<input>")]
+pub async fn post_shopping_list(
+    db: &State<Database>,
+    input: Json<ShoppingListInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<ShoppingList>, MyError> {
+    let input = input.into_inner();
+    if input.recipes.is_empty() {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some("A shopping list needs at least one recipe.".to_string()),
+        ));
+    }
+
+    let mut recipes = vec![];
+    let mut ingredients = vec![];
+    for entry in input.recipes.iter() {
+        let recipe_id = parse_id(&entry.recipe_id)
+            .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+        let recipe = match recipe::find_one_recipe(&db, recipe_id).await {
+            Ok(Some(recipe)) => recipe,
+            _ => {
+                return Err(MyError::build(
+                    Status::NotFound.code,
+                    Some(format!("Recipe not found with _id {}", &recipe_id)),
+                ))
+            }
+        };
+        let servings = entry.servings.unwrap_or(recipe.servings).max(1);
+        for ingredient in scaling::scaled_ingredients(&recipe, servings) {
+            ingredients.push((recipe_id.to_string(), ingredient));
+        }
+        recipes.push(ShoppingListRecipeDocument {
+            recipe_id,
+            servings,
+        });
+    }
+
+    let document = ShoppingListDocument {
+        _id: None,
+        user_id: user.0,
+        name: input
+            .name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or("Shopping list".to_string()),
+        recipes,
+        items: shopping::aggregate(&ingredients),
+        created_at: DateTime::now(),
+        updated_at: DateTime::now(),
+    };
+
+    let list_id = shopping_list::insert_shopping_list(&db, document)
+        .await
+        .map(|result| result.inserted_id.as_object_id())
+        .map_err(|_err| {
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("Shopping list not added.".to_string()),
+            )
+        })?
+        .ok_or({
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("No Object ID found!".to_string()),
+            )
+        })?;
+
+    match shopping_list::find_one_shopping_list(&db, list_id).await {
+        Ok(Some(list)) => Ok(Json(list)),
+        _ => Err(MyError::build(
+            Status::InternalServerError.code,
+            Some("Shopping list not added.".to_string()),
+        )),
+    }
+}
+
+#[openapi(tag = "Shopping List")]
+#[get("/shopping-lists?<limit>&<page>")]
+pub async fn get_shopping_lists(
+    db: &State<Database>,
+    limit: Option<i64>,
+    page: Option<i64>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<Vec<ShoppingList>>, MyError> {
+    // Setting default values
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+    match shopping_list::find_user_shopping_lists(&db, &user.0, limit, page).await {
+        Ok(lists) => Ok(Json(lists)),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
+
+#[openapi(tag = "Shopping List")]
+#[get("/shopping-list/<id>")]
+pub async fn get_shopping_list(
+    db: &State<Database>,
+    id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<ShoppingList>, MyError> {
+    find_own_list(&db, &id, &user).await.map(Json)
+}
+
+/// Checks an item off the list, or back on with `checked: false`.
+#[openapi(tag = "Shopping List")]
+#[put("/shopping-list/<id>/items/<item_id>", data = "<input>")]
+pub async fn check_shopping_list_item(
+    db: &State<Database>,
+    id: String,
+    item_id: String,
+    input: Json<ShoppingListCheckInput>,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<ShoppingList>, MyError> {
+    let existing = find_own_list(&db, &id, &user).await?;
+    let list_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    match shopping_list::set_item_checked(&db, list_id, &item_id, input.checked).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Item not found with id {}", &item_id)),
+            ))
+        }
+        Err(error) => {
+            return Err(MyError::build(
+                Status::InternalServerError.code,
+                Some(error.details),
+            ))
+        }
+    }
+
+    match shopping_list::find_one_shopping_list(&db, list_id).await {
+        Ok(Some(list)) => Ok(Json(list)),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Shopping list not found with _id {}", &list_id)),
+        )),
+    }
+}
+
+#[openapi(tag = "Shopping List")]
+#[delete("/shopping-list/<id>")]
+pub async fn delete_shopping_list(
+    db: &State<Database>,
+    id: String,
+    user: User,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let existing = find_own_list(&db, &id, &user).await?;
+    let list_id = parse_id(&existing._id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    match shopping_list::delete_one_shopping_list(&db, list_id).await {
+        Ok(res) if res.modified_count == 1 => Ok(Json("Shopping list successfully deleted!")),
+        Ok(_) => Err(MyError::build(
+            Status::NotFound.code,
+            Some("Not Found.".to_string()),
+        )),
+        Err(error) => {
+            println!("{:?}", error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some("Shopping list not deleted.".to_string()),
+            ))
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::{parse_id, recipe, taxonomy};
+use crate::errors::response::MyError;
+use crate::models::taxonomy::{TaxonomyTerm, TaxonomyTermInput, TermKind};
+use crate::request_guards::basic::{AdminKey, ApiKey};
+
+async fn check_slug_available(
+    db: &Database,
+    input: &TaxonomyTermInput,
+    own_id: Option<&String>,
+) -> Result<(), MyError> {
+    let slug = input.slug();
+    if slug.is_empty() {
+        return Err(MyError::build(
+            Status::BadRequest.code,
+            Some("Term name must contain letters or digits.".to_string()),
+        ));
+    }
+    match taxonomy::find_term_by_slug(db, input.kind, &slug).await {
+        Ok(Some(term)) if Some(&term._id) != own_id => Err(MyError::build(
+            Status::Conflict.code,
+            Some(format!("A {} with slug '{}' already exists.", input.kind, slug)),
+        )),
+        Ok(_) => Ok(()),
+        Err(error) => Err(MyError::build(
+            Status::InternalServerError.code,
+            Some(error.details),
+        )),
+    }
+}
+
+#[openapi(tag = "Taxonomy")]
+#[post("/taxonomy", data = "<input>")]
+pub async fn post_term(
+    db: &State<Database>,
+    input: Json<TaxonomyTermInput>,
+    _key: AdminKey,
+) -> Result<Json<TaxonomyTerm>, MyError> {
+    let input = input.into_inner();
+    check_slug_available(&db, &input, None).await?;
+
+    let id = taxonomy::insert_term(&db, input)
+        .await
+        .map(|result| result.inserted_id.as_object_id())
+        .map_err(|_err| {
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("Term not added.".to_string()),
+            )
+        })?
+        .ok_or({
+            MyError::build(
+                Status::InternalServerError.code,
+                Some("No Object ID found!".to_string()),
+            )
+        })?;
+
+    match taxonomy::find_one_term(&db, id).await {
+        Ok(Some(term)) => Ok(Json(term)),
+        _ => Err(MyError::build(
+            Status::InternalServerError.code,
+            Some("Term not added.".to_string()),
+        )),
+    }
+}
+
+#[openapi(tag = "Taxonomy")]
+#[get("/taxonomy?<kind>")]
+pub async fn get_terms(
+    db: &State<Database>,
+    kind: Option<TermKind>,
+    _key: ApiKey,
+) -> Result<Json<Vec<TaxonomyTerm>>, MyError> {
+    match taxonomy::find_terms(&db, kind).await {
+        Ok(terms) => Ok(Json(terms)),
+        Err(_error) => {
+            println!("{:?}", _error);
+            Err(MyError::build(Status::BadRequest.code, Some(_error.to_string())))
+        }
+    }
+}
+
+#[openapi(tag = "Taxonomy")]
+#[get("/taxonomy/<id>")]
+pub async fn get_term(
+    db: &State<Database>,
+    id: String,
+    _key: ApiKey,
+) -> Result<Json<TaxonomyTerm>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match taxonomy::find_one_term(&db, id).await {
+        Ok(Some(term)) => Ok(Json(term)),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Term not found with _id {}", &id)),
+        )),
+    }
+}
+
+#[openapi(tag = "Taxonomy")]
+#[put("/taxonomy/<id>", data = "<input>")]
+pub async fn update_term(
+    db: &State<Database>,
+    id: String,
+    input: Json<TaxonomyTermInput>,
+    _key: AdminKey,
+) -> Result<Json<TaxonomyTerm>, MyError> {
+    let input = input.into_inner();
+    check_slug_available(&db, &input, Some(&id)).await?;
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match taxonomy::update_term(&db, id, input).await {
+        Ok(Some(term)) => Ok(Json(term)),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Term not found with _id {}", &id)),
+        )),
+    }
+}
+
+/// Moves a term to the trash. It is removed from the recipes it was assigned
+/// to when purged.
+#[openapi(tag = "Taxonomy")]
+#[delete("/taxonomy/<id>")]
+pub async fn delete_term(
+    db: &State<Database>,
+    id: String,
+    _key: AdminKey,
+) -> Result<Json<&str>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match taxonomy::delete_one_term(&db, id).await {
+        Ok(res) if res.modified_count == 1 => Ok(Json("Term successfully deleted!")),
+        Ok(_) => Err(MyError::build(
+            Status::NotFound.code,
+            Some("Not Found.".to_string()),
+        )),
+        Err(error) => {
+            println!("{:?}", error);
+            Err(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("Term not found with _id {}", &id)),
+            ))
+        }
+    }
+}
+
+#[openapi(tag = "Taxonomy")]
+#[post("/recipe/<id>/terms/<term_id>")]
+pub async fn assign_term(
+    db: &State<Database>,
+    id: String,
+    term_id: String,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let term_id = parse_id(&term_id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match taxonomy::find_one_term(&db, term_id).await {
+        Ok(Some(_)) => {}
+        _ => {
+            return Err(MyError::build(
+                Status::NotFound.code,
+                Some(format!("Term not found with _id {}", &term_id)),
+            ))
+        }
+    }
+    match recipe::assign_term(&db, id, term_id).await {
+        Ok(true) => Ok(Json("Term successfully assigned!")),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe not found with _id {}", &id)),
+        )),
+    }
+}
+
+#[openapi(tag = "Taxonomy")]
+#[delete("/recipe/<id>/terms/<term_id>")]
+pub async fn unassign_term(
+    db: &State<Database>,
+    id: String,
+    term_id: String,
+    _key: ApiKey,
+) -> Result<Json<&str>, MyError> {
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let term_id = parse_id(&term_id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    match recipe::unassign_term(&db, id, term_id).await {
+        Ok(true) => Ok(Json("Term successfully unassigned!")),
+        _ => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe not found with _id {}", &id)),
+        )),
+    }
+}
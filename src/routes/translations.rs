@@ -0,0 +1,97 @@
+use mongodb::bson::doc;
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::{parse_id, recipe};
+use crate::errors::response::MyError;
+use crate::models::locale::MissingTranslations;
+use crate::request_guards::basic::{AdminKey, ApiKey};
+use crate::services::locale::{default_locale, missing_translations, normalize_tag, supported_locales};
+
+/// `lang` alone, or every locale of `SUPPORTED_LOCALES`.
+fn locales(lang: Option<String>) -> Result<Vec<String>, MyError> {
+    let default = default_locale();
+    match lang {
+        Some(lang) => {
+            let locale = normalize_tag(&lang).ok_or(MyError::build(
+                Status::BadRequest.code,
+                Some(format!("\"{}\" is not a language tag.", lang)),
+            ))?;
+            if locale == default {
+                return Err(MyError::build(
+                    Status::BadRequest.code,
+                    Some(format!("{} is the default locale, nothing is translated to it.", locale)),
+                ));
+            }
+            Ok(vec![locale])
+        }
+        None => Ok(supported_locales(&default)),
+    }
+}
+
+/// The fields of a recipe not yet translated, per supported locale or to
+/// `lang`. Locales with nothing missing are listed too. Recipes that aren't
+/// published need the `x-admin-key` header.
+#[openapi(tag = "Translation")]
+#[get("/recipe/<id>/translations?<lang>")]
+pub async fn get_recipe_translations(
+    db: &State<Database>,
+    id: String,
+    lang: Option<String>,
+    admin: Option<AdminKey>,
+    _key: ApiKey,
+) -> Result<Json<Vec<MissingTranslations>>, MyError> {
+    let locales = locales(lang)?;
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+
+    let found = match admin {
+        Some(_) => recipe::find_one_recipe(&db, id).await,
+        None => recipe::find_one_published_recipe(&db, id).await,
+    };
+    match found {
+        Ok(Some(recipe)) => Ok(Json(
+            locales
+                .iter()
+                .map(|locale| missing_translations(&recipe, locale))
+                .collect(),
+        )),
+        Ok(None) => Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("Recipe not found with _id {}", &id)),
+        )),
+        Err(err) => Err(MyError::build(Status::InternalServerError.code, Some(err.details))),
+    }
+}
+
+/// Recipes lacking translations, one entry per recipe and locale, for
+/// every supported locale or just `lang`. Pages count recipes, of any
+/// status.
+#[openapi(tag = "Translation")]
+#[get("/recipes/translations/missing?<lang>&<limit>&<page>")]
+pub async fn get_missing_translations(
+    db: &State<Database>,
+    lang: Option<String>,
+    limit: Option<i64>,
+    page: Option<i64>,
+    _key: AdminKey,
+) -> Result<Json<Vec<MissingTranslations>>, MyError> {
+    let locales = locales(lang)?;
+    // Setting default values
+    let limit: i64 = limit.unwrap_or(12);
+    let page: i64 = page.unwrap_or(1);
+
+    let recipes = recipe::find_recipes_missing_translations(&db, &locales, limit, page)
+        .await
+        .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+    Ok(Json(
+        recipes
+            .iter()
+            .flat_map(|recipe| locales.iter().map(move |locale| missing_translations(recipe, locale)))
+            .filter(|missing| !missing.missing.is_empty())
+            .collect(),
+    ))
+}
@@ -0,0 +1,89 @@
+use mongodb::bson::{DateTime, Document};
+use mongodb::Database;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+
+use crate::db::error::DbError;
+use crate::db::{parse_id, review, trash};
+use crate::errors::response::MyError;
+use crate::models::trash::{TrashItem, TrashKind, TRASH_KINDS};
+use crate::request_guards::basic::AdminKey;
+use crate::routes::reviews::refresh_rating;
+use crate::services::trash::{retention_days, to_item};
+
+/// Deleted resources that can still be restored, most recently deleted
+/// first, all kinds unless `kind` is given. They are purged for good after
+/// `TRASH_RETENTION_DAYS`.
+#[openapi(tag = "Trash")]
+#[get("/trash?<kind>&<limit>&<page>")]
+pub async fn get_trash(
+    db: &State<Database>,
+    kind: Option<TrashKind>,
+    limit: Option<i64>,
+    page: Option<i64>,
+    _key: AdminKey,
+) -> Result<Json<Vec<TrashItem>>, MyError> {
+    let limit = limit.unwrap_or(12).max(1) as usize;
+    let page = page.unwrap_or(1).max(1) as usize;
+    let kinds = match kind {
+        Some(kind) => vec![kind],
+        None => TRASH_KINDS.to_vec(),
+    };
+
+    let mut trashed: Vec<(TrashKind, Document)> = vec![];
+    for kind in kinds {
+        let documents = trash::find_trash(&db, kind)
+            .await
+            .map_err(|err| MyError::build(Status::InternalServerError.code, Some(err.details)))?;
+        trashed.extend(documents.into_iter().map(|document| (kind, document)));
+    }
+    let deleted_at = |document: &Document| document.get_datetime("deleted_at").ok().copied();
+    trashed.sort_by(|(_, a), (_, b)| deleted_at(b).cmp(&deleted_at(a)));
+
+    let retention = retention_days();
+    Ok(Json(
+        trashed
+            .iter()
+            .filter_map(|(kind, document)| to_item(*kind, document, retention))
+            .skip((page - 1) * limit)
+            .take(limit)
+            .collect(),
+    ))
+}
+
+/// Takes a deleted resource out of the trash, e.g. `POST /recipe/<id>/restore`.
+/// `kind` is one of the kinds listed in `GET /trash`.
+#[openapi(tag = "Trash")]
+#[post("/<kind>/<id>/restore")]
+pub async fn restore_from_trash(
+    db: &State<Database>,
+    kind: String,
+    id: String,
+    _key: AdminKey,
+) -> Result<Json<&str>, MyError> {
+    let kind = kind.parse::<TrashKind>().map_err(|_err| {
+        MyError::build(
+            Status::NotFound.code,
+            Some(format!("Nothing of kind {} goes to the trash.", kind)),
+        )
+    })?;
+    let id = parse_id(&id)
+        .map_err(|err| MyError::build(Status::BadRequest.code, Some(err.details)))?;
+    let internal = |err: DbError| MyError::build(Status::InternalServerError.code, Some(err.details));
+
+    if !trash::restore(&db, kind, id).await.map_err(internal)? {
+        return Err(MyError::build(
+            Status::NotFound.code,
+            Some(format!("No {} in the trash with _id {}", kind, &id)),
+        ));
+    }
+    // A restored review counts for the recipe's rating again.
+    if kind == TrashKind::review {
+        if let Some(restored) = review::find_one_review(&db, id).await.map_err(internal)? {
+            refresh_rating(&db, &restored.recipe_id).await?;
+        }
+    }
+    Ok(Json("Successfully restored!"))
+}
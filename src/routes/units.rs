@@ -0,0 +1,34 @@
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+use crate::errors::response::MyError;
+use crate::models::recipe::{Ingredient, IngredientUnit};
+use crate::request_guards::basic::ApiKey;
+use crate::services::units;
+
+/// Converts an amount of an ingredient to another unit. Converting between
+/// weight, volume and pieces uses the ingredient's density and piece weight.
+#[openapi(tag = "Units")]
+#[get("/units/convert?<amount>&<from>&<to>&<ingredient>")]
+pub async fn convert_units(
+    amount: f64,
+    from: IngredientUnit,
+    to: IngredientUnit,
+    ingredient: Option<String>,
+    _key: ApiKey,
+) -> Result<Json<Ingredient>, MyError> {
+    let name = ingredient.unwrap_or_default();
+    match units::convert(amount, from, to, &name) {
+        Some(converted) => Ok(Json(Ingredient {
+            name,
+            amount: units::round_amount(converted, to),
+            unit: to,
+            name_translations: Default::default(),
+        })),
+        None => Err(MyError::build(
+            Status::BadRequest.code,
+            Some(format!("Cannot convert {} to {} for '{}'.", from, to, name)),
+        )),
+    }
+}
@@ -0,0 +1,338 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use mongodb::bson::oid::ObjectId;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::models::catalog::{
+    CatalogAction, CatalogImportItem, CatalogImportReport, CatalogManifest, ConflictPolicy,
+    CATALOG_FORMAT, CATALOG_VERSION,
+};
+use crate::models::gif::RecipeStep;
+use crate::models::image::Image;
+use crate::models::recipe::Recipe;
+use crate::services::tar;
+
+const MANIFEST: &str = "manifest.json";
+const RECIPES: &str = "recipes.ndjson";
+const STEPS: &str = "steps.ndjson";
+const IMAGES: &str = "images.ndjson";
+const MEDIA_DIR: &str = "media/";
+
+/// The whole recipe catalog as stored in an archive: a manifest, one NDJSON
+/// file per kind of record and the media files. Media paths of the records
+/// name entries under `media/` once the files are in the archive. Archives
+/// read back only hold the names of their media files.
+#[derive(Debug, Clone)]
+pub struct CatalogArchive {
+    pub manifest: CatalogManifest,
+    pub recipes: Vec<Recipe>,
+    pub steps: Vec<RecipeStep>,
+    pub images: Vec<Image>,
+    pub media: Vec<(String, Vec<u8>)>,
+}
+
+/// Archive entry of the `index`th media file, keeping a safe version of its
+/// file name to make the archive easier to look through.
+pub fn media_entry(index: usize, path: &str) -> String {
+    let file_name: String = path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or("")
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '_' || *c == '-')
+        .take(64)
+        .collect();
+    format!("{}{}-{}", MEDIA_DIR, index, file_name)
+}
+
+fn is_remote(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+fn to_ndjson<T: Serialize>(records: &[T]) -> Result<Vec<u8>, String> {
+    let mut lines = vec![];
+    for record in records.iter() {
+        lines.extend(serde_json::to_vec(record).map_err(|err| err.to_string())?);
+        lines.push(b'\n');
+    }
+    Ok(lines)
+}
+
+fn from_ndjson<T: DeserializeOwned>(data: &[u8], file: &str) -> Result<Vec<T>, String> {
+    let text = std::str::from_utf8(data).map_err(|_| format!("{} is not UTF-8.", file))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .map_err(|err| format!("{} line {}: {}", file, index + 1, err))
+        })
+        .collect()
+}
+
+fn relink(path: &mut String, paths: &HashMap<String, String>) {
+    if let Some(new_path) = paths.get(path.as_str()) {
+        *path = new_path.clone();
+    }
+}
+
+fn relink_recipe(recipe: &mut Recipe, paths: &HashMap<String, String>) {
+    for image in recipe.images.iter_mut() {
+        relink(&mut image.path, paths);
+    }
+    for step in recipe.steps.iter_mut() {
+        relink(&mut step.gif.path, paths);
+    }
+}
+
+impl CatalogArchive {
+    pub fn new(
+        recipes: Vec<Recipe>,
+        steps: Vec<RecipeStep>,
+        images: Vec<Image>,
+        exported_at: String,
+    ) -> CatalogArchive {
+        CatalogArchive {
+            manifest: CatalogManifest {
+                format: CATALOG_FORMAT.to_string(),
+                version: CATALOG_VERSION,
+                exported_at,
+                recipes: recipes.len(),
+                steps: steps.len(),
+                images: images.len(),
+                media: 0,
+            },
+            recipes,
+            steps,
+            images,
+            media: vec![],
+        }
+    }
+
+    /// Local media files the records refer to, each once. Media that is only
+    /// a URL stays a URL.
+    pub fn media_paths(&self) -> Vec<String> {
+        let recipe_paths = self.recipes.iter().flat_map(|recipe| {
+            recipe
+                .images
+                .iter()
+                .map(|image| &image.path)
+                .chain(recipe.steps.iter().map(|step| &step.gif.path))
+        });
+        let paths = recipe_paths
+            .chain(self.steps.iter().map(|step| &step.gif.path))
+            .chain(self.images.iter().map(|image| &image.path));
+
+        let mut unique: Vec<String> = vec![];
+        for path in paths {
+            if !path.is_empty() && !is_remote(path) && !unique.contains(path) {
+                unique.push(path.clone());
+            }
+        }
+        unique
+    }
+
+    /// Adds a media file the records refer to by `path` and points them at
+    /// its entry instead.
+    pub fn add_media(&mut self, path: &str, data: Vec<u8>) {
+        let entry = media_entry(self.media.len(), path);
+        self.relink_media(&HashMap::from([(path.to_string(), entry.clone())]));
+        self.media.push((entry, data));
+        self.manifest.media = self.media.len();
+    }
+
+    /// Replaces media paths of every record found in `paths`.
+    pub fn relink_media(&mut self, paths: &HashMap<String, String>) {
+        for recipe in self.recipes.iter_mut() {
+            relink_recipe(recipe, paths);
+        }
+        for step in self.steps.iter_mut() {
+            relink(&mut step.gif.path, paths);
+        }
+        for image in self.images.iter_mut() {
+            relink(&mut image.path, paths);
+        }
+    }
+
+    /// The archive as a tar file, `mtime` being the export time in seconds.
+    pub fn to_bytes(&self, mtime: i64) -> Result<Vec<u8>, String> {
+        let mut entries = vec![
+            (
+                MANIFEST.to_string(),
+                serde_json::to_vec_pretty(&self.manifest).map_err(|err| err.to_string())?,
+            ),
+            (RECIPES.to_string(), to_ndjson(&self.recipes)?),
+            (STEPS.to_string(), to_ndjson(&self.steps)?),
+            (IMAGES.to_string(), to_ndjson(&self.images)?),
+        ];
+        entries.extend(self.media.iter().cloned());
+        tar::write(&entries, mtime)
+    }
+
+    /// Reads an archive written by `to_bytes` from `reader` as it goes,
+    /// refusing other formats and newer versions. Media files are listed
+    /// without their contents, `extract_media` copies them out.
+    pub fn read<R: Read>(reader: R) -> Result<CatalogArchive, String> {
+        let mut records = vec![];
+        let mut media = vec![];
+        tar::read_stream(reader, |name, data| {
+            if name.starts_with(MEDIA_DIR) {
+                media.push((name.to_string(), vec![]));
+            } else if [MANIFEST, RECIPES, STEPS, IMAGES].contains(&name) {
+                let mut contents = vec![];
+                data.read_to_end(&mut contents)
+                    .map_err(|err| format!("{}: {}", name, err))?;
+                records.push((name.to_string(), contents));
+            }
+            Ok(())
+        })?;
+
+        let file = |name: &str| {
+            records
+                .iter()
+                .find(|(entry, _)| entry == name)
+                .map(|(_, data)| data.as_slice())
+                .ok_or(format!("The archive has no {}.", name))
+        };
+
+        let manifest: CatalogManifest = serde_json::from_slice(file(MANIFEST)?)
+            .map_err(|err| format!("{}: {}", MANIFEST, err))?;
+        if manifest.format != CATALOG_FORMAT {
+            return Err(format!("The archive is not a {} archive.", CATALOG_FORMAT));
+        }
+        if manifest.version > CATALOG_VERSION {
+            return Err(format!(
+                "The archive is version {}, this server reads up to version {}.",
+                manifest.version, CATALOG_VERSION
+            ));
+        }
+
+        Ok(CatalogArchive {
+            recipes: from_ndjson(file(RECIPES)?, RECIPES)?,
+            steps: from_ndjson(file(STEPS)?, STEPS)?,
+            images: from_ndjson(file(IMAGES)?, IMAGES)?,
+            media,
+            manifest,
+        })
+    }
+
+    pub fn has_media(&self, entry: &str) -> bool {
+        self.media.iter().any(|(name, _)| name == entry)
+    }
+}
+
+/// Copies the media files of the archive in `reader` to the files `paths`
+/// gives for their entries, leaving the other entries out.
+pub fn extract_media<R: Read>(reader: R, paths: &HashMap<String, PathBuf>) -> Result<(), String> {
+    tar::read_stream(reader, |name, data| {
+        if let Some(path) = paths.get(name) {
+            let mut file = File::create(path)
+                .map_err(|err| format!("Writing {} failed: {}", name, err))?;
+            io::copy(data, &mut file).map_err(|err| format!("Writing {} failed: {}", name, err))?;
+        }
+        Ok(())
+    })
+}
+
+/// Decides what happens to each archived recipe. Recipes keep their id
+/// unless it is taken, by a stored recipe in `existing` or an earlier one
+/// of the archive, and `policy` is `duplicate`; ids that aren't ObjectIds
+/// are always replaced. Recipes in the trash, `trashed`, take their id as
+/// well, and overwriting one restores it.
+pub fn plan_import(
+    recipes: &[Recipe],
+    existing: &HashSet<String>,
+    trashed: &HashSet<String>,
+    policy: ConflictPolicy,
+) -> Vec<CatalogImportItem> {
+    let mut taken: HashSet<String> = existing.union(trashed).cloned().collect();
+    recipes
+        .iter()
+        .map(|recipe| {
+            let (action, target_id) = if ObjectId::parse_str(&recipe._id).is_err() {
+                (CatalogAction::create, ObjectId::new().to_string())
+            } else if !taken.contains(&recipe._id) {
+                (CatalogAction::create, recipe._id.clone())
+            } else {
+                match policy {
+                    ConflictPolicy::skip => (CatalogAction::skip, recipe._id.clone()),
+                    ConflictPolicy::overwrite if trashed.contains(&recipe._id) => {
+                        (CatalogAction::restore, recipe._id.clone())
+                    }
+                    ConflictPolicy::overwrite => (CatalogAction::overwrite, recipe._id.clone()),
+                    ConflictPolicy::duplicate => {
+                        (CatalogAction::duplicate, ObjectId::new().to_string())
+                    }
+                }
+            };
+            taken.insert(target_id.clone());
+            CatalogImportItem {
+                source_id: recipe._id.clone(),
+                target_id,
+                name: recipe.name.clone(),
+                action,
+            }
+        })
+        .collect()
+}
+
+/// Media entries of the archive the recipes refer to, each once.
+pub fn referenced_media<'a>(recipes: impl Iterator<Item = &'a Recipe>) -> Vec<String> {
+    let mut entries: Vec<String> = vec![];
+    for recipe in recipes {
+        let paths = recipe
+            .images
+            .iter()
+            .map(|image| &image.path)
+            .chain(recipe.steps.iter().map(|step| &step.gif.path));
+        for path in paths {
+            if path.starts_with(MEDIA_DIR) && !entries.contains(path) {
+                entries.push(path.clone());
+            }
+        }
+    }
+    entries
+}
+
+/// The archived recipe as it is written: under its target id and with the
+/// new ids of its images and steps from `ids`.
+pub fn remap_recipe(mut recipe: Recipe, target_id: &str, ids: &HashMap<String, String>) -> Recipe {
+    recipe._id = target_id.to_string();
+    for image in recipe.images.iter_mut() {
+        relink(&mut image._id, ids);
+    }
+    for step in recipe.steps.iter_mut() {
+        relink(&mut step._id, ids);
+    }
+    recipe.collections = vec![];
+    recipe
+}
+
+/// The report of an import, without the image, step and media counts.
+pub fn summarize(
+    items: Vec<CatalogImportItem>,
+    version: u32,
+    policy: ConflictPolicy,
+    dry_run: bool,
+) -> CatalogImportReport {
+    let count = |action: CatalogAction| items.iter().filter(|item| item.action == action).count();
+    CatalogImportReport {
+        dry_run,
+        version,
+        policy,
+        created: count(CatalogAction::create),
+        skipped: count(CatalogAction::skip),
+        overwritten: count(CatalogAction::overwrite),
+        duplicated: count(CatalogAction::duplicate),
+        restored: count(CatalogAction::restore),
+        recipes: items,
+        images: 0,
+        steps: 0,
+        media: 0,
+        missing_media: vec![],
+    }
+}
@@ -0,0 +1,29 @@
+/// Puts `id` at `position` of the collection, clamped to its end. A recipe
+/// already in the collection is moved rather than added twice.
+pub fn insert_at<T: PartialEq + Clone>(ids: &[T], id: T, position: Option<usize>) -> Vec<T> {
+    let mut ids: Vec<T> = ids.iter().filter(|existing| **existing != id).cloned().collect();
+    let position = position.unwrap_or(ids.len()).min(ids.len());
+    ids.insert(position, id);
+    ids
+}
+
+/// Checks that `requested` holds exactly the recipes of the collection,
+/// each once, and returns it as the new order.
+pub fn reorder<T: PartialEq + Clone>(current: &[T], requested: &[T]) -> Result<Vec<T>, String> {
+    if requested.len() != current.len() {
+        return Err(format!(
+            "Expected {} recipes, got {}.",
+            current.len(),
+            requested.len()
+        ));
+    }
+    for (index, id) in requested.iter().enumerate() {
+        if !current.contains(id) {
+            return Err("The order lists a recipe that is not in the collection.".to_string());
+        }
+        if requested[..index].contains(id) {
+            return Err("The order lists a recipe twice.".to_string());
+        }
+    }
+    Ok(requested.to_vec())
+}
@@ -0,0 +1,196 @@
+use mongodb::bson::DateTime;
+use rocket::tokio::sync::broadcast;
+
+use crate::models::cooking::{CookingEvent, CookingStep, CookingTimerDocument};
+use crate::models::gif::RecipeStep;
+use crate::models::recipe::Recipe;
+
+/// Events kept for devices that are slow to read them.
+const EVENT_CAPACITY: usize = 256;
+
+/// The realtime channel of cooking sessions, managed by Rocket. Every
+/// subscriber gets every event and keeps those of its session.
+#[derive(Clone)]
+pub struct CookingChannel(pub broadcast::Sender<CookingEvent>);
+
+impl CookingChannel {
+    pub fn new() -> CookingChannel {
+        let (sender, _receiver) = broadcast::channel(EVENT_CAPACITY);
+        CookingChannel(sender)
+    }
+
+    /// Pushes the event to the devices following its session, if any.
+    pub fn send(&self, event: CookingEvent) {
+        let _ = self.0.send(event);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Word(String),
+    /// `-` between the ends of a range
+    Dash,
+    /// Punctuation ending a phrase, so "10 minutes. 5 more" isn't 15 minutes
+    Break,
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            let mut number = c.to_string();
+            while let Some(next) = chars.peek().copied() {
+                let separator = matches!(next, '.' | ',' | '/');
+                if next.is_ascii_digit() || separator && !number.ends_with(['.', ',', '/']) {
+                    number.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let ends_with_separator = number.ends_with(['.', ',', '/']);
+            let number = number.trim_end_matches(['.', ',', '/']).replace(',', ".");
+            let value = match number.split_once('/') {
+                Some((numerator, denominator)) => match (numerator.parse::<f64>(), denominator.parse::<f64>()) {
+                    (Ok(numerator), Ok(denominator)) if denominator > 0.0 => Some(numerator / denominator),
+                    _ => None,
+                },
+                None => number.parse::<f64>().ok(),
+            };
+            if let Some(value) = value {
+                tokens.push(Token::Number(value));
+            }
+            if ends_with_separator {
+                tokens.push(Token::Break);
+            }
+        } else if c.is_alphabetic() {
+            let mut word = c.to_lowercase().to_string();
+            while let Some(next) = chars.peek().copied().filter(|next| next.is_alphabetic()) {
+                word.extend(next.to_lowercase());
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        } else if c == '-' || c == '–' {
+            tokens.push(Token::Dash);
+        } else if matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | '(' | ')') {
+            tokens.push(Token::Break);
+        }
+    }
+    tokens
+}
+
+/// Seconds in one of the unit.
+fn unit_seconds(word: &str) -> Option<f64> {
+    match word {
+        "s" | "sec" | "secs" | "second" | "seconds" => Some(1.0),
+        "min" | "mins" | "minute" | "minutes" => Some(60.0),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(3600.0),
+        _ => None,
+    }
+}
+
+fn is_word(token: Option<&Token>, words: &[&str]) -> bool {
+    matches!(token, Some(Token::Word(word)) if words.contains(&word.as_str()))
+}
+
+/// The amount starting at `index`, and the index after it. Of a range like
+/// "25-30" or "2 to 3" the lower end is taken, for the timer to go off when
+/// it's time to check.
+fn amount_at(tokens: &[Token], index: usize) -> Option<(f64, usize)> {
+    match tokens.get(index)? {
+        Token::Number(value) => {
+            let range = tokens.get(index + 1) == Some(&Token::Dash)
+                || is_word(tokens.get(index + 1), &["to", "or"]);
+            match (range, tokens.get(index + 2)) {
+                (true, Some(Token::Number(_))) => Some((*value, index + 3)),
+                _ => Some((*value, index + 1)),
+            }
+        }
+        Token::Word(word) if word == "a" || word == "an" => Some((1.0, index + 1)),
+        _ => None,
+    }
+}
+
+/// The duration starting at `index` in seconds, and the index after it.
+fn duration_at(tokens: &[Token], index: usize) -> Option<(f64, usize)> {
+    let (amount, next) = amount_at(tokens, index)?;
+    match tokens.get(next)? {
+        Token::Word(word) => unit_seconds(word).map(|seconds| (amount * seconds, next + 1)),
+        _ => None,
+    }
+}
+
+/// The first duration mentioned in an English step description, in seconds:
+/// "simmer 10 minutes", "bake for 25-30 min", "rest 1 hour and 15 minutes"
+/// or "leave for an hour".
+pub fn detect_duration(text: &str) -> Option<i32> {
+    let tokens = tokenize(text);
+    for index in 0..tokens.len() {
+        if let Some((mut seconds, mut next)) = duration_at(&tokens, index) {
+            loop {
+                let start = if is_word(tokens.get(next), &["and"]) { next + 1 } else { next };
+                match duration_at(&tokens, start) {
+                    Some((more, end)) => {
+                        seconds += more;
+                        next = end;
+                    }
+                    None => break,
+                }
+            }
+            return Some(seconds.round() as i32).filter(|seconds| *seconds > 0);
+        }
+    }
+    None
+}
+
+/// How long the step takes: its own `duration_in_seconds`, else the one in
+/// its description.
+pub fn step_duration(step: &RecipeStep) -> Option<i32> {
+    step.duration_in_seconds
+        .filter(|seconds| *seconds > 0)
+        .or_else(|| detect_duration(&step.description))
+}
+
+/// The step at `index` as shown while cooking, `None` past the last one.
+pub fn cooking_step(recipe: &Recipe, index: i32) -> Option<CookingStep> {
+    let step = recipe.steps.get(usize::try_from(index).ok()?)?;
+    Some(CookingStep {
+        index,
+        step_count: recipe.steps.len() as i32,
+        description: step.description.clone(),
+        gif: step.gif.clone(),
+        duration_in_seconds: step_duration(step),
+    })
+}
+
+/// A timer started at `now`, labelled after its step unless given a label.
+pub fn new_timer(
+    id: String,
+    step_index: i32,
+    label: Option<String>,
+    duration_in_seconds: i32,
+    now: DateTime,
+) -> CookingTimerDocument {
+    CookingTimerDocument {
+        id,
+        step_index,
+        label: label
+            .filter(|label| !label.trim().is_empty())
+            .unwrap_or(format!("Step {}", step_index + 1)),
+        duration_in_seconds,
+        started_at: now,
+        ends_at: DateTime::from_millis(now.timestamp_millis() + i64::from(duration_in_seconds) * 1000),
+        expired: false,
+    }
+}
+
+/// The timers that have run out by `now` and weren't pushed yet.
+pub fn due_timers(timers: &[CookingTimerDocument], now: DateTime) -> Vec<CookingTimerDocument> {
+    timers
+        .iter()
+        .filter(|timer| !timer.expired && timer.ends_at <= now)
+        .cloned()
+        .collect()
+}
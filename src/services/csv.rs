@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+/// A comma separated table with a header row. Blank lines and lines
+/// starting with `#` are skipped. Fields can't contain commas.
+pub struct Table<'a> {
+    columns: HashMap<&'a str, usize>,
+    /// Line number, counting from 1, and fields of every data row
+    pub rows: Vec<(usize, Vec<&'a str>)>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(csv: &'a str) -> Result<Table<'a>, String> {
+        let mut lines = csv
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+
+        let columns = match lines.next() {
+            Some((_, header)) => header
+                .split(',')
+                .enumerate()
+                .map(|(index, column)| (column.trim(), index))
+                .collect(),
+            None => return Err("Table is empty.".to_string()),
+        };
+        let rows = lines
+            .map(|(index, line)| (index + 1, line.split(',').map(|field| field.trim()).collect()))
+            .collect();
+        Ok(Table { columns, rows })
+    }
+
+    pub fn column(&self, name: &str) -> Result<usize, String> {
+        self.columns
+            .get(name)
+            .copied()
+            .ok_or(format!("Table has no '{}' column.", name))
+    }
+}
+
+/// The field at `index`, empty when the row is shorter.
+pub fn text<'a>(row: &[&'a str], index: usize) -> &'a str {
+    row.get(index).copied().unwrap_or_default()
+}
+
+/// The number at `index`, `None` when the field is empty.
+pub fn number(row: &[&str], index: usize, line: usize) -> Result<Option<f64>, String> {
+    match text(row, index) {
+        "" => Ok(None),
+        field => field
+            .parse::<f64>()
+            .map(Some)
+            .map_err(|_err| format!("Invalid number '{}' on line {}.", field, line)),
+    }
+}
@@ -0,0 +1,79 @@
+use std::env;
+
+use chrono::{Datelike, NaiveDate};
+use strum_macros::{Display, EnumString};
+
+use crate::models::recipe::Recipe;
+use crate::services::meal_planner::seed_from;
+
+/// Days before the recipe of the day may be picked again, unless
+/// `DAILY_RECIPE_WINDOW` says otherwise.
+const DEFAULT_WINDOW: i64 = 30;
+
+/// Seasons of the northern hemisphere. Recipes tagged with a taxonomy term
+/// whose slug is a season are only picked in that season.
+#[derive(Debug, Display, PartialEq, Eq, EnumString, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum Season {
+    spring,
+    summer,
+    #[strum(serialize = "autumn", serialize = "fall")]
+    autumn,
+    winter,
+}
+
+pub fn season_of(date: NaiveDate) -> Season {
+    match date.month() {
+        3..=5 => Season::spring,
+        6..=8 => Season::summer,
+        9..=11 => Season::autumn,
+        _ => Season::winter,
+    }
+}
+
+pub fn window() -> i64 {
+    env::var("DAILY_RECIPE_WINDOW")
+        .ok()
+        .and_then(|window| window.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_WINDOW)
+        .max(0)
+}
+
+/// Slug of the tag a recipe needs to be picked, from `DAILY_RECIPE_TAG`.
+pub fn required_tag() -> Option<String> {
+    env::var("DAILY_RECIPE_TAG")
+        .ok()
+        .filter(|tag| !tag.trim().is_empty())
+}
+
+/// A recipe that may be picked, with the seasons it is tagged with.
+/// No seasons means all year round.
+#[derive(Debug, Clone)]
+pub struct DailyCandidate {
+    pub recipe: Recipe,
+    pub seasons: Vec<Season>,
+}
+
+/// Picks the recipe of the day. The pick only depends on the date, the
+/// candidates and the recipes picked recently, never on who asks.
+/// Recipes in `recent` are skipped unless nothing else is in season.
+pub fn pick(date: NaiveDate, candidates: &[DailyCandidate], recent: &[String]) -> Option<Recipe> {
+    let season = season_of(date);
+    let mut in_season: Vec<&DailyCandidate> = candidates
+        .iter()
+        .filter(|candidate| candidate.seasons.is_empty() || candidate.seasons.contains(&season))
+        .collect();
+    in_season.sort_by(|a, b| a.recipe._id.cmp(&b.recipe._id));
+
+    let fresh: Vec<&DailyCandidate> = in_season
+        .iter()
+        .filter(|candidate| !recent.contains(&candidate.recipe._id))
+        .copied()
+        .collect();
+    let pool = if fresh.is_empty() { in_season } else { fresh };
+    if pool.is_empty() {
+        return None;
+    }
+    let index = (seed_from(&date.to_string()) % pool.len() as u64) as usize;
+    Some(pool[index].recipe.clone())
+}
@@ -0,0 +1,85 @@
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+use serde::Deserialize;
+
+use crate::models::dietary::{Allergen, DietaryInfo, DietaryOverrides, IngredientOrigin};
+use crate::models::recipe::Ingredient;
+use crate::services::names;
+
+/// Ingredient knowledge table shipped with the server.
+const KNOWLEDGE_TABLE: &str = include_str!("../../data/ingredients.json");
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct IngredientFacts {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub origin: IngredientOrigin,
+    #[serde(default)]
+    pub allergens: Vec<Allergen>,
+}
+
+impl IngredientFacts {
+    fn known_names(&self) -> Vec<&str> {
+        let mut known = vec![self.name.as_str()];
+        known.extend(self.aliases.iter().map(|alias| alias.as_str()));
+        known
+    }
+}
+
+/// The parsed knowledge table with all names normalized.
+pub fn knowledge() -> &'static Vec<IngredientFacts> {
+    static TABLE: OnceLock<Vec<IngredientFacts>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let table: Vec<IngredientFacts> =
+            serde_json::from_str(KNOWLEDGE_TABLE).expect("data/ingredients.json is invalid.");
+        table
+            .into_iter()
+            .map(|facts| IngredientFacts {
+                name: names::normalize(&facts.name),
+                aliases: facts.aliases.iter().map(|alias| names::normalize(alias)).collect(),
+                ..facts
+            })
+            .collect()
+    })
+}
+
+pub fn lookup(name: &str) -> Option<&'static IngredientFacts> {
+    names::find_best(name, knowledge(), |facts| facts.known_names())
+}
+
+/// Derives allergens and diets from the ingredients, then applies the editor's overrides.
+pub fn infer(ingredients: &[Ingredient], overrides: &DietaryOverrides) -> DietaryInfo {
+    let mut allergens: BTreeSet<Allergen> = BTreeSet::new();
+    let mut unknown_ingredients: Vec<String> = vec![];
+    let mut vegan = true;
+    let mut vegetarian = true;
+
+    for ingredient in ingredients {
+        match lookup(&ingredient.name) {
+            Some(facts) => {
+                allergens.extend(facts.allergens.iter().copied());
+                vegan &= facts.origin.is_vegan();
+                vegetarian &= facts.origin.is_vegetarian();
+            }
+            None => unknown_ingredients.push(ingredient.name.clone()),
+        }
+    }
+
+    allergens.extend(overrides.add_allergens.iter().copied());
+    for allergen in overrides.remove_allergens.iter() {
+        allergens.remove(allergen);
+    }
+
+    // Unknown ingredients could contain anything, so no diet is claimed for them.
+    let complete = unknown_ingredients.is_empty();
+    DietaryInfo {
+        vegan: overrides.vegan.unwrap_or(complete && vegan),
+        vegetarian: overrides.vegetarian.unwrap_or(complete && vegetarian),
+        gluten_free: overrides
+            .gluten_free
+            .unwrap_or(complete && !allergens.contains(&Allergen::gluten)),
+        allergens: allergens.into_iter().collect(),
+        unknown_ingredients,
+    }
+}
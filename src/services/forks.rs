@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use crate::models::recipe::{Recipe, RecipeStatus};
+
+/// A copy of `original` to store as its fork. Images and steps get the new
+/// ids in `ids`, keyed by their old ones, and keep their media paths so the
+/// files are shared. Likes, views and ratings start over and the fork is a
+/// draft.
+pub fn fork_recipe(original: &Recipe, ids: &HashMap<String, String>) -> Recipe {
+    let mut fork = original.clone();
+    for image in fork.images.iter_mut() {
+        if let Some(id) = ids.get(&image._id) {
+            image._id = id.clone();
+        }
+    }
+    for step in fork.steps.iter_mut() {
+        if let Some(id) = ids.get(&step._id) {
+            step._id = id.clone();
+        }
+    }
+    Recipe {
+        _id: "".to_string(),
+        num_of_likes: 0,
+        num_of_views: 0,
+        rating_average: 0.0,
+        rating_count: 0,
+        collections: vec![],
+        forked_from: Some(original._id.clone()),
+        status: RecipeStatus::draft,
+        publish_at: None,
+        ..fork
+    }
+}
@@ -0,0 +1,123 @@
+use serde_json::Value;
+
+use crate::models::recipe::Recipe;
+use crate::services::ingredient_parser;
+
+/// Page served for `GET /recipe/<id>` with `Accept: text/html`.
+const RECIPE_TEMPLATE: &str = include_str!("../../templates/recipe.html");
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fills the `{{key}}` placeholders of a template in one pass, so text
+/// that looks like a placeholder inside a value stays as it is. Values are
+/// inserted as given and must be escaped already.
+pub fn render(template: &str, values: &[(&str, String)]) -> String {
+    let mut page = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        page.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find("}}") {
+            Some(end) => end,
+            None => break,
+        };
+        let key = &rest[2..end];
+        match values.iter().find(|(name, _)| *name == key) {
+            Some((_, value)) => page.push_str(value),
+            None => page.push_str(&rest[..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+    page.push_str(rest);
+    page
+}
+
+/// The recipe as a standalone page, with its JSON-LD embedded for search
+/// engines. `json_ld` is what `jsonld::from_recipe` gives for the recipe.
+pub fn render_recipe(recipe: &Recipe, json_ld: &Value) -> String {
+    let image_url = json_ld["image"][0].as_str().map(|url| url.to_string());
+    // "</" would end the script element early.
+    let json_ld = json_ld.to_string().replace("</", "<\\/");
+    let per_serving = recipe.nutrition.per_serving(recipe.servings);
+    let nutrition = [
+        ("Calories", per_serving.calories, "kcal"),
+        ("Fat", per_serving.fat, "g"),
+        ("Carbohydrates", per_serving.carbs, "g"),
+        ("Fiber", per_serving.fiber, "g"),
+        ("Protein", per_serving.protein, "g"),
+    ];
+
+    render(
+        RECIPE_TEMPLATE,
+        &[
+            ("name", escape(&recipe.name)),
+            (
+                "description",
+                escape(&format!(
+                    "{}, {} servings, ready in {} minutes.",
+                    recipe.name, recipe.servings, recipe.preparation_time_in_minutes
+                )),
+            ),
+            (
+                "og_image",
+                image_url
+                    .as_ref()
+                    .map(|url| format!("<meta property=\"og:image\" content=\"{}\">", escape(url)))
+                    .unwrap_or_default(),
+            ),
+            (
+                "image",
+                image_url
+                    .as_ref()
+                    .map(|url| format!("<img src=\"{}\" alt=\"{}\">", escape(url), escape(&recipe.name)))
+                    .unwrap_or_default(),
+            ),
+            ("servings", recipe.servings.to_string()),
+            ("minutes", recipe.preparation_time_in_minutes.to_string()),
+            (
+                "ingredients",
+                recipe
+                    .ingredients
+                    .iter()
+                    .map(|ingredient| {
+                        format!("    <li>{}</li>", escape(&ingredient_parser::format_line(ingredient)))
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            ),
+            (
+                "steps",
+                recipe
+                    .steps
+                    .iter()
+                    .map(|step| format!("    <li>{}</li>", escape(&step.description)))
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            ),
+            (
+                "nutrition",
+                nutrition
+                    .iter()
+                    .map(|(label, amount, unit)| {
+                        format!("    <tr><td>{}</td><td>{} {}</td></tr>", label, amount, unit)
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            ),
+            ("json_ld", json_ld),
+        ],
+    )
+}
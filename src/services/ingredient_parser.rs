@@ -0,0 +1,322 @@
+use crate::models::ingredient_parser::ParsedIngredient;
+use crate::models::recipe::{Ingredient, IngredientUnit};
+
+/// Unit spellings, matched after lowercasing and dropping a trailing dot.
+/// Multi-word aliases come first so "fl oz" wins over "fl".
+const UNIT_ALIASES: &[(&str, IngredientUnit)] = &[
+    ("fluid ounces", IngredientUnit::fl_oz),
+    ("fluid ounce", IngredientUnit::fl_oz),
+    ("fl oz", IngredientUnit::fl_oz),
+    ("fl. oz", IngredientUnit::fl_oz),
+    ("floz", IngredientUnit::fl_oz),
+    ("cups", IngredientUnit::cup),
+    ("cup", IngredientUnit::cup),
+    ("c", IngredientUnit::cup),
+    ("tablespoons", IngredientUnit::tbsp),
+    ("tablespoon", IngredientUnit::tbsp),
+    ("tbsps", IngredientUnit::tbsp),
+    ("tbsp", IngredientUnit::tbsp),
+    ("tbs", IngredientUnit::tbsp),
+    ("tbl", IngredientUnit::tbsp),
+    ("teaspoons", IngredientUnit::tsp),
+    ("teaspoon", IngredientUnit::tsp),
+    ("tsps", IngredientUnit::tsp),
+    ("tsp", IngredientUnit::tsp),
+    ("grams", IngredientUnit::g),
+    ("gram", IngredientUnit::g),
+    ("gr", IngredientUnit::g),
+    ("g", IngredientUnit::g),
+    ("kilograms", IngredientUnit::kg),
+    ("kilogram", IngredientUnit::kg),
+    ("kilos", IngredientUnit::kg),
+    ("kilo", IngredientUnit::kg),
+    ("kg", IngredientUnit::kg),
+    ("milligrams", IngredientUnit::mg),
+    ("milligram", IngredientUnit::mg),
+    ("mg", IngredientUnit::mg),
+    ("millilitres", IngredientUnit::ml),
+    ("milliliters", IngredientUnit::ml),
+    ("millilitre", IngredientUnit::ml),
+    ("milliliter", IngredientUnit::ml),
+    ("ml", IngredientUnit::ml),
+    ("decilitres", IngredientUnit::dcl),
+    ("deciliters", IngredientUnit::dcl),
+    ("decilitre", IngredientUnit::dcl),
+    ("deciliter", IngredientUnit::dcl),
+    ("dcl", IngredientUnit::dcl),
+    ("dl", IngredientUnit::dcl),
+    ("litres", IngredientUnit::l),
+    ("liters", IngredientUnit::l),
+    ("litre", IngredientUnit::l),
+    ("liter", IngredientUnit::l),
+    ("l", IngredientUnit::l),
+    ("ounces", IngredientUnit::oz),
+    ("ounce", IngredientUnit::oz),
+    ("oz", IngredientUnit::oz),
+    ("pounds", IngredientUnit::lb),
+    ("pound", IngredientUnit::lb),
+    ("lbs", IngredientUnit::lb),
+    ("lb", IngredientUnit::lb),
+    ("pinches", IngredientUnit::pinch),
+    ("pinch", IngredientUnit::pinch),
+    ("cloves", IngredientUnit::clove),
+    ("clove", IngredientUnit::clove),
+    ("pieces", IngredientUnit::piece),
+    ("piece", IngredientUnit::piece),
+    ("pcs", IngredientUnit::piece),
+    ("pc", IngredientUnit::piece),
+];
+
+/// Phrases at the end of a line that are notes rather than part of the name.
+const TRAILING_NOTES: &[&str] = &["to taste", "optional", "to serve", "for garnish", "divided"];
+
+fn vulgar_fraction(c: char) -> Option<&'static str> {
+    let fraction = match c {
+        '½' => "1/2",
+        '⅓' => "1/3",
+        '⅔' => "2/3",
+        '¼' => "1/4",
+        '¾' => "3/4",
+        '⅕' => "1/5",
+        '⅖' => "2/5",
+        '⅗' => "3/5",
+        '⅘' => "4/5",
+        '⅙' => "1/6",
+        '⅚' => "5/6",
+        '⅛' => "1/8",
+        '⅜' => "3/8",
+        '⅝' => "5/8",
+        '⅞' => "7/8",
+        _ => return None,
+    };
+    Some(fraction)
+}
+
+/// Spells out unicode fractions and dashes so the rest of the parser deals
+/// with plain ASCII numbers: "1½" becomes "1 1/2", "2–3" becomes "2-3".
+fn normalize_line(line: &str) -> String {
+    let mut normalized = String::new();
+    for c in line.chars() {
+        match c {
+            '–' | '—' | '‒' => normalized.push('-'),
+            '⁄' => normalized.push('/'),
+            c => match vulgar_fraction(c) {
+                Some(fraction) => {
+                    normalized.push(' ');
+                    normalized.push_str(fraction);
+                }
+                None => normalized.push(c),
+            },
+        }
+    }
+    normalized
+        .trim()
+        .trim_start_matches(|c: char| c == '-' || c == '*' || c == '•' || c.is_whitespace())
+        .to_string()
+}
+
+/// Parses "2", "2.5", "2,5" or "1/2".
+fn parse_number(token: &str) -> Option<f64> {
+    if let Some((numerator, denominator)) = token.split_once('/') {
+        let numerator = numerator.parse::<f64>().ok()?;
+        let denominator = denominator.parse::<f64>().ok()?;
+        return if denominator == 0.0 { None } else { Some(numerator / denominator) };
+    }
+    if !token.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    token.replace(',', ".").parse::<f64>().ok()
+}
+
+/// Splits a token like "200g" or "1.5kg" into its number and the rest.
+fn split_attached_unit(token: &str) -> (&str, &str) {
+    let end = token
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ',' || c == '/'))
+        .unwrap_or(token.len());
+    (&token[..end], &token[end..])
+}
+
+fn unit_alias(words: &[&str]) -> Option<(IngredientUnit, usize)> {
+    // "T" and "t" are the usual shorthands for tablespoon and teaspoon.
+    match words.first().map(|word| word.trim_end_matches('.')) {
+        Some("T") => return Some((IngredientUnit::tbsp, 1)),
+        Some("t") => return Some((IngredientUnit::tsp, 1)),
+        _ => {}
+    }
+    for (alias, unit) in UNIT_ALIASES {
+        let length = alias.split(' ').count();
+        if words.len() < length {
+            continue;
+        }
+        let candidate = words[..length].join(" ").to_lowercase();
+        if candidate.trim_end_matches('.') == *alias {
+            return Some((*unit, length));
+        }
+    }
+    None
+}
+
+/// Reads a quantity like "2", "2 1/2", "2-3" or "2 to 3" from the start of
+/// `tokens`. Returns the amount, the upper end of a range and how many
+/// tokens were used.
+fn parse_quantity(tokens: &[String]) -> Option<(f64, Option<f64>, usize)> {
+    let mut amount = parse_number(tokens.first()?)?;
+    let mut used = 1;
+
+    // Mixed number, "2 1/2"
+    if let Some(fraction) = tokens.get(1).filter(|token| token.contains('/')) {
+        if let Some(value) = parse_number(fraction) {
+            amount += value;
+            used += 1;
+        }
+    }
+
+    // Range, "2-3", "2 - 3" or "2 to 3"
+    let separator = tokens.get(used).map(|token| token.to_lowercase());
+    if matches!(separator.as_deref(), Some("-") | Some("to") | Some("or")) {
+        if let Some(max) = tokens.get(used + 1).and_then(|token| parse_number(token)) {
+            let mut max = max;
+            let mut extra = 2;
+            if let Some(value) = tokens
+                .get(used + 2)
+                .filter(|token| token.contains('/'))
+                .and_then(|token| parse_number(token))
+            {
+                max += value;
+                extra += 1;
+            }
+            return Some((amount, Some(max), used + extra));
+        }
+    }
+    Some((amount, None, used))
+}
+
+/// Breaks the line into tokens, separating numbers from attached units
+/// ("200g") and range dashes ("2-3").
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    for word in text.split_whitespace() {
+        let starts_with_number = word.starts_with(|c: char| c.is_ascii_digit());
+        match word.split_once('-') {
+            Some((low, high))
+                if starts_with_number && !low.is_empty() && high.starts_with(|c: char| c.is_ascii_digit()) =>
+            {
+                tokens.push(low.to_string());
+                tokens.push("-".to_string());
+                let (number, unit) = split_attached_unit(high);
+                tokens.push(number.to_string());
+                if !unit.is_empty() {
+                    tokens.push(unit.to_string());
+                }
+            }
+            _ if starts_with_number => {
+                let (number, unit) = split_attached_unit(word);
+                tokens.push(number.to_string());
+                if !unit.is_empty() {
+                    tokens.push(unit.to_string());
+                }
+            }
+            _ => tokens.push(word.to_string()),
+        }
+    }
+    tokens
+}
+
+/// Parses a free-text ingredient line such as "2 1/2 cups all-purpose flour, sifted".
+pub fn parse_line(line: &str) -> ParsedIngredient {
+    let mut text = normalize_line(line);
+    let mut notes: Vec<String> = vec![];
+
+    // Parenthesised remarks, "(about 2 cups)"
+    while let (Some(start), Some(end)) = (text.find('('), text.find(')')) {
+        if end < start {
+            break;
+        }
+        let remark = text[start + 1..end].trim().to_string();
+        if !remark.is_empty() {
+            notes.push(remark);
+        }
+        text.replace_range(start..=end, " ");
+    }
+
+    // Preparation after the first comma, "…, finely chopped"
+    if let Some((head, tail)) = text.clone().split_once(',') {
+        if !tail.trim().is_empty() && !head.trim().chars().all(|c| c.is_ascii_digit()) {
+            notes.push(tail.trim().to_string());
+            text = head.to_string();
+        }
+    }
+
+    let tokens = tokenize(&text);
+    let (amount, amount_max, mut position) = match parse_quantity(&tokens) {
+        Some((amount, max, used)) => (Some(amount), max, used),
+        None => (None, None, 0),
+    };
+
+    let words: Vec<&str> = tokens[position..].iter().map(|token| token.as_str()).collect();
+    let unit = match unit_alias(&words) {
+        // A lone letter is only a unit when it follows an amount.
+        Some((unit, used)) if amount.is_some() || words[0].len() > 1 => {
+            position += used;
+            Some(unit)
+        }
+        _ => None,
+    };
+    if tokens.get(position).map(|token| token.to_lowercase()).as_deref() == Some("of") {
+        position += 1;
+    }
+
+    let mut name = tokens[position..].join(" ");
+    loop {
+        let lower = name.to_lowercase();
+        match TRAILING_NOTES.iter().find(|note| lower.ends_with(*note)) {
+            Some(note) => {
+                notes.push(note.to_string());
+                name.truncate(name.len() - note.len());
+                name = name.trim().trim_end_matches(',').trim().to_string();
+            }
+            None => break,
+        }
+    }
+
+    let mut parsed = ParsedIngredient {
+        input: line.to_string(),
+        name: name.trim().to_string(),
+        amount,
+        amount_max,
+        unit,
+        notes: if notes.is_empty() { None } else { Some(notes.join("; ")) },
+        ingredient: None,
+    };
+    parsed.ingredient = parsed.to_ingredient();
+    parsed
+}
+
+/// Parses every non-blank line of a pasted ingredient list.
+pub fn parse_lines(text: &str) -> Vec<ParsedIngredient> {
+    text.lines()
+        .filter(|line| !normalize_line(line).is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+/// An amount and unit as `parse_line` reads them, e.g. "1.5 cup". Pieces
+/// are left out, "2" rather than "2 piece".
+pub fn format_quantity(amount: f64, unit: IngredientUnit) -> String {
+    let amount = format!("{:.2}", amount);
+    let amount = amount.trim_end_matches('0').trim_end_matches('.');
+    match unit {
+        IngredientUnit::piece => amount.to_string(),
+        IngredientUnit::fl_oz => format!("{} fl oz", amount),
+        unit => format!("{} {}", amount, unit),
+    }
+}
+
+/// Writes an ingredient back as a line `parse_line` reads, e.g. "1.5 cup flour".
+pub fn format_line(ingredient: &Ingredient) -> String {
+    format!(
+        "{} {}",
+        format_quantity(ingredient.amount, ingredient.unit),
+        ingredient.name
+    )
+}
@@ -0,0 +1,507 @@
+use std::env;
+
+use serde_json::{json, Map, Value};
+
+use crate::models::gif::{Gif, RecipeStep};
+use crate::models::image::Image;
+use crate::models::import::ImportedRecipe;
+use crate::models::recipe::{Nutrition, Recipe, RecipeStatus};
+use crate::services::ingredient_parser;
+
+/// Recipe properties the importer reads. Everything else is reported.
+const MAPPED_FIELDS: [&str; 13] = [
+    "@context",
+    "@id",
+    "@type",
+    "name",
+    "recipeIngredient",
+    "ingredients",
+    "recipeInstructions",
+    "recipeYield",
+    "nutrition",
+    "totalTime",
+    "prepTime",
+    "cookTime",
+    "image",
+];
+
+/// Reads the JSON-LD documents of a request body, which is either JSON-LD
+/// itself or an HTML page embedding it in `application/ld+json` scripts.
+pub fn parse_documents(body: &str) -> Result<Vec<Value>, String> {
+    let trimmed = body.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return serde_json::from_str(trimmed)
+            .map(|document| vec![document])
+            .map_err(|err| format!("Invalid JSON-LD: {}", err));
+    }
+
+    let mut documents = vec![];
+    for script in ld_json_scripts(body) {
+        // Blogs routinely ship broken blocks next to the good one.
+        if let Ok(document) = serde_json::from_str(script.trim()) {
+            documents.push(document);
+        }
+    }
+    if documents.is_empty() {
+        return Err("No JSON-LD found in the document.".to_string());
+    }
+    Ok(documents)
+}
+
+/// Contents of the `<script type="application/ld+json">` elements of a page.
+fn ld_json_scripts(html: &str) -> Vec<&str> {
+    // Lowercasing ASCII keeps byte offsets, so they apply to `html` as well.
+    let lower = html.to_ascii_lowercase();
+    let mut scripts = vec![];
+    let mut position = 0;
+    while let Some(start) = lower[position..].find("<script").map(|at| at + position) {
+        let open_end = match lower[start..].find('>') {
+            Some(at) => start + at + 1,
+            None => break,
+        };
+        let close = match lower[open_end..].find("</script") {
+            Some(at) => open_end + at,
+            None => break,
+        };
+        if lower[start..open_end].contains("application/ld+json") {
+            scripts.push(&html[open_end..close]);
+        }
+        position = close;
+    }
+    scripts
+}
+
+fn is_recipe(object: &Map<String, Value>) -> bool {
+    let is_recipe_type = |value: &Value| value.as_str().is_some_and(|kind| kind.ends_with("Recipe"));
+    match object.get("@type") {
+        Some(Value::Array(types)) => types.iter().any(is_recipe_type),
+        Some(kind) => is_recipe_type(kind),
+        None => false,
+    }
+}
+
+/// The `Recipe` nodes of a document, looking into arrays, `@graph` and
+/// `mainEntity` the way blogs and SEO plugins nest them.
+pub fn find_recipes(document: &Value) -> Vec<&Map<String, Value>> {
+    match document {
+        Value::Array(items) => items.iter().flat_map(find_recipes).collect(),
+        Value::Object(object) if is_recipe(object) => vec![object],
+        Value::Object(object) => ["@graph", "mainEntity"]
+            .iter()
+            .filter_map(|key| object.get(*key))
+            .flat_map(find_recipes)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Minutes of an ISO 8601 duration such as `PT1H30M` or `P1DT2H`.
+/// Seconds are rounded up to the next minute.
+pub fn parse_duration(text: &str) -> Option<i32> {
+    let text = text.trim().to_ascii_uppercase();
+    let rest = text.strip_prefix('P')?;
+    let (date, time) = match rest.split_once('T') {
+        Some((date, time)) => (date, time),
+        None => (rest, ""),
+    };
+    if date.is_empty() && time.is_empty() {
+        return None;
+    }
+
+    let mut seconds = 0.0;
+    let date_units: &[(char, f64)] = &[('W', 604800.0), ('D', 86400.0)];
+    let time_units: &[(char, f64)] = &[('H', 3600.0), ('M', 60.0), ('S', 1.0)];
+    for (part, units) in [(date, date_units), (time, time_units)] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() || c == '.' || c == ',' {
+                number.push(if c == ',' { '.' } else { c });
+                continue;
+            }
+            let (_, factor) = units.iter().find(|(unit, _)| *unit == c)?;
+            seconds += number.parse::<f64>().ok()? * factor;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    Some((seconds / 60.0).ceil() as i32)
+}
+
+/// Strips markup and the common entities from text blogs put in JSON-LD.
+pub fn clean_text(text: &str) -> String {
+    let mut plain = String::new();
+    let mut tag: Option<String> = None;
+    for c in text.chars() {
+        match (&mut tag, c) {
+            (None, '<') => tag = Some(String::new()),
+            (Some(name), '>') => {
+                // Block elements separate words, inline ones like <b> don't.
+                let name = name.trim_start_matches('/').to_ascii_lowercase();
+                if ["br", "p", "li", "div"].iter().any(|block| name.starts_with(block)) {
+                    plain.push(' ');
+                }
+                tag = None;
+            }
+            (Some(name), _) => name.push(c),
+            (None, _) => plain.push(c),
+        }
+    }
+
+    let mut decoded = String::new();
+    let mut rest = plain.as_str();
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').filter(|end| *end <= 10).map(|end| &rest[1..end]);
+        let replacement = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (entity, replacement) {
+            (Some(entity), Some(replacement)) => {
+                decoded.push(replacement);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// The first number in a value like `4`, `"240 kcal"` or `"9,5 g"`.
+fn number_in(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => {
+            let start = text.find(|c: char| c.is_ascii_digit())?;
+            let number: String = text[start..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+                .map(|c| if c == ',' { '.' } else { c })
+                .collect();
+            number.trim_end_matches('.').parse().ok()
+        }
+        Value::Array(items) => items.iter().find_map(number_in),
+        Value::Object(object) => object.get("value").and_then(number_in),
+        _ => None,
+    }
+}
+
+fn text_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(clean_text(text)).filter(|text| !text.is_empty()),
+        Value::Array(items) => items.iter().find_map(text_of),
+        _ => None,
+    }
+}
+
+fn images_of(value: &Value, title: &str) -> Vec<Image> {
+    let image = |path: &str, width: Option<&Value>, height: Option<&Value>, caption: Option<&Value>| Image {
+        _id: "".to_string(),
+        path: path.to_string(),
+        width: width.and_then(number_in).unwrap_or(0.0) as i32,
+        height: height.and_then(number_in).unwrap_or(0.0) as i32,
+        title: caption.and_then(text_of).unwrap_or(title.to_string()),
+        created_at: "".to_string(),
+    };
+    match value {
+        Value::String(url) => vec![image(url, None, None, None)],
+        Value::Array(items) => items.iter().flat_map(|item| images_of(item, title)).collect(),
+        Value::Object(object) => object
+            .get("url")
+            .or_else(|| object.get("contentUrl"))
+            .and_then(|url| url.as_str())
+            .map(|url| {
+                vec![image(
+                    url,
+                    object.get("width"),
+                    object.get("height"),
+                    object.get("caption").or_else(|| object.get("name")),
+                )]
+            })
+            .unwrap_or_default(),
+        _ => vec![],
+    }
+}
+
+fn step(description: String, image: Option<&Value>) -> RecipeStep {
+    let path = image
+        .map(|image| images_of(image, ""))
+        .and_then(|images| images.into_iter().next())
+        .map(|image| image.path)
+        .unwrap_or_default();
+    RecipeStep {
+        _id: "".to_string(),
+        description,
+        description_translations: Default::default(),
+        duration_in_seconds: None,
+        gif: Gif {
+            path,
+            width: 0,
+            height: 0,
+            title: "".to_string(),
+        },
+        created_at: "".to_string(),
+    }
+}
+
+/// Steps from plain text, lists of text, `HowToStep`s and `HowToSection`s.
+fn steps_of(value: &Value) -> Vec<RecipeStep> {
+    match value {
+        Value::String(text) => text
+            .split('\n')
+            .map(clean_text)
+            .filter(|line| !line.is_empty())
+            .map(|line| step(line, None))
+            .collect(),
+        Value::Array(items) => items.iter().flat_map(steps_of).collect(),
+        Value::Object(object) => match object.get("itemListElement") {
+            Some(items) => steps_of(items),
+            None => object
+                .get("text")
+                .or_else(|| object.get("name"))
+                .and_then(text_of)
+                .map(|text| vec![step(text, object.get("image"))])
+                .unwrap_or_default(),
+        },
+        _ => vec![],
+    }
+}
+
+/// Nutrition of the whole recipe from the per serving `NutritionInformation`.
+fn nutrition_of(value: &Value, servings: i32, unmapped: &mut Vec<String>) -> Nutrition {
+    let mut per_serving = Nutrition::default();
+    let object = match value.as_object() {
+        Some(object) => object,
+        None => {
+            unmapped.push("nutrition".to_string());
+            return per_serving;
+        }
+    };
+    for (key, amount) in object.iter() {
+        let field = match key.as_str() {
+            "@type" => continue,
+            "calories" => &mut per_serving.calories,
+            "fatContent" => &mut per_serving.fat,
+            "carbohydrateContent" => &mut per_serving.carbs,
+            "fiberContent" => &mut per_serving.fiber,
+            "proteinContent" => &mut per_serving.protein,
+            _ => {
+                unmapped.push(format!("nutrition.{}", key));
+                continue;
+            }
+        };
+        match number_in(amount) {
+            Some(amount) => *field = amount.round() as i32,
+            None => unmapped.push(format!("nutrition.{}", key)),
+        }
+    }
+    per_serving.scaled(servings as f64)
+}
+
+/// Maps a schema.org `Recipe` onto a recipe. Ingredient lines are parsed
+/// like `POST /ingredients/parse` does; lines without a quantity are
+/// reported instead of guessed.
+pub fn to_recipe(object: &Map<String, Value>) -> Result<ImportedRecipe, String> {
+    let name = object
+        .get("name")
+        .and_then(text_of)
+        .ok_or("The recipe has no name.".to_string())?;
+    let mut unmapped: Vec<String> = object
+        .keys()
+        .filter(|key| !MAPPED_FIELDS.contains(&key.as_str()))
+        .cloned()
+        .collect();
+
+    let servings = match object.get("recipeYield") {
+        Some(value) => match number_in(value) {
+            Some(servings) if servings >= 1.0 => servings.round() as i32,
+            _ => {
+                unmapped.push("recipeYield".to_string());
+                1
+            }
+        },
+        None => 1,
+    };
+
+    let duration = |key: &str, unmapped: &mut Vec<String>| -> Option<i32> {
+        let text = object.get(key)?.as_str();
+        let minutes = text.and_then(parse_duration);
+        if minutes.is_none() {
+            unmapped.push(key.to_string());
+        }
+        minutes
+    };
+    let total = duration("totalTime", &mut unmapped);
+    let parts = [
+        duration("prepTime", &mut unmapped),
+        duration("cookTime", &mut unmapped),
+    ];
+    let preparation_time_in_minutes = total.unwrap_or(parts.iter().flatten().sum());
+
+    let mut ingredients = vec![];
+    let lines = object
+        .get("recipeIngredient")
+        .or_else(|| object.get("ingredients"));
+    let lines: Vec<&Value> = match lines {
+        Some(Value::Array(lines)) => lines.iter().collect(),
+        Some(line) => vec![line],
+        None => vec![],
+    };
+    for line in lines {
+        let text = match text_of(line) {
+            Some(text) => text,
+            None => continue,
+        };
+        match ingredient_parser::parse_line(&text).ingredient {
+            Some(ingredient) => ingredients.push(ingredient),
+            None => unmapped.push(format!("recipeIngredient: {}", text)),
+        }
+    }
+
+    let nutrition = match object.get("nutrition") {
+        Some(value) => nutrition_of(value, servings, &mut unmapped),
+        None => Nutrition::default(),
+    };
+
+    let recipe = Recipe {
+        _id: "".to_string(),
+        images: object
+            .get("image")
+            .map(|image| images_of(image, &name))
+            .unwrap_or_default(),
+        name,
+        name_translations: Default::default(),
+        preparation_time_in_minutes,
+        servings,
+        nutrition_per_serving: nutrition.per_serving(servings),
+        nutrition,
+        num_of_likes: 0,
+        num_of_views: 0,
+        rating_average: 0.0,
+        rating_count: 0,
+        ingredients,
+        steps: object.get("recipeInstructions").map(steps_of).unwrap_or_default(),
+        term_ids: vec![],
+        dietary: Default::default(),
+        dietary_overrides: Default::default(),
+        collections: vec![],
+        forked_from: None,
+        localized: None,
+        status: RecipeStatus::draft,
+        publish_at: None,
+        created_at: "".to_string(),
+    };
+    Ok(ImportedRecipe { recipe, unmapped })
+}
+
+/// Where the API is reachable from outside, from `PUBLIC_BASE_URL`, so
+/// exported media URLs are absolute. Relative URLs when it is missing.
+pub fn public_base_url() -> String {
+    env::var("PUBLIC_BASE_URL")
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .unwrap_or_default()
+}
+
+/// URL of uploaded media, served without an API key at `/media/<route>/<id>`
+/// while the recipe is published. Imported media keeps the URL it was
+/// imported with.
+pub fn media_url(path: &str, route: &str, id: &str, base_url: &str) -> Option<String> {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        Some(path.to_string())
+    } else if path.is_empty() || id.is_empty() {
+        None
+    } else {
+        Some(format!("{}/media/{}/{}", base_url, route, id))
+    }
+}
+
+/// Minutes as an ISO 8601 duration, `PT1H30M`.
+pub fn format_duration(minutes: i32) -> String {
+    let minutes = minutes.max(0);
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("PT{}M", minutes),
+        (hours, 0) => format!("PT{}H", hours),
+        (hours, minutes) => format!("PT{}H{}M", hours, minutes),
+    }
+}
+
+/// The recipe as a schema.org `Recipe`. Nutrition is given per serving as
+/// schema.org expects.
+pub fn from_recipe(recipe: &Recipe, base_url: &str) -> Value {
+    let per_serving = recipe.nutrition.per_serving(recipe.servings);
+    let images: Vec<String> = recipe
+        .images
+        .iter()
+        .filter_map(|image| media_url(&image.path, "image", &image._id, base_url))
+        .collect();
+    let steps: Vec<Value> = recipe
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            let mut how_to = json!({
+                "@type": "HowToStep",
+                "position": index + 1,
+                "text": step.description,
+            });
+            if let Some(url) = media_url(&step.gif.path, "gif", &step._id, base_url) {
+                how_to["image"] = json!(url);
+            }
+            how_to
+        })
+        .collect();
+
+    let mut document = json!({
+        "@context": "https://schema.org",
+        "@type": "Recipe",
+        "name": recipe.name,
+        "image": images,
+        "recipeYield": format!("{} servings", recipe.servings),
+        "totalTime": format_duration(recipe.preparation_time_in_minutes),
+        "recipeIngredient": recipe
+            .ingredients
+            .iter()
+            .map(ingredient_parser::format_line)
+            .collect::<Vec<String>>(),
+        "recipeInstructions": steps,
+        "nutrition": {
+            "@type": "NutritionInformation",
+            "calories": format!("{} kcal", per_serving.calories),
+            "fatContent": format!("{} g", per_serving.fat),
+            "carbohydrateContent": format!("{} g", per_serving.carbs),
+            "fiberContent": format!("{} g", per_serving.fiber),
+            "proteinContent": format!("{} g", per_serving.protein),
+        },
+    });
+    if let Some(localized) = recipe.localized.as_ref() {
+        document["inLanguage"] = json!(localized.locale);
+    }
+    if !recipe._id.is_empty() {
+        document["@id"] = json!(format!("{}/recipe/{}", base_url, recipe._id));
+    }
+    if recipe.rating_count > 0 {
+        document["aggregateRating"] = json!({
+            "@type": "AggregateRating",
+            "ratingValue": (recipe.rating_average * 10.0).round() / 10.0,
+            "ratingCount": recipe.rating_count,
+        });
+    }
+    document
+}
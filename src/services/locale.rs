@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::env;
+
+use crate::models::locale::{LocalizedRecipe, MissingTranslations};
+use crate::models::recipe::Recipe;
+
+const DEFAULT_LOCALE: &str = "en";
+
+/// A language tag in lowercase with `-` between its parts, so "pt_BR" and
+/// "pt-br" are the same locale. `None` if it isn't a tag.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace('_', "-").to_lowercase();
+    let mut parts = tag.split('-');
+    let language = parts.next()?;
+    if language.len() < 2 || language.len() > 8 || !language.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+    if !parts.all(|part| !part.is_empty() && part.len() <= 8 && part.chars().all(|c| c.is_ascii_alphanumeric())) {
+        return None;
+    }
+    Some(tag)
+}
+
+/// The locale of the untranslated fields, from `DEFAULT_LOCALE`.
+pub fn default_locale() -> String {
+    env::var("DEFAULT_LOCALE")
+        .ok()
+        .and_then(|tag| normalize_tag(&tag))
+        .unwrap_or(DEFAULT_LOCALE.to_string())
+}
+
+/// The locales recipes are translated to, from the comma separated
+/// `SUPPORTED_LOCALES`, without the default one.
+pub fn supported_locales(default: &str) -> Vec<String> {
+    let mut locales: Vec<String> = vec![];
+    for tag in env::var("SUPPORTED_LOCALES").unwrap_or_default().split(',') {
+        if let Some(tag) = normalize_tag(tag) {
+            if tag != default && !locales.contains(&tag) {
+                locales.push(tag);
+            }
+        }
+    }
+    locales
+}
+
+/// The tags of an `Accept-Language` header by decreasing quality, in the
+/// order given for equal ones. `*`, `q=0` and malformed entries are dropped.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut weighted: Vec<(String, f64)> = vec![];
+    for entry in header.split(',') {
+        let mut parts = entry.split(';');
+        let tag = match parts.next().and_then(normalize_tag) {
+            Some(tag) => tag,
+            None => continue,
+        };
+        let mut quality = 1.0;
+        for parameter in parts {
+            if let Some(value) = parameter.trim().strip_prefix("q=") {
+                quality = value.trim().parse::<f64>().unwrap_or(0.0);
+            }
+        }
+        if quality > 0.0 {
+            weighted.push((tag, quality));
+        }
+    }
+    weighted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    weighted.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// The locales to look for a translation in: each one asked for, followed
+/// by its language alone ("de-at" then "de") unless asked for later, and
+/// the default locale last.
+pub fn fallback_chain(asked: &[String], default: &str) -> Vec<String> {
+    let mut chain: Vec<String> = vec![];
+    let mut push = |tag: &str| {
+        if !chain.iter().any(|known| known == tag) {
+            chain.push(tag.to_string());
+        }
+    };
+    for (index, tag) in asked.iter().enumerate() {
+        push(tag);
+        if let Some((language, _)) = tag.split_once('-') {
+            if !asked[index + 1..].iter().any(|later| later == language) {
+                push(language);
+            }
+        }
+    }
+    push(default);
+    chain
+}
+
+/// The text for the first locale of `chain` it has, with that locale.
+/// `text` itself is in the `default` locale and used when nothing matches.
+fn pick<'a>(
+    text: &'a str,
+    translations: &'a BTreeMap<String, String>,
+    chain: &'a [String],
+    default: &'a str,
+) -> (&'a str, &'a str) {
+    for locale in chain.iter() {
+        if locale == default {
+            break;
+        }
+        if let Some(translation) = translations.get(locale).filter(|text| !text.trim().is_empty()) {
+            return (translation, locale);
+        }
+    }
+    (text, default)
+}
+
+/// The name, ingredient names and step descriptions of the recipe in the
+/// first locale of `chain` each is translated to.
+pub fn localized_text(recipe: &Recipe, chain: &[String], default: &str) -> LocalizedRecipe {
+    let (name, locale) = pick(&recipe.name, &recipe.name_translations, chain, default);
+    LocalizedRecipe {
+        locale: locale.to_string(),
+        name: name.to_string(),
+        ingredient_names: recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| pick(&ingredient.name, &ingredient.name_translations, chain, default).0.to_string())
+            .collect(),
+        step_descriptions: recipe
+            .steps
+            .iter()
+            .map(|step| pick(&step.description, &step.description_translations, chain, default).0.to_string())
+            .collect(),
+    }
+}
+
+/// The recipe as stored with `localized` set, for JSON that may be sent
+/// back on an update.
+pub fn with_localized_text(mut recipe: Recipe, chain: &[String], default: &str) -> Recipe {
+    recipe.localized = Some(localized_text(&recipe, chain, default));
+    recipe
+}
+
+/// The recipe with its name, ingredient names and step descriptions
+/// replaced by their `localized_text`, for pages, cards and JSON-LD that
+/// are only read.
+pub fn localize_recipe(mut recipe: Recipe, chain: &[String], default: &str) -> Recipe {
+    let localized = localized_text(&recipe, chain, default);
+    recipe.name = localized.name.clone();
+    for (ingredient, name) in recipe.ingredients.iter_mut().zip(localized.ingredient_names.iter()) {
+        ingredient.name = name.clone();
+    }
+    for (step, description) in recipe.steps.iter_mut().zip(localized.step_descriptions.iter()) {
+        step.description = description.clone();
+    }
+    recipe.localized = Some(localized);
+    recipe
+}
+
+/// Re-keys the translations by normalized tag and drops empty ones.
+fn normalize_map(
+    translations: &BTreeMap<String, String>,
+    default: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut normalized = BTreeMap::new();
+    for (tag, text) in translations.iter() {
+        let locale = normalize_tag(tag).ok_or(format!("\"{}\" is not a language tag.", tag))?;
+        if locale == default {
+            return Err(format!(
+                "There are no translations to the default locale {}, it is the field itself.",
+                default
+            ));
+        }
+        if !text.trim().is_empty() {
+            normalized.insert(locale, text.trim().to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+/// Checks the tags of every translation of the recipe and normalizes them,
+/// so each locale is stored under one key.
+pub fn normalize_translations(mut recipe: Recipe, default: &str) -> Result<Recipe, String> {
+    recipe.name_translations = normalize_map(&recipe.name_translations, default)?;
+    for ingredient in recipe.ingredients.iter_mut() {
+        ingredient.name_translations = normalize_map(&ingredient.name_translations, default)?;
+    }
+    for step in recipe.steps.iter_mut() {
+        step.description_translations = normalize_map(&step.description_translations, default)?;
+    }
+    Ok(recipe)
+}
+
+/// The fields of the recipe with text but no translation to `locale`, like
+/// "name", "ingredients[2].name" or "steps[0].description".
+pub fn missing_translations(recipe: &Recipe, locale: &str) -> MissingTranslations {
+    let lacks = |text: &str, translations: &BTreeMap<String, String>| {
+        !text.trim().is_empty() && translations.get(locale).filter(|text| !text.trim().is_empty()).is_none()
+    };
+    let mut missing = vec![];
+    if lacks(&recipe.name, &recipe.name_translations) {
+        missing.push("name".to_string());
+    }
+    for (index, ingredient) in recipe.ingredients.iter().enumerate() {
+        if lacks(&ingredient.name, &ingredient.name_translations) {
+            missing.push(format!("ingredients[{}].name", index));
+        }
+    }
+    for (index, step) in recipe.steps.iter().enumerate() {
+        if lacks(&step.description, &step.description_translations) {
+            missing.push(format!("steps[{}].description", index));
+        }
+    }
+    MissingTranslations {
+        recipe_id: recipe._id.clone(),
+        name: recipe.name.clone(),
+        locale: locale.to_string(),
+        missing,
+    }
+}
@@ -0,0 +1,346 @@
+use crate::models::gif::{Gif, RecipeStep};
+use crate::models::image::Image;
+use crate::models::import::ImportedRecipe;
+use crate::models::recipe::{Nutrition, Recipe, RecipeStatus};
+use crate::services::ingredient_parser;
+use crate::services::jsonld::{media_url, parse_duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    /// Before the first `##` heading
+    Intro,
+    Ingredients,
+    Steps,
+    Other,
+}
+
+/// A value of the front matter, without the quotes it may be written in.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// A value written so `unquote` reads it back unchanged.
+fn quote(value: &str) -> String {
+    if value != value.trim() || unquote(value) != value {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Minutes as a bare number, "1 h 30 min" or an ISO 8601 duration.
+fn parse_minutes(text: &str) -> Option<i32> {
+    if let Ok(minutes) = text.parse::<i32>() {
+        return Some(minutes).filter(|minutes| *minutes >= 0);
+    }
+    if let Some(minutes) = parse_duration(text) {
+        return Some(minutes);
+    }
+    let mut minutes = 0.0;
+    let mut number: Option<f64> = None;
+    for token in text.split_whitespace() {
+        match (token.parse::<f64>(), number) {
+            (Ok(value), None) => number = Some(value),
+            (Err(_), Some(value)) => {
+                let factor = match token.to_lowercase().trim_end_matches('.') {
+                    "h" | "hr" | "hrs" | "hour" | "hours" => 60.0,
+                    "m" | "min" | "mins" | "minute" | "minutes" => 1.0,
+                    _ => return None,
+                };
+                minutes += value * factor;
+                number = None;
+            }
+            _ => return None,
+        }
+    }
+    if number.is_some() || minutes == 0.0 && !text.trim().starts_with('0') {
+        return None;
+    }
+    Some(minutes.round() as i32)
+}
+
+/// `![alt](path)` on its own, as (alt, path).
+fn image_link(line: &str) -> Option<(String, String)> {
+    let rest = line.trim().strip_prefix("![")?;
+    let (alt, rest) = rest.split_once("](")?;
+    let path = rest.strip_suffix(')')?;
+    Some((alt.to_string(), path.trim().to_string()))
+}
+
+/// The text of an ordered list item like "2. Stir" or "2) Stir".
+fn ordered_item(line: &str) -> Option<&str> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let rest = &line[digits..];
+    let rest = rest.strip_prefix('.').or_else(|| rest.strip_prefix(')'))?;
+    rest.strip_prefix(' ').map(str::trim)
+}
+
+fn bullet_item(line: &str) -> Option<&str> {
+    ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+        .map(str::trim)
+}
+
+fn step(description: String) -> RecipeStep {
+    RecipeStep {
+        _id: "".to_string(),
+        description,
+        description_translations: Default::default(),
+        duration_in_seconds: None,
+        gif: Gif {
+            path: "".to_string(),
+            width: 0,
+            height: 0,
+            title: "".to_string(),
+        },
+        created_at: "".to_string(),
+    }
+}
+
+/// Reads a recipe written in the editorial Markdown format:
+///
+/// ```text
+/// ---
+/// name: Tomato soup
+/// servings: 4
+/// time: 45
+/// calories: 1200
+/// image: /media/tomato-soup.jpg
+/// ---
+///
+/// # Tomato soup
+///
+/// ## Ingredients
+///
+/// - 800 g tomatoes
+/// - 1 tsp salt
+///
+/// ## Steps
+///
+/// 1. Roast the tomatoes.
+///    ![roasting](/media/roasting.gif)
+/// 2. Blend with the salt.
+/// ```
+///
+/// The front matter is optional, only a `name` (or `title`, else the `#`
+/// heading) is required. `time` is in minutes, or like "1 h 30 min";
+/// `calories`, `fat`, `carbs`, `fiber` and `protein` are for the whole
+/// recipe; `image` may be repeated. Ingredient lines are parsed like
+/// `POST /ingredients/parse` does, indented lines continue a step and an
+/// image on its own line is the GIF of the step. Whatever isn't read is
+/// reported, e.g. `author` or `Ingredients: salt to taste`.
+pub fn to_recipe(text: &str) -> Result<ImportedRecipe, String> {
+    let mut lines = text
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .peekable();
+
+    let mut unmapped: Vec<String> = vec![];
+    let mut name: Option<String> = None;
+    let mut servings = 1;
+    let mut minutes = 0;
+    let mut nutrition = Nutrition::default();
+    let mut images: Vec<Image> = vec![];
+
+    if lines.peek().map(|line| line.trim()) == Some("---") {
+        lines.next();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if line.trim() == "---" {
+                closed = true;
+                break;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim().to_lowercase(), unquote(value)),
+                None => {
+                    unmapped.push(line.trim().to_string());
+                    continue;
+                }
+            };
+            let number = || value.parse::<f64>().ok().map(|number| number.round() as i32);
+            let read = match key.as_str() {
+                "name" | "title" if !value.is_empty() => {
+                    name = Some(value.to_string());
+                    true
+                }
+                "servings" => number()
+                    .filter(|number| *number >= 1)
+                    .map(|number| servings = number)
+                    .is_some(),
+                "time" => parse_minutes(value).map(|number| minutes = number).is_some(),
+                "calories" => number().map(|number| nutrition.calories = number).is_some(),
+                "fat" => number().map(|number| nutrition.fat = number).is_some(),
+                "carbs" => number().map(|number| nutrition.carbs = number).is_some(),
+                "fiber" => number().map(|number| nutrition.fiber = number).is_some(),
+                "protein" => number().map(|number| nutrition.protein = number).is_some(),
+                "image" if !value.is_empty() => {
+                    images.push(Image {
+                        _id: "".to_string(),
+                        path: value.to_string(),
+                        width: 0,
+                        height: 0,
+                        title: "".to_string(),
+                        created_at: "".to_string(),
+                    });
+                    true
+                }
+                _ => false,
+            };
+            if !read {
+                unmapped.push(key);
+            }
+        }
+        if !closed {
+            return Err("The front matter has no closing ---.".to_string());
+        }
+    }
+
+    let mut section = Section::Intro;
+    let mut ingredients = vec![];
+    let mut steps: Vec<RecipeStep> = vec![];
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(heading) = trimmed.strip_prefix("## ") {
+            section = match heading.trim().to_lowercase().as_str() {
+                "ingredients" => Section::Ingredients,
+                "steps" | "instructions" | "method" | "directions" => Section::Steps,
+                _ => {
+                    unmapped.push(format!("## {}", heading.trim()));
+                    Section::Other
+                }
+            };
+            continue;
+        }
+        if let Some(title) = trimmed.strip_prefix("# ") {
+            if name.is_none() && section == Section::Intro {
+                name = Some(title.trim().to_string());
+            }
+            continue;
+        }
+
+        match section {
+            Section::Intro => {
+                if !unmapped.iter().any(|field| field == "description") {
+                    unmapped.push("description".to_string());
+                }
+            }
+            Section::Ingredients => {
+                let item = bullet_item(trimmed).or_else(|| ordered_item(trimmed));
+                match item.and_then(|item| ingredient_parser::parse_line(item).ingredient) {
+                    Some(ingredient) => ingredients.push(ingredient),
+                    None => unmapped.push(format!("Ingredients: {}", item.unwrap_or(trimmed))),
+                }
+            }
+            Section::Steps => {
+                let indented = line.starts_with(' ') || line.starts_with('\t');
+                match (ordered_item(trimmed), image_link(trimmed), steps.last_mut()) {
+                    (Some(text), _, _) if !indented => steps.push(step(text.to_string())),
+                    (_, Some((title, path)), Some(last)) if last.gif.path.is_empty() => {
+                        last.gif.title = title;
+                        last.gif.path = path;
+                    }
+                    (_, None, Some(last)) if indented => {
+                        last.description = format!("{} {}", last.description, trimmed)
+                            .trim()
+                            .to_string();
+                    }
+                    _ => unmapped.push(format!("Steps: {}", trimmed)),
+                }
+            }
+            Section::Other => {}
+        }
+    }
+
+    let name = name.ok_or("The recipe has no name.".to_string())?;
+    let recipe = Recipe {
+        _id: "".to_string(),
+        name,
+        name_translations: Default::default(),
+        images,
+        preparation_time_in_minutes: minutes,
+        servings,
+        nutrition_per_serving: nutrition.per_serving(servings),
+        nutrition,
+        num_of_likes: 0,
+        num_of_views: 0,
+        rating_average: 0.0,
+        rating_count: 0,
+        ingredients,
+        steps,
+        term_ids: vec![],
+        dietary: Default::default(),
+        dietary_overrides: Default::default(),
+        collections: vec![],
+        forked_from: None,
+        localized: None,
+        status: RecipeStatus::draft,
+        publish_at: None,
+        created_at: "".to_string(),
+    };
+    Ok(ImportedRecipe { recipe, unmapped })
+}
+
+/// Writes the recipe in the format `to_recipe` reads. Images and GIFs link
+/// to where the API serves them, under `base_url`, as in JSON-LD.
+pub fn from_recipe(recipe: &Recipe, base_url: &str) -> String {
+    let nutrition = &recipe.nutrition;
+    let mut front_matter = vec![
+        format!("name: {}", quote(&recipe.name)),
+        format!("servings: {}", recipe.servings),
+        format!("time: {}", recipe.preparation_time_in_minutes),
+        format!("calories: {}", nutrition.calories),
+        format!("fat: {}", nutrition.fat),
+        format!("carbs: {}", nutrition.carbs),
+        format!("fiber: {}", nutrition.fiber),
+        format!("protein: {}", nutrition.protein),
+    ];
+    for image in recipe.images.iter() {
+        if let Some(url) = media_url(&image.path, "image", &image._id, base_url) {
+            front_matter.push(format!("image: {}", quote(&url)));
+        }
+    }
+
+    let mut markdown = format!("---\n{}\n---\n\n# {}\n", front_matter.join("\n"), recipe.name);
+    if !recipe.ingredients.is_empty() {
+        markdown.push_str("\n## Ingredients\n\n");
+        for ingredient in recipe.ingredients.iter() {
+            markdown.push_str(&format!("- {}\n", ingredient_parser::format_line(ingredient)));
+        }
+    }
+    if !recipe.steps.is_empty() {
+        markdown.push_str("\n## Steps\n\n");
+        for (index, step) in recipe.steps.iter().enumerate() {
+            let number = format!("{}. ", index + 1);
+            let description = step.description.split_whitespace().collect::<Vec<&str>>().join(" ");
+            markdown.push_str(&format!("{}{}\n", number, description));
+            if let Some(url) = media_url(&step.gif.path, "gif", &step._id, base_url) {
+                markdown.push_str(&format!(
+                    "{}![{}]({})\n",
+                    " ".repeat(number.len()),
+                    step.gif.title,
+                    url
+                ));
+            }
+        }
+    }
+    markdown
+}
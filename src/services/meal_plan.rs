@@ -0,0 +1,90 @@
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::models::meal_plan::{MealPlanDay, MealPlanEntry, MealPlanRange};
+use crate::models::recipe::{Nutrition, Recipe};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parses a `YYYY-MM-DD` date.
+pub fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), DATE_FORMAT)
+        .map_err(|_err| format!("'{}' is not a date in YYYY-MM-DD format.", date))
+}
+
+pub fn format_date(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Monday and Sunday of the week containing `date`.
+pub fn week_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+    (monday, monday + Duration::days(6))
+}
+
+/// First and last day of a `YYYY-MM` month.
+pub fn month_of(month: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let first = parse_date(&format!("{}-01", month.trim()))
+        .map_err(|_err| format!("'{}' is not a month in YYYY-MM format.", month))?;
+    let next = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    };
+    let last = next
+        .map(|next| next - Duration::days(1))
+        .ok_or(format!("'{}' is out of range.", month))?;
+    Ok((first, last))
+}
+
+/// Moves a `YYYY-MM-DD` date by a number of days.
+pub fn shift_date(date: &str, days: i64) -> Result<String, String> {
+    Ok(format_date(parse_date(date)? + Duration::days(days)))
+}
+
+/// Nutrition of the planned portions of an entry, from the recipe's
+/// per-serving nutrition.
+pub fn entry_nutrition(entry: &MealPlanEntry, recipe: &Recipe) -> Nutrition {
+    recipe
+        .nutrition
+        .scaled(entry.servings.max(1) as f64 / recipe.servings.max(1) as f64)
+}
+
+/// Lays the entries out day by day from `from` to `to` and totals the
+/// nutrition of each day. Entries of deleted recipes are listed but don't
+/// count towards the totals.
+pub fn build_range(
+    from: NaiveDate,
+    to: NaiveDate,
+    entries: Vec<MealPlanEntry>,
+    recipes: &[Recipe],
+) -> MealPlanRange {
+    let mut days = vec![];
+    let mut date = from;
+    while date <= to {
+        let day = format_date(date);
+        let mut day_entries: Vec<MealPlanEntry> = entries
+            .iter()
+            .filter(|entry| entry.date == day)
+            .cloned()
+            .collect();
+        day_entries.sort_by(|a, b| a.slot.cmp(&b.slot).then(a.created_at.cmp(&b.created_at)));
+
+        let mut nutrition = Nutrition::default();
+        for entry in day_entries.iter() {
+            if let Some(recipe) = recipes.iter().find(|recipe| recipe._id == entry.recipe_id) {
+                nutrition.add(&entry_nutrition(entry, recipe));
+            }
+        }
+        days.push(MealPlanDay {
+            date: day,
+            entries: day_entries,
+            nutrition,
+        });
+        date += Duration::days(1);
+    }
+    MealPlanRange {
+        from: format_date(from),
+        to: format_date(to),
+        days,
+    }
+}
@@ -0,0 +1,233 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::models::dietary::Allergen;
+use crate::models::meal_plan::MealSlot;
+use crate::models::recipe::{Nutrition, Recipe};
+use crate::services::meal_plan::format_date;
+
+/// Combinations tried per day. Enough to get close to the targets with a
+/// few hundred recipes while keeping generation instant.
+const ATTEMPTS_PER_DAY: usize = 200;
+
+/// Small deterministic generator (SplitMix64), so a seed always produces
+/// the same plan.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 up to, but excluding, `bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// Seed derived from a text, e.g. the user and start date, so repeated
+/// requests give the same plan unless a seed is passed.
+pub fn seed_from(text: &str) -> u64 {
+    // FNV-1a
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// What the generated plan has to respect. Nutrition targets are per day
+/// and per person; every planned meal is one serving.
+#[derive(Debug, Clone, Default)]
+pub struct PlanConstraints {
+    pub calories: Option<i32>,
+    pub protein: Option<i32>,
+    pub carbs: Option<i32>,
+    pub fat: Option<i32>,
+    pub max_preparation_minutes: Option<i32>,
+    pub vegan: bool,
+    pub vegetarian: bool,
+    pub gluten_free: bool,
+    pub exclude_allergens: Vec<Allergen>,
+    /// A recipe is not planned again within this many days
+    pub no_repeat_days: i64,
+}
+
+impl PlanConstraints {
+    pub fn allows(&self, recipe: &Recipe) -> bool {
+        let dietary = &recipe.dietary;
+        self.max_preparation_minutes
+            .is_none_or(|max| recipe.preparation_time_in_minutes <= max)
+            && (!self.vegan || dietary.vegan)
+            && (!self.vegetarian || dietary.vegetarian || dietary.vegan)
+            && (!self.gluten_free || dietary.gluten_free)
+            && !dietary
+                .allergens
+                .iter()
+                .any(|allergen| self.exclude_allergens.contains(allergen))
+    }
+
+    /// Relative distance of a day's nutrition from the targets, 0 when
+    /// every target is met exactly or there are none.
+    fn distance(&self, nutrition: &Nutrition) -> f64 {
+        [
+            (self.calories, nutrition.calories),
+            (self.protein, nutrition.protein),
+            (self.carbs, nutrition.carbs),
+            (self.fat, nutrition.fat),
+        ]
+        .iter()
+        .filter_map(|(target, actual)| {
+            target
+                .filter(|target| *target > 0)
+                .map(|target| (*actual - target).abs() as f64 / target as f64)
+        })
+        .sum()
+    }
+}
+
+/// A recipe the planner may pick. `slots` limits it to some meals of the
+/// day, empty means any.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub recipe: Recipe,
+    pub slots: Vec<MealSlot>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedMeal {
+    pub date: String,
+    pub slot: MealSlot,
+    pub recipe_id: String,
+}
+
+/// Picks a recipe for every slot of every day from `start`.
+///
+/// Each day, recipes used within the no-repeat window are set aside and
+/// random combinations drawn with the seeded generator are compared by
+/// their distance from the nutrition targets; the closest one is kept.
+/// When every allowed recipe for a slot was used recently, the one used
+/// longest ago is repeated. Slots without any allowed recipe stay empty.
+/// `history` lists meals planned before `start`, as date and recipe id.
+pub fn generate(
+    candidates: &[Candidate],
+    constraints: &PlanConstraints,
+    start: NaiveDate,
+    days: i64,
+    slots: &[MealSlot],
+    history: &[(NaiveDate, String)],
+    seed: u64,
+) -> Vec<PlannedMeal> {
+    let mut rng = Rng::new(seed);
+    // Sorting makes the result independent of the order the database returns.
+    let mut allowed: Vec<&Candidate> = candidates
+        .iter()
+        .filter(|candidate| constraints.allows(&candidate.recipe))
+        .collect();
+    allowed.sort_by(|a, b| a.recipe._id.cmp(&b.recipe._id));
+
+    let mut last_used: Vec<(String, NaiveDate)> = vec![];
+    for (date, recipe_id) in history {
+        remember(&mut last_used, recipe_id, *date);
+    }
+
+    let mut plan = vec![];
+    for offset in 0..days {
+        let date = start + Duration::days(offset);
+        let pools: Vec<Vec<&Candidate>> = slots
+            .iter()
+            .map(|slot| pool(&allowed, *slot, date, &last_used, constraints.no_repeat_days))
+            .collect();
+
+        let mut best: Option<(f64, Vec<Option<&Candidate>>)> = None;
+        for _ in 0..ATTEMPTS_PER_DAY {
+            let mut picks: Vec<Option<&Candidate>> = vec![];
+            for pool in pools.iter() {
+                // Skips recipes already picked for another meal of the day.
+                let fresh: Vec<&Candidate> = pool
+                    .iter()
+                    .filter(|candidate| {
+                        !picks.iter().flatten().any(|picked| picked.recipe._id == candidate.recipe._id)
+                    })
+                    .copied()
+                    .collect();
+                let choices = if fresh.is_empty() { pool } else { &fresh };
+                picks.push(match choices.len() {
+                    0 => None,
+                    len => Some(choices[rng.below(len)]),
+                });
+            }
+
+            let mut nutrition = Nutrition::default();
+            for pick in picks.iter().flatten() {
+                nutrition.add(&pick.recipe.nutrition.per_serving(pick.recipe.servings));
+            }
+            let distance = constraints.distance(&nutrition);
+            if best.as_ref().is_none_or(|(best, _)| distance < *best) {
+                best = Some((distance, picks));
+            }
+        }
+
+        if let Some((_, picks)) = best {
+            for (slot, pick) in slots.iter().zip(picks) {
+                if let Some(pick) = pick {
+                    remember(&mut last_used, &pick.recipe._id, date);
+                    plan.push(PlannedMeal {
+                        date: format_date(date),
+                        slot: *slot,
+                        recipe_id: pick.recipe._id.clone(),
+                    });
+                }
+            }
+        }
+    }
+    plan
+}
+
+fn remember(last_used: &mut Vec<(String, NaiveDate)>, recipe_id: &str, date: NaiveDate) {
+    match last_used.iter_mut().find(|(id, _)| id == recipe_id) {
+        Some((_, used)) if *used < date => *used = date,
+        Some(_) => {}
+        None => last_used.push((recipe_id.to_string(), date)),
+    }
+}
+
+/// Recipes allowed in the slot and not used within the window, or else
+/// the one used longest ago.
+fn pool<'a>(
+    allowed: &[&'a Candidate],
+    slot: MealSlot,
+    date: NaiveDate,
+    last_used: &[(String, NaiveDate)],
+    window: i64,
+) -> Vec<&'a Candidate> {
+    let used = |candidate: &Candidate| {
+        last_used
+            .iter()
+            .find(|(id, _)| *id == candidate.recipe._id)
+            .map(|(_, used)| *used)
+    };
+    let fitting: Vec<&Candidate> = allowed
+        .iter()
+        .filter(|candidate| candidate.slots.is_empty() || candidate.slots.contains(&slot))
+        .copied()
+        .collect();
+    let unrepeated: Vec<&Candidate> = fitting
+        .iter()
+        .filter(|candidate| used(candidate).is_none_or(|used| (date - used).num_days() >= window))
+        .copied()
+        .collect();
+    if !unrepeated.is_empty() {
+        return unrepeated;
+    }
+    fitting
+        .iter()
+        .min_by_key(|candidate| used(candidate))
+        .map(|candidate| vec![*candidate])
+        .unwrap_or_default()
+}
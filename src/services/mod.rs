@@ -0,0 +1,28 @@
+pub mod catalog;
+pub mod collections;
+pub mod cooking;
+pub mod csv;
+pub mod daily;
+pub mod dietary;
+pub mod forks;
+pub mod html;
+pub mod ingredient_parser;
+pub mod jsonld;
+pub mod locale;
+pub mod markdown;
+pub mod meal_plan;
+pub mod meal_planner;
+pub mod names;
+pub mod nutrition;
+pub mod pantry;
+pub mod pdf;
+pub mod print;
+pub mod publishing;
+pub mod scaling;
+pub mod search;
+pub mod shopping;
+pub mod similarity;
+pub mod tar;
+pub mod trash;
+pub mod units;
+pub mod uploads;
@@ -0,0 +1,55 @@
+/// Lowercases `name` and reduces it to space separated words, so that
+/// "Extra-Virgin  Olive Oil" and "extra virgin olive oil" compare equal.
+pub fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Drops a plural ending from every word, e.g. "tomatoes" -> "tomato".
+pub fn singular(name: &str) -> String {
+    name.split(' ')
+        .map(|word| {
+            if word.len() > 4 && word.ends_with("oes") {
+                &word[..word.len() - 2]
+            } else if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+                &word[..word.len() - 1]
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Whether the words of `needle` appear consecutively in `haystack`.
+/// Both are expected to be normalized.
+pub fn contains_words(haystack: &str, needle: &str) -> bool {
+    !needle.is_empty() && format!(" {} ", haystack).contains(&format!(" {} ", needle))
+}
+
+/// Finds the entry whose name best matches `name`: an exact match first,
+/// then the entry with the longest name contained in `name`.
+/// `names` lists the normalized names an entry is known by.
+pub fn find_best<'a, T, F>(name: &str, entries: &'a [T], names: F) -> Option<&'a T>
+where
+    F: Fn(&T) -> Vec<&str>,
+{
+    let name = normalize(name);
+    let single = singular(&name);
+    let mut best: Option<(&T, usize)> = None;
+    for entry in entries {
+        for known in names(entry) {
+            if known == name || known == single {
+                return Some(entry);
+            }
+            let contained = contains_words(&name, known) || contains_words(&single, known);
+            if contained && best.is_none_or(|(_, len)| known.len() > len) {
+                best = Some((entry, known.len()));
+            }
+        }
+    }
+    best.map(|(entry, _)| entry)
+}
@@ -0,0 +1,204 @@
+use std::env;
+use std::sync::OnceLock;
+
+use crate::models::nutrition::{IngredientNutrition, NutritionReport};
+use crate::models::recipe::{Ingredient, Nutrition};
+use crate::services::csv::{self, Table};
+use crate::services::{names, units};
+
+/// Food composition dataset shipped with the server. `FOOD_COMPOSITION_CSV`
+/// can point to a larger dataset in the same format.
+const BUNDLED_DATASET: &str = include_str!("../../data/food_composition.csv");
+
+/// Density used for volume units when the food doesn't define one.
+const DEFAULT_DENSITY: f64 = 1.0;
+
+/// Nutrients per 100 g of a food.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoodComposition {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub calories: f64,
+    pub fat: f64,
+    pub carbs: f64,
+    pub fiber: f64,
+    pub protein: f64,
+    /// Grams per millilitre, used for volume units
+    pub density: Option<f64>,
+    /// Grams per piece or clove, used for count units
+    pub piece_grams: Option<f64>,
+}
+
+impl FoodComposition {
+    fn known_names(&self) -> Vec<&str> {
+        let mut known = vec![self.name.as_str()];
+        known.extend(self.aliases.iter().map(|alias| alias.as_str()));
+        known
+    }
+}
+
+/// Parses a dataset with the header
+/// `name,aliases,calories,fat,carbs,fiber,protein,density,piece_grams`, in
+/// any column order, the last two being optional. Aliases are separated by
+/// `;`, lines starting with `#` are comments.
+pub fn parse_dataset(csv: &str) -> Result<Vec<FoodComposition>, String> {
+    let table = Table::parse(csv)?;
+    let (name, aliases) = (table.column("name")?, table.column("aliases")?);
+    let nutrients = [
+        table.column("calories")?,
+        table.column("fat")?,
+        table.column("carbs")?,
+        table.column("fiber")?,
+        table.column("protein")?,
+    ];
+    let (density, piece_grams) = (table.column("density").ok(), table.column("piece_grams").ok());
+
+    let mut foods = vec![];
+    for (line, row) in table.rows.iter() {
+        let required = |index: usize| -> Result<f64, String> {
+            csv::number(row, index, *line)?
+                .ok_or(format!("Missing number on line {} of the food composition dataset.", line))
+        };
+        let optional = |column: Option<usize>| -> Result<Option<f64>, String> {
+            match column {
+                Some(index) => csv::number(row, index, *line),
+                None => Ok(None),
+            }
+        };
+        foods.push(FoodComposition {
+            name: names::normalize(csv::text(row, name)),
+            aliases: csv::text(row, aliases)
+                .split(';')
+                .map(names::normalize)
+                .filter(|alias| !alias.is_empty())
+                .collect(),
+            calories: required(nutrients[0])?,
+            fat: required(nutrients[1])?,
+            carbs: required(nutrients[2])?,
+            fiber: required(nutrients[3])?,
+            protein: required(nutrients[4])?,
+            density: optional(density)?,
+            piece_grams: optional(piece_grams)?,
+        });
+    }
+    Ok(foods)
+}
+
+static DATASET: OnceLock<Vec<FoodComposition>> = OnceLock::new();
+
+/// Reads the dataset `FOOD_COMPOSITION_CSV` points to, else the bundled one.
+fn read_dataset() -> Result<Vec<FoodComposition>, String> {
+    match env::var("FOOD_COMPOSITION_CSV") {
+        Ok(path) => {
+            let csv = std::fs::read_to_string(&path)
+                .map_err(|err| format!("Cannot read {}: {}", path, err))?;
+            parse_dataset(&csv).map_err(|err| format!("{}: {}", path, err))
+        }
+        Err(_) => parse_dataset(BUNDLED_DATASET)
+            .map_err(|err| format!("data/food_composition.csv: {}", err)),
+    }
+}
+
+/// Loads the dataset ahead of its first use, failing when it can't be read.
+pub fn load_dataset() -> Result<(), String> {
+    if DATASET.get().is_none() {
+        let _ = DATASET.set(read_dataset()?);
+    }
+    Ok(())
+}
+
+pub fn dataset() -> &'static Vec<FoodComposition> {
+    DATASET.get_or_init(|| read_dataset().unwrap_or_else(|err| panic!("{}", err)))
+}
+
+pub fn lookup(name: &str) -> Option<&'static FoodComposition> {
+    names::find_best(name, dataset(), |food| food.known_names())
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Nutrients {
+    calories: f64,
+    fat: f64,
+    carbs: f64,
+    fiber: f64,
+    protein: f64,
+}
+
+impl Nutrients {
+    fn of(food: &FoodComposition, grams: f64) -> Nutrients {
+        let factor = grams / 100.0;
+        Nutrients {
+            calories: food.calories * factor,
+            fat: food.fat * factor,
+            carbs: food.carbs * factor,
+            fiber: food.fiber * factor,
+            protein: food.protein * factor,
+        }
+    }
+
+    fn add(&mut self, other: &Nutrients) {
+        self.calories += other.calories;
+        self.fat += other.fat;
+        self.carbs += other.carbs;
+        self.fiber += other.fiber;
+        self.protein += other.protein;
+    }
+
+    fn divided(&self, by: f64) -> Nutrients {
+        Nutrients {
+            calories: self.calories / by,
+            fat: self.fat / by,
+            carbs: self.carbs / by,
+            fiber: self.fiber / by,
+            protein: self.protein / by,
+        }
+    }
+
+    fn rounded(&self) -> Nutrition {
+        Nutrition {
+            calories: self.calories.round() as i32,
+            fat: self.fat.round() as i32,
+            carbs: self.carbs.round() as i32,
+            fiber: self.fiber.round() as i32,
+            protein: self.protein.round() as i32,
+        }
+    }
+}
+
+/// Computes the nutrition of the ingredients in total and per serving.
+/// Servings below one are treated as one.
+pub fn calculate(ingredients: &[Ingredient], servings: i32) -> NutritionReport {
+    let servings = servings.max(1);
+    let mut total = Nutrients::default();
+    let mut matched = vec![];
+    let mut unmatched_ingredients = vec![];
+
+    for ingredient in ingredients {
+        let food = lookup(&ingredient.name);
+        let grams = food.and_then(|food| {
+            let density = food.density.unwrap_or(DEFAULT_DENSITY);
+            units::to_grams(ingredient.amount, ingredient.unit, &ingredient.name, Some(density))
+        });
+        match (food, grams) {
+            (Some(food), Some(grams)) => {
+                let nutrients = Nutrients::of(food, grams);
+                total.add(&nutrients);
+                matched.push(IngredientNutrition {
+                    ingredient: ingredient.name.clone(),
+                    food: food.name.clone(),
+                    grams: (grams * 10.0).round() / 10.0,
+                    nutrition: nutrients.rounded(),
+                });
+            }
+            _ => unmatched_ingredients.push(ingredient.name.clone()),
+        }
+    }
+
+    NutritionReport {
+        total: total.rounded(),
+        per_serving: total.divided(servings as f64).rounded(),
+        servings,
+        ingredients: matched,
+        unmatched_ingredients,
+    }
+}
@@ -0,0 +1,147 @@
+use std::env;
+use std::sync::OnceLock;
+
+use crate::models::pantry::PantryMatch;
+use crate::models::recipe::Recipe;
+use crate::services::csv::{self, Table};
+use crate::services::names;
+
+/// Ingredient synonyms shipped with the server. `INGREDIENT_ALIASES_CSV`
+/// can point to another table in the same format.
+const BUNDLED_ALIASES: &str = include_str!("../../data/ingredient_aliases.csv");
+
+/// An ingredient and the other names it goes by.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasGroup {
+    pub name: String,
+    pub aliases: Vec<String>,
+}
+
+impl AliasGroup {
+    fn known_names(&self) -> Vec<&str> {
+        let mut known = vec![self.name.as_str()];
+        known.extend(self.aliases.iter().map(|alias| alias.as_str()));
+        known
+    }
+}
+
+/// Parses a table with the header `name,aliases`, aliases separated by `;`.
+pub fn parse_aliases(csv: &str) -> Result<Vec<AliasGroup>, String> {
+    let table = Table::parse(csv)?;
+    let (name, aliases) = (table.column("name")?, table.column("aliases")?);
+
+    let mut groups = vec![];
+    for (_, row) in table.rows.iter() {
+        groups.push(AliasGroup {
+            name: names::normalize(csv::text(row, name)),
+            aliases: csv::text(row, aliases)
+                .split(';')
+                .map(names::normalize)
+                .filter(|alias| !alias.is_empty())
+                .collect(),
+        });
+    }
+    Ok(groups)
+}
+
+pub fn aliases() -> &'static Vec<AliasGroup> {
+    static ALIASES: OnceLock<Vec<AliasGroup>> = OnceLock::new();
+    ALIASES.get_or_init(|| match env::var("INGREDIENT_ALIASES_CSV") {
+        Ok(path) => {
+            let csv = std::fs::read_to_string(&path)
+                .unwrap_or_else(|err| panic!("Cannot read {}: {}", path, err));
+            parse_aliases(&csv).unwrap_or_else(|err| panic!("{}", err))
+        }
+        Err(_) => parse_aliases(BUNDLED_ALIASES).expect("data/ingredient_aliases.csv is invalid."),
+    })
+}
+
+/// The name an ingredient is matched by, in singular: the name of its
+/// group if it is a known name, else with the longest known name it ends
+/// with replaced, so "Chopped Scallions" and "chopped green onion" both
+/// become "chopped green onion". Known names elsewhere in the name are
+/// kept, "red pepper flakes" aren't bell peppers, and so are those that
+/// only end the group's own name, "almond flour" isn't all-purpose flour.
+pub fn canonical(name: &str, groups: &[AliasGroup]) -> String {
+    let single = names::singular(&names::normalize(name));
+    let mut best: Option<(String, String)> = None;
+    for group in groups {
+        let group_name = names::singular(&group.name);
+        for known in group.known_names() {
+            let known = names::singular(known);
+            if known == single {
+                return group_name;
+            }
+            let ends_name = single.ends_with(&format!(" {}", known));
+            let ends_group_name = group_name.ends_with(&format!(" {}", known));
+            if ends_name && !ends_group_name && best.as_ref().is_none_or(|(alias, _)| known.len() > alias.len()) {
+                best = Some((known, group_name.clone()));
+            }
+        }
+    }
+    match best {
+        Some((alias, group)) => format!("{} {}", &single[..single.len() - alias.len() - 1], group),
+        None => single,
+    }
+}
+
+/// Whether a pantry item covers a recipe ingredient, both canonical: one has
+/// to end with the other, so "basil" covers "fresh basil" and "sea salt"
+/// covers "salt", but "salt" doesn't cover "salted butter" and "tomato"
+/// doesn't cover "tomato paste".
+pub fn covers(item: &str, ingredient: &str) -> bool {
+    !item.is_empty()
+        && (item == ingredient
+            || ingredient.ends_with(&format!(" {}", item))
+            || item.ends_with(&format!(" {}", ingredient)))
+}
+
+/// How much of the recipe the pantry covers.
+pub fn match_recipe(recipe: &Recipe, pantry: &[String], groups: &[AliasGroup]) -> PantryMatch {
+    let mut matched_ingredients = vec![];
+    let mut missing_ingredients = vec![];
+    for ingredient in recipe.ingredients.iter() {
+        let required = canonical(&ingredient.name, groups);
+        if pantry.iter().any(|item| covers(item, &required)) {
+            matched_ingredients.push(ingredient.name.clone());
+        } else {
+            missing_ingredients.push(ingredient.name.clone());
+        }
+    }
+    let total = matched_ingredients.len() + missing_ingredients.len();
+    PantryMatch {
+        coverage: if total == 0 {
+            0.0
+        } else {
+            matched_ingredients.len() as f64 / total as f64
+        },
+        matched_ingredients,
+        missing_ingredients,
+        recipe: recipe.clone(),
+    }
+}
+
+/// Ranks recipes by the fraction of their ingredients found in the pantry,
+/// then by fewest missing ingredients. Recipes below `min_coverage` or
+/// without any match are left out.
+pub fn rank(recipes: &[Recipe], pantry: &[String], min_coverage: f64) -> Vec<PantryMatch> {
+    let groups = aliases();
+    let pantry: Vec<String> = pantry
+        .iter()
+        .map(|item| canonical(item, groups))
+        .filter(|item| !item.is_empty())
+        .collect();
+
+    let mut matches: Vec<PantryMatch> = recipes
+        .iter()
+        .map(|recipe| match_recipe(recipe, &pantry, groups))
+        .filter(|found| !found.matched_ingredients.is_empty() && found.coverage >= min_coverage)
+        .collect();
+    matches.sort_by(|a, b| {
+        b.coverage
+            .total_cmp(&a.coverage)
+            .then(a.missing_ingredients.len().cmp(&b.missing_ingredients.len()))
+            .then(a.recipe.name.cmp(&b.recipe.name))
+    });
+    matches
+}
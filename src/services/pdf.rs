@@ -0,0 +1,307 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::io::Reader;
+use image::ColorType;
+
+/// Longest side images are downscaled to before embedding.
+const MAX_IMAGE_SIDE: u32 = 1200;
+
+/// Widths of ASCII 32 to 126 in thousandths of the font size, from the
+/// Adobe metrics of the standard fonts every PDF reader ships.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+/// Width of `text` in points.
+pub fn text_width(text: &str, font: Font, size: f64) -> f64 {
+    let widths = match font {
+        Font::Regular => &HELVETICA_WIDTHS,
+        Font::Bold => &HELVETICA_BOLD_WIDTHS,
+    };
+    let units: u32 = text
+        .chars()
+        .map(|c| match c as u32 {
+            code @ 32..=126 => widths[(code - 32) as usize] as u32,
+            _ => 556,
+        })
+        .sum();
+    units as f64 * size / 1000.0
+}
+
+/// Breaks `text` into lines no wider than `max_width`. Words longer than a
+/// line are split wherever they overflow.
+pub fn wrap(text: &str, font: Font, size: f64, max_width: f64) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if text_width(&candidate, font, size) <= max_width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            if !line.is_empty() && text_width(&format!("{}{}", line, c), font, size) > max_width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// A text string in the WinAnsi encoding of the standard fonts, written
+/// with escapes so the content stream stays ASCII.
+fn encode_text(text: &str) -> String {
+    let mut encoded = String::new();
+    for c in text.chars() {
+        let byte: u8 = match c {
+            '(' | ')' | '\\' => {
+                encoded.push('\\');
+                encoded.push(c);
+                continue;
+            }
+            ' '..='~' => {
+                encoded.push(c);
+                continue;
+            }
+            '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            c if c.is_whitespace() => b' ',
+            _ => b'?',
+        };
+        if byte.is_ascii() {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("\\{:03o}", byte));
+        }
+    }
+    encoded
+}
+
+/// A JPEG ready to embed, whatever format it was read from.
+#[derive(Debug, Clone)]
+pub struct Jpeg {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl Jpeg {
+    /// Decodes an image, the first frame for a GIF, and encodes it again as
+    /// a JPEG no larger than `MAX_IMAGE_SIDE`.
+    pub fn from_image_bytes(bytes: &[u8]) -> Option<Jpeg> {
+        let image = Reader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .ok()?
+            .decode()
+            .ok()?;
+        let image = if image.width() > MAX_IMAGE_SIDE || image.height() > MAX_IMAGE_SIDE {
+            image.thumbnail(MAX_IMAGE_SIDE, MAX_IMAGE_SIDE)
+        } else {
+            image
+        };
+        let rgb = image.to_rgb8();
+        let mut data = vec![];
+        JpegEncoder::new_with_quality(&mut data, 85)
+            .encode(rgb.as_raw(), rgb.width(), rgb.height(), ColorType::Rgb8)
+            .ok()?;
+        Some(Jpeg {
+            width: rgb.width(),
+            height: rgb.height(),
+            data,
+        })
+    }
+}
+
+/// A PDF being drawn page by page. Coordinates are in points from the
+/// bottom left corner, as in PDF itself.
+pub struct PdfDocument {
+    pub width: f64,
+    pub height: f64,
+    pages: Vec<String>,
+    images: Vec<Jpeg>,
+}
+
+impl PdfDocument {
+    pub fn new(width: f64, height: f64) -> PdfDocument {
+        PdfDocument {
+            width,
+            height,
+            pages: vec![String::new()],
+            images: vec![],
+        }
+    }
+
+    pub fn add_page(&mut self) {
+        self.pages.push(String::new());
+    }
+
+    fn draw(&mut self, operators: String) {
+        if let Some(page) = self.pages.last_mut() {
+            page.push_str(&operators);
+        }
+    }
+
+    /// `y` is the baseline of the text.
+    pub fn text(&mut self, x: f64, y: f64, font: Font, size: f64, text: &str) {
+        self.draw(format!(
+            "BT /{} {:.2} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+            font.resource(),
+            size,
+            x,
+            y,
+            encode_text(text)
+        ));
+    }
+
+    pub fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, width: f64, gray: f64) {
+        self.draw(format!(
+            "q {:.2} G {:.2} w {:.2} {:.2} m {:.2} {:.2} l S Q\n",
+            gray, width, x1, y1, x2, y2
+        ));
+    }
+
+    /// A rectangle filled with `fill` gray and outlined in `stroke` gray,
+    /// from 0 for black to 1 for white.
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: f64, stroke: f64) {
+        self.draw(format!(
+            "q {:.2} g {:.2} G 0.5 w {:.2} {:.2} {:.2} {:.2} re B Q\n",
+            fill, stroke, x, y, width, height
+        ));
+    }
+
+    /// Adds an image to the document, to be drawn with `image`.
+    pub fn add_image(&mut self, jpeg: Jpeg) -> usize {
+        self.images.push(jpeg);
+        self.images.len() - 1
+    }
+
+    pub fn image(&mut self, index: usize, x: f64, y: f64, width: f64, height: f64) {
+        self.draw(format!(
+            "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q\n",
+            width, height, x, y, index
+        ));
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Catalog, page tree and the two fonts come first, then the images,
+        // then every page followed by its content stream.
+        let first_image = 5;
+        let first_page = first_image + self.images.len();
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|index| first_page + index * 2).collect();
+
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<String>>().join(" "),
+                self.pages.len()
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        ];
+        for jpeg in self.images.iter() {
+            let mut object = format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+                 /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+                jpeg.width,
+                jpeg.height,
+                jpeg.data.len()
+            )
+            .into_bytes();
+            object.extend_from_slice(&jpeg.data);
+            object.extend_from_slice(b"\nendstream");
+            objects.push(object);
+        }
+        let images: String = (0..self.images.len())
+            .map(|index| format!("/Im{} {} 0 R ", index, first_image + index))
+            .collect();
+        for (index, content) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R >> /XObject << {}>> >> \
+                     /Contents {} 0 R >>",
+                    self.width,
+                    self.height,
+                    images,
+                    page_ids[index] + 1
+                )
+                .into_bytes(),
+            );
+            objects.push(
+                format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content).into_bytes(),
+            );
+        }
+
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = vec![];
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .as_bytes(),
+        );
+        pdf
+    }
+}
@@ -0,0 +1,337 @@
+use crate::models::meal_plan::MealPlanRange;
+use crate::models::print::PageSize;
+use crate::models::recipe::{Nutrition, Recipe};
+use crate::services::ingredient_parser::format_quantity;
+use crate::services::meal_plan::parse_date;
+use crate::services::pdf::{text_width, wrap, Font, Jpeg, PdfDocument};
+
+/// Font sizes and spacing in points, smaller on index cards.
+#[derive(Clone, Copy)]
+struct Style {
+    margin: f64,
+    title: f64,
+    heading: f64,
+    body: f64,
+    small: f64,
+    gap: f64,
+}
+
+impl Style {
+    fn of(size: PageSize) -> Style {
+        match size {
+            PageSize::index_card => Style {
+                margin: 18.0,
+                title: 14.0,
+                heading: 10.0,
+                body: 8.0,
+                small: 6.5,
+                gap: 6.0,
+            },
+            _ => Style {
+                margin: 48.0,
+                title: 24.0,
+                heading: 14.0,
+                body: 10.5,
+                small: 8.5,
+                gap: 12.0,
+            },
+        }
+    }
+
+    fn leading(&self, size: f64) -> f64 {
+        size * 1.3
+    }
+}
+
+/// Draws blocks top to bottom, starting a new page when one doesn't fit.
+struct Layout {
+    pdf: PdfDocument,
+    style: Style,
+    /// Top of the space left on the page
+    y: f64,
+}
+
+impl Layout {
+    fn new(size: PageSize) -> Layout {
+        let (width, height) = size.dimensions();
+        let style = Style::of(size);
+        Layout {
+            y: height - style.margin,
+            pdf: PdfDocument::new(width, height),
+            style,
+        }
+    }
+
+    fn left(&self) -> f64 {
+        self.style.margin
+    }
+
+    fn content_width(&self) -> f64 {
+        self.pdf.width - 2.0 * self.style.margin
+    }
+
+    fn content_height(&self) -> f64 {
+        self.pdf.height - 2.0 * self.style.margin
+    }
+
+    /// Makes room for a block of `height`, on a new page if needed. Blocks
+    /// taller than a page start at the top of one and run over its end.
+    fn reserve(&mut self, height: f64) {
+        let at_top = self.y >= self.pdf.height - self.style.margin;
+        if self.y - height < self.style.margin && !at_top {
+            self.pdf.add_page();
+            self.y = self.pdf.height - self.style.margin;
+        }
+    }
+
+    fn space(&mut self, height: f64) {
+        self.y -= height;
+    }
+
+    /// Wrapped text starting at `x`, one line at a time so paragraphs can
+    /// break across pages.
+    fn paragraph(&mut self, text: &str, font: Font, size: f64, x: f64, width: f64) {
+        let leading = self.style.leading(size);
+        for line in wrap(text, font, size, width) {
+            self.reserve(leading);
+            self.pdf.text(x, self.y - size, font, size, &line);
+            self.y -= leading;
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        let size = self.style.heading;
+        // Keep a heading on the page of what follows it.
+        self.reserve(self.style.leading(size) + self.style.leading(self.style.body) * 2.0);
+        self.space(self.style.gap);
+        let (x, width) = (self.left(), self.content_width());
+        self.paragraph(text, Font::Bold, size, x, width);
+        self.space(self.style.gap / 3.0);
+    }
+
+    /// An image fitted to `width` and at most `max_height`, centred.
+    fn image(&mut self, jpeg: Jpeg, max_height: f64) {
+        let (mut width, mut height) = fit(&jpeg, self.content_width(), max_height);
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        if height > self.content_height() {
+            width *= self.content_height() / height;
+            height = self.content_height();
+        }
+        self.reserve(height);
+        let x = self.left() + (self.content_width() - width) / 2.0;
+        let index = self.pdf.add_image(jpeg);
+        self.pdf.image(index, x, self.y - height, width, height);
+        self.y -= height;
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.pdf.to_bytes()
+    }
+}
+
+/// Size of an image scaled to fit the box, keeping its proportions.
+fn fit(jpeg: &Jpeg, max_width: f64, max_height: f64) -> (f64, f64) {
+    if jpeg.width == 0 || jpeg.height == 0 {
+        return (0.0, 0.0);
+    }
+    let scale = (max_width / jpeg.width as f64).min(max_height / jpeg.height as f64);
+    (jpeg.width as f64 * scale, jpeg.height as f64 * scale)
+}
+
+fn nutrition_box(layout: &mut Layout, nutrition: &Nutrition) {
+    let style = layout.style;
+    let cells = [
+        ("Calories", format!("{} kcal", nutrition.calories)),
+        ("Fat", format!("{} g", nutrition.fat)),
+        ("Carbs", format!("{} g", nutrition.carbs)),
+        ("Fiber", format!("{} g", nutrition.fiber)),
+        ("Protein", format!("{} g", nutrition.protein)),
+    ];
+    let padding = style.gap / 2.0;
+    let (small, body) = (style.small, style.body);
+    let height = padding * 2.0 + style.leading(small) + style.leading(body);
+    let cell_width = layout.content_width() / cells.len() as f64;
+
+    layout.reserve(height);
+    let (x, top) = (layout.left(), layout.y);
+    layout.pdf.rect(x, top - height, layout.content_width(), height, 0.95, 0.6);
+    for (index, (label, value)) in cells.iter().enumerate() {
+        let cell_x = x + cell_width * index as f64 + padding;
+        layout.pdf.text(cell_x, top - padding - small, Font::Regular, small, label);
+        layout.pdf.text(
+            cell_x,
+            top - padding - style.leading(small) - body,
+            Font::Bold,
+            body,
+            value,
+        );
+    }
+    layout.y -= height;
+}
+
+/// A printable recipe card: title, hero image, ingredient table, numbered
+/// steps with the poster frame of their GIF, and nutrition per serving.
+/// `frames` holds the frame of each step, if it has one.
+pub fn recipe_pdf(
+    recipe: &Recipe,
+    size: PageSize,
+    hero: Option<Jpeg>,
+    frames: Vec<Option<Jpeg>>,
+) -> Vec<u8> {
+    let mut layout = Layout::new(size);
+    let (x, width) = (layout.left(), layout.content_width());
+    let style = layout.style;
+
+    layout.paragraph(&recipe.name, Font::Bold, style.title, x, width);
+    layout.paragraph(
+        &format!(
+            "{} servings · {} minutes",
+            recipe.servings, recipe.preparation_time_in_minutes
+        ),
+        Font::Regular,
+        style.small,
+        x,
+        width,
+    );
+    if let Some(hero) = hero {
+        layout.space(style.gap);
+        let max_height = layout.content_height() * if size == PageSize::index_card { 0.6 } else { 0.4 };
+        layout.image(hero, max_height);
+    }
+
+    if !recipe.ingredients.is_empty() {
+        layout.heading("Ingredients");
+        let quantities: Vec<String> = recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| format_quantity(ingredient.amount, ingredient.unit))
+            .collect();
+        let column = quantities
+            .iter()
+            .map(|quantity| text_width(quantity, Font::Bold, style.body))
+            .fold(0.0, f64::max)
+            .min(width * 0.35)
+            + style.gap;
+        let leading = style.leading(style.body);
+        for (ingredient, quantity) in recipe.ingredients.iter().zip(quantities.iter()) {
+            let names = wrap(&ingredient.name, Font::Regular, style.body, width - column);
+            let quantities = wrap(quantity, Font::Bold, style.body, column - style.gap);
+            let rows = names.len().max(quantities.len()).max(1);
+            layout.reserve(leading * rows as f64 + 2.0);
+            for (row, line) in quantities.iter().enumerate() {
+                let y = layout.y - style.body - leading * row as f64;
+                layout.pdf.text(x, y, Font::Bold, style.body, line);
+            }
+            for (row, line) in names.iter().enumerate() {
+                let y = layout.y - style.body - leading * row as f64;
+                layout.pdf.text(x + column, y, Font::Regular, style.body, line);
+            }
+            layout.y -= leading * rows as f64 + 2.0;
+            layout.pdf.line(x, layout.y, x + width, layout.y, 0.3, 0.8);
+        }
+    }
+
+    if !recipe.steps.is_empty() {
+        layout.heading("Steps");
+        let mut frames = frames.into_iter();
+        let number_width = text_width("00.", Font::Bold, style.body) + style.gap / 2.0;
+        let thumbnail_width = (width * 0.22).min(110.0);
+        let leading = style.leading(style.body);
+        for (index, step) in recipe.steps.iter().enumerate() {
+            let frame = frames.next().flatten();
+            let thumbnail = frame
+                .as_ref()
+                .map(|frame| fit(frame, thumbnail_width, thumbnail_width))
+                .filter(|(width, height)| *width > 0.0 && *height > 0.0);
+            let text_x = x + number_width + thumbnail.map_or(0.0, |_| thumbnail_width + style.gap / 2.0);
+            let lines = wrap(&step.description, Font::Regular, style.body, x + width - text_x);
+            let thumbnail_height = thumbnail.map_or(0.0, |(_, height)| height);
+
+            // Keep the number and thumbnail together with the first line; the
+            // rest of the description breaks across pages like a paragraph.
+            layout.reserve(thumbnail_height.max(leading).min(layout.content_height()));
+            let top = layout.y;
+            layout
+                .pdf
+                .text(x, top - style.body, Font::Bold, style.body, &format!("{}.", index + 1));
+            if let (Some(frame), Some((frame_width, frame_height))) = (frame, thumbnail) {
+                let image = layout.pdf.add_image(frame);
+                layout
+                    .pdf
+                    .image(image, x + number_width, top - frame_height, frame_width, frame_height);
+            }
+            let mut same_page = true;
+            for line in &lines {
+                let before = layout.y;
+                layout.reserve(leading);
+                same_page &= layout.y <= before;
+                layout.pdf.text(text_x, layout.y - style.body, Font::Regular, style.body, line);
+                layout.y -= leading;
+            }
+            if same_page {
+                layout.y = layout.y.min(top - thumbnail_height);
+            }
+            layout.space(style.gap / 2.0);
+        }
+    }
+
+    layout.heading("Nutrition per serving");
+    nutrition_box(&mut layout, &recipe.nutrition.per_serving(recipe.servings));
+    layout.finish()
+}
+
+/// A printable meal plan, one block per day with its meals and nutrition.
+/// `recipes` are the recipes the entries refer to.
+pub fn meal_plan_pdf(range: &MealPlanRange, recipes: &[Recipe], size: PageSize) -> Vec<u8> {
+    let mut layout = Layout::new(size);
+    let (x, width) = (layout.left(), layout.content_width());
+    let style = layout.style;
+
+    layout.paragraph(
+        &format!("Meal plan {} – {}", range.from, range.to),
+        Font::Bold,
+        style.title,
+        x,
+        width,
+    );
+    let slot_width = text_width("breakfast", Font::Bold, style.body) + style.gap;
+    for day in range.days.iter() {
+        let title = match parse_date(&day.date) {
+            Ok(date) => date.format("%A, %B %-d").to_string(),
+            Err(_) => day.date.clone(),
+        };
+        layout.heading(&title);
+        if day.entries.is_empty() {
+            layout.paragraph("Nothing planned", Font::Regular, style.body, x, width);
+            continue;
+        }
+        for entry in day.entries.iter() {
+            let name = recipes
+                .iter()
+                .find(|recipe| recipe._id == entry.recipe_id)
+                .map(|recipe| recipe.name.as_str())
+                .unwrap_or("Deleted recipe");
+            let mut text = format!("{} ({} servings)", name, entry.servings);
+            if let Some(note) = entry.note.as_ref().filter(|note| !note.trim().is_empty()) {
+                text = format!("{} – {}", text, note.trim());
+            }
+            layout.reserve(style.leading(style.body));
+            let slot = entry.slot.to_string();
+            layout.pdf.text(x, layout.y - style.body, Font::Bold, style.body, &slot);
+            layout.paragraph(&text, Font::Regular, style.body, x + slot_width, width - slot_width);
+        }
+        layout.paragraph(
+            &format!(
+                "{} kcal · {} g protein · {} g carbs · {} g fat",
+                day.nutrition.calories, day.nutrition.protein, day.nutrition.carbs, day.nutrition.fat
+            ),
+            Font::Regular,
+            style.small,
+            x + slot_width,
+            width - slot_width,
+        );
+    }
+    layout.finish()
+}
@@ -0,0 +1,45 @@
+use std::env;
+use std::time::Duration;
+
+use mongodb::bson::DateTime;
+
+use crate::models::recipe::RecipeStatus;
+
+const DEFAULT_INTERVAL: u64 = 60;
+
+/// How often recipes due to be published are looked for, every
+/// `PUBLISH_INTERVAL_SECONDS`.
+pub fn interval() -> Duration {
+    let seconds = env::var("PUBLISH_INTERVAL_SECONDS")
+        .ok()
+        .and_then(|seconds| seconds.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL);
+    Duration::from_secs(seconds.max(1))
+}
+
+/// Recipes go from draft to review, are published and then archived. A
+/// recipe in review can be sent back to draft, and so can an archived one to
+/// be worked on again.
+pub fn check_transition(from: RecipeStatus, to: RecipeStatus) -> Result<(), String> {
+    use RecipeStatus::*;
+    match (from, to) {
+        (draft, in_review) | (in_review, draft) | (in_review, published) => Ok(()),
+        (published, archived) | (archived, draft) => Ok(()),
+        _ if from == to => Err(format!("The recipe is already {}.", from)),
+        _ => Err(format!("A recipe cannot go from {} to {}.", from, to)),
+    }
+}
+
+/// Submitting for review and sending back to draft is up to the editors,
+/// anything changing what readers see takes the admin key.
+pub fn needs_admin(from: RecipeStatus, to: RecipeStatus) -> bool {
+    to == RecipeStatus::published || to == RecipeStatus::archived || from == RecipeStatus::archived
+}
+
+/// The time to publish at from RFC 3339, `None` when it isn't after `now`
+/// and the recipe is to be published right away.
+pub fn parse_publish_at(text: &str, now: DateTime) -> Result<Option<DateTime>, String> {
+    let publish_at = DateTime::parse_rfc3339_str(text.trim())
+        .map_err(|_err| format!("\"{}\" is not an RFC 3339 time.", text))?;
+    Ok(Some(publish_at).filter(|publish_at| *publish_at > now))
+}
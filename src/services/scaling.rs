@@ -0,0 +1,47 @@
+use crate::models::recipe::{Ingredient, Recipe};
+use crate::services::units;
+
+/// Scales the amount and re-expresses it in the ingredient's own measurement
+/// system, so 500 g doubled becomes 1 kg.
+pub fn scale_ingredient(ingredient: &Ingredient, factor: f64) -> Ingredient {
+    let system = units::native_system(ingredient.unit);
+    let (amount, unit) = units::normalize(ingredient.amount * factor, ingredient.unit, system);
+    Ingredient {
+        name: ingredient.name.clone(),
+        amount,
+        unit,
+        name_translations: ingredient.name_translations.clone(),
+    }
+}
+
+/// The recipe's ingredients for the given number of servings, in their own
+/// units and unrounded, for adding up before rounding once.
+pub fn scaled_ingredients(recipe: &Recipe, servings: i32) -> Vec<Ingredient> {
+    let factor = servings.max(1) as f64 / recipe.servings.max(1) as f64;
+    recipe
+        .ingredients
+        .iter()
+        .map(|ingredient| Ingredient {
+            amount: ingredient.amount * factor,
+            ..ingredient.clone()
+        })
+        .collect()
+}
+
+/// Rescales the recipe's ingredients and nutrition to the given number of servings.
+pub fn scale_recipe(recipe: Recipe, servings: i32) -> Recipe {
+    let servings = servings.max(1);
+    let factor = servings as f64 / recipe.servings.max(1) as f64;
+    let nutrition = recipe.nutrition.scaled(factor);
+    Recipe {
+        ingredients: recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| scale_ingredient(ingredient, factor))
+            .collect(),
+        nutrition_per_serving: nutrition.per_serving(servings),
+        nutrition,
+        servings,
+        ..recipe
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Document, Regex};
+
+use crate::models::taxonomy::{Facet, FacetCount, TaxonomyTerm, TermKind};
+
+/// Kinds of terms in the order their facets are listed.
+pub const FACET_KINDS: [TermKind; 4] = [TermKind::tag, TermKind::cuisine, TermKind::meal_type, TermKind::diet];
+
+/// Matches the recipes carrying every one of the terms, all recipes when
+/// there are none.
+pub fn term_filter(term_ids: Vec<ObjectId>) -> Document {
+    let mut filter = Document::new();
+    if !term_ids.is_empty() {
+        filter.insert("term_ids", doc! { "$all": term_ids });
+    }
+    filter
+}
+
+/// Matches the recipes whose name contains `q`, ignoring case, in any
+/// locale of `chain`. `None` when `q` is blank.
+pub fn name_filter(q: &str, chain: &[String], default: &str) -> Option<Document> {
+    if q.trim().is_empty() {
+        return None;
+    }
+    let pattern = q
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' { c.to_string() } else { format!("\\{}", c) })
+        .collect::<String>();
+    let regex = Regex { pattern, options: "i".to_string() };
+    let names: Vec<Document> = chain
+        .iter()
+        .map(|locale| match locale == default {
+            true => doc! { "name": regex.clone() },
+            false => doc! { format!("name_translations.{}", locale): regex.clone() },
+        })
+        .collect();
+    Some(doc! { "$or": names })
+}
+
+/// One facet per kind of term, each term with the number of matching
+/// recipes carrying it, most frequent first and then by name.
+pub fn facets(terms: &[TaxonomyTerm], counts: &HashMap<String, i64>) -> Vec<Facet> {
+    FACET_KINDS
+        .iter()
+        .map(|kind| {
+            let mut kind_counts: Vec<FacetCount> = terms
+                .iter()
+                .filter(|term| term.kind == *kind)
+                .map(|term| FacetCount {
+                    term_id: term._id.clone(),
+                    name: term.name.clone(),
+                    slug: term.slug.clone(),
+                    count: counts.get(&term._id).copied().unwrap_or(0),
+                })
+                .collect();
+            kind_counts.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
+            Facet { kind: *kind, counts: kind_counts }
+        })
+        .collect()
+}
@@ -0,0 +1,150 @@
+use std::sync::OnceLock;
+
+use mongodb::bson::oid::ObjectId;
+
+use crate::models::recipe::{Ingredient, IngredientUnit, UnitSystem};
+use crate::models::shopping_list::{Aisle, ShoppingListItem};
+use crate::services::csv::{self, Table};
+use crate::services::names;
+use crate::services::units::{self, Dimension};
+
+/// Aisle of common ingredients shipped with the server.
+const BUNDLED_AISLES: &str = include_str!("../../data/aisles.csv");
+
+pub struct AisleEntry {
+    pub aisle: Aisle,
+    pub names: Vec<String>,
+}
+
+/// Parses a table with the header `aisle,names`, names separated by `;`.
+pub fn parse_aisles(csv: &str) -> Result<Vec<AisleEntry>, String> {
+    let table = Table::parse(csv)?;
+    let (aisle, names) = (table.column("aisle")?, table.column("names")?);
+
+    let mut entries = vec![];
+    for (line, row) in table.rows.iter() {
+        entries.push(AisleEntry {
+            aisle: csv::text(row, aisle)
+                .parse::<Aisle>()
+                .map_err(|_err| format!("Unknown aisle on line {}.", line))?,
+            names: csv::text(row, names)
+                .split(';')
+                .map(names::normalize)
+                .filter(|name| !name.is_empty())
+                .collect(),
+        });
+    }
+    Ok(entries)
+}
+
+pub fn aisles() -> &'static Vec<AisleEntry> {
+    static AISLES: OnceLock<Vec<AisleEntry>> = OnceLock::new();
+    AISLES.get_or_init(|| parse_aisles(BUNDLED_AISLES).expect("data/aisles.csv is invalid."))
+}
+
+pub fn aisle_of(ingredient: &str) -> Aisle {
+    names::find_best(ingredient, aisles(), |entry| {
+        entry.names.iter().map(|name| name.as_str()).collect()
+    })
+    .map(|entry| entry.aisle)
+    .unwrap_or(Aisle::other)
+}
+
+/// Running total of one ingredient in one dimension.
+struct Total {
+    name: String,
+    key: String,
+    dimension: Dimension,
+    /// Unit the total is kept in: the first one seen
+    unit: IngredientUnit,
+    amount: f64,
+    imperial_only: bool,
+    recipe_ids: Vec<String>,
+}
+
+impl Total {
+    fn add(&mut self, amount: f64, unit: IngredientUnit, imperial: bool, recipe_ids: &[String]) {
+        self.amount += amount * units::base_size(unit) / units::base_size(self.unit);
+        self.imperial_only &= imperial;
+        for recipe_id in recipe_ids {
+            if !self.recipe_ids.contains(recipe_id) {
+                self.recipe_ids.push(recipe_id.clone());
+            }
+        }
+    }
+}
+
+/// Sums the ingredients of several recipes into shopping list items.
+/// Ingredients are merged by name, ignoring case and plurals, and amounts
+/// are added up per dimension. Volumes of an ingredient also bought by
+/// weight are converted to weight when its density is known. Totals are
+/// expressed in the metric system unless every amount was imperial, and
+/// only they are rounded.
+pub fn aggregate(ingredients: &[(String, Ingredient)]) -> Vec<ShoppingListItem> {
+    let mut totals: Vec<Total> = vec![];
+    for (recipe_id, ingredient) in ingredients {
+        let key = names::singular(&names::normalize(&ingredient.name));
+        let dimension = units::dimension(ingredient.unit);
+        let existing = totals.iter_mut().find(|total| {
+            total.key == key
+                && total.dimension == dimension
+                && (dimension != Dimension::Count || total.unit == ingredient.unit)
+        });
+        match existing {
+            Some(total) => total.add(
+                ingredient.amount,
+                ingredient.unit,
+                units::native_system(ingredient.unit) == UnitSystem::imperial,
+                std::slice::from_ref(recipe_id),
+            ),
+            None => totals.push(Total {
+                name: ingredient.name.trim().to_string(),
+                key,
+                dimension,
+                unit: ingredient.unit,
+                amount: ingredient.amount,
+                imperial_only: units::native_system(ingredient.unit) == UnitSystem::imperial,
+                recipe_ids: vec![recipe_id.clone()],
+            }),
+        }
+    }
+
+    // Fold volumes into the weight of the same ingredient.
+    let mut index = 0;
+    while index < totals.len() {
+        let volume = &totals[index];
+        let mass = totals.iter().position(|total| {
+            total.key == volume.key && total.dimension == Dimension::Mass
+        });
+        let grams = units::to_grams(volume.amount, volume.unit, &volume.name, None);
+        match (volume.dimension, mass, grams) {
+            (Dimension::Volume, Some(mass), Some(grams)) => {
+                let volume = totals.remove(index);
+                let mass = if mass > index { mass - 1 } else { mass };
+                totals[mass].add(grams, IngredientUnit::g, volume.imperial_only, &volume.recipe_ids);
+            }
+            _ => index += 1,
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|total| {
+            let system = if total.imperial_only {
+                UnitSystem::imperial
+            } else {
+                UnitSystem::metric
+            };
+            let (amount, unit) = units::normalize(total.amount, total.unit, system);
+            ShoppingListItem {
+                id: ObjectId::new().to_hex(),
+                aisle: aisle_of(&total.name),
+                name: total.name,
+                amount,
+                unit,
+                checked: false,
+                recipe_ids: total.recipe_ids,
+            }
+        })
+        .collect()
+}
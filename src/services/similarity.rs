@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::models::recipe::{Nutrition, Recipe};
+use crate::services::names;
+
+/// How much each signal counts towards the similarity score.
+const INGREDIENT_WEIGHT: f64 = 0.5;
+const TAG_WEIGHT: f64 = 0.25;
+const TIME_WEIGHT: f64 = 0.1;
+const NUTRITION_WEIGHT: f64 = 0.15;
+
+/// What the similarity of a recipe is computed from.
+#[derive(Debug, Clone)]
+struct Features {
+    id: String,
+    ingredients: Vec<String>,
+    term_ids: Vec<String>,
+    minutes: i32,
+    nutrition: Nutrition,
+}
+
+impl Features {
+    fn of(recipe: &Recipe) -> Features {
+        let mut ingredients: Vec<String> = recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| names::singular(&names::normalize(&ingredient.name)))
+            .filter(|name| !name.is_empty())
+            .collect();
+        ingredients.sort();
+        ingredients.dedup();
+        Features {
+            id: recipe._id.clone(),
+            ingredients,
+            term_ids: recipe.term_ids.clone(),
+            minutes: recipe.preparation_time_in_minutes,
+            nutrition: recipe.nutrition.per_serving(recipe.servings),
+        }
+    }
+}
+
+/// Similarity of every recipe to every other, computed from the recipe
+/// features only, so ratings and views changing don't invalidate it.
+pub struct SimilarityIndex {
+    recipes: Vec<Features>,
+    /// Inverse document frequency of each ingredient
+    idf: HashMap<String, f64>,
+}
+
+impl SimilarityIndex {
+    pub fn build(recipes: &[Recipe]) -> SimilarityIndex {
+        let recipes: Vec<Features> = recipes.iter().map(Features::of).collect();
+        let mut frequency: HashMap<String, usize> = HashMap::new();
+        for features in recipes.iter() {
+            for ingredient in features.ingredients.iter() {
+                *frequency.entry(ingredient.clone()).or_default() += 1;
+            }
+        }
+        let count = recipes.len() as f64;
+        let idf = frequency
+            .into_iter()
+            .map(|(ingredient, frequency)| (ingredient, (1.0 + count / frequency as f64).ln()))
+            .collect();
+        SimilarityIndex { recipes, idf }
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.recipes.iter().any(|features| features.id == id)
+    }
+
+    /// Ids of the recipes most similar to `id` with their score from 0 to 1,
+    /// best first. Ties are broken by id so the order is stable.
+    pub fn similar(&self, id: &str, limit: usize) -> Vec<(String, f64)> {
+        let target = match self.recipes.iter().find(|features| features.id == id) {
+            Some(target) => target,
+            None => return vec![],
+        };
+        let mut scored: Vec<(String, f64)> = self
+            .recipes
+            .iter()
+            .filter(|features| features.id != id)
+            .map(|features| (features.id.clone(), self.score(target, features)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        scored.truncate(limit);
+        scored
+    }
+
+    fn score(&self, a: &Features, b: &Features) -> f64 {
+        INGREDIENT_WEIGHT * self.ingredient_overlap(a, b)
+            + TAG_WEIGHT * jaccard(&a.term_ids, &b.term_ids)
+            + TIME_WEIGHT * proximity(a.minutes as f64, b.minutes as f64)
+            + NUTRITION_WEIGHT * nutrition_proximity(&a.nutrition, &b.nutrition)
+    }
+
+    /// Jaccard similarity of the ingredient sets, each ingredient weighted by
+    /// its IDF so sharing saffron counts more than sharing salt.
+    fn ingredient_overlap(&self, a: &Features, b: &Features) -> f64 {
+        let weight = |ingredient: &String| self.idf.get(ingredient).copied().unwrap_or(0.0);
+        let shared: f64 = a
+            .ingredients
+            .iter()
+            .filter(|ingredient| b.ingredients.contains(ingredient))
+            .map(weight)
+            .sum();
+        let total: f64 = a.ingredients.iter().map(weight).sum::<f64>()
+            + b.ingredients.iter().map(weight).sum::<f64>()
+            - shared;
+        if total > 0.0 {
+            shared / total
+        } else {
+            0.0
+        }
+    }
+}
+
+fn jaccard(a: &[String], b: &[String]) -> f64 {
+    let shared = a.iter().filter(|item| b.contains(item)).count();
+    let total = a.len() + b.len() - shared;
+    if total > 0 {
+        shared as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+/// 1 for equal values, going to 0 as one becomes a multiple of the other.
+fn proximity(a: f64, b: f64) -> f64 {
+    let larger = a.max(b);
+    if larger <= 0.0 {
+        1.0
+    } else {
+        1.0 - (a - b).abs() / larger
+    }
+}
+
+fn nutrition_proximity(a: &Nutrition, b: &Nutrition) -> f64 {
+    let pairs = [
+        (a.calories, b.calories),
+        (a.protein, b.protein),
+        (a.carbs, b.carbs),
+        (a.fat, b.fat),
+    ];
+    pairs
+        .iter()
+        .map(|(a, b)| proximity(*a as f64, *b as f64))
+        .sum::<f64>()
+        / pairs.len() as f64
+}
+
+static INDEX: RwLock<Option<Arc<SimilarityIndex>>> = RwLock::new(None);
+
+/// Bumped on every recipe write, so an index built from recipes read
+/// before the write is never cached.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+pub fn cached() -> Option<Arc<SimilarityIndex>> {
+    INDEX.read().ok().and_then(|index| index.clone())
+}
+
+/// Caches the index if no recipe was written since `generation` was read.
+pub fn store(index: SimilarityIndex, generation: u64) -> Arc<SimilarityIndex> {
+    let index = Arc::new(index);
+    if let Ok(mut cached) = INDEX.write() {
+        if GENERATION.load(Ordering::SeqCst) == generation {
+            *cached = Some(index.clone());
+        }
+    }
+    index
+}
+
+/// Drops the cached index. Called whenever recipes change.
+pub fn invalidate() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut cached) = INDEX.write() {
+        *cached = None;
+    }
+}
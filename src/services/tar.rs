@@ -0,0 +1,132 @@
+use std::io::{self, Read};
+
+/// Size of tar headers and of the blocks file contents are padded to.
+const BLOCK: usize = 512;
+
+/// Writes the octal `value` into a header field, NUL terminated.
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{:0width$o}", value, width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+}
+
+fn read_octal(field: &[u8]) -> Option<u64> {
+    let text: String = field
+        .iter()
+        .take_while(|byte| **byte != 0)
+        .map(|byte| *byte as char)
+        .collect();
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(text, 8).ok()
+}
+
+fn read_name(field: &[u8]) -> String {
+    let end = field.iter().position(|byte| *byte == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).to_string()
+}
+
+/// Sum of the header bytes with the checksum field counted as spaces.
+fn checksum(header: &[u8]) -> u64 {
+    header
+        .iter()
+        .enumerate()
+        .map(|(index, byte)| if (148..156).contains(&index) { b' ' } else { *byte } as u64)
+        .sum()
+}
+
+/// Writes a ustar archive of regular files. Names are at most 100 bytes and
+/// `mtime` is the modification time of every file in seconds since the epoch.
+pub fn write(entries: &[(String, Vec<u8>)], mtime: i64) -> Result<Vec<u8>, String> {
+    let mut archive = vec![];
+    for (name, data) in entries.iter() {
+        if name.is_empty() || name.len() > 100 {
+            return Err(format!("Cannot store \"{}\" in a tar archive.", name));
+        }
+        let mut header = [0u8; BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        write_octal(&mut header[100..108], 0o644);
+        write_octal(&mut header[108..116], 0);
+        write_octal(&mut header[116..124], 0);
+        write_octal(&mut header[124..136], data.len() as u64);
+        write_octal(&mut header[136..148], mtime.max(0) as u64);
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        let sum = format!("{:06o}\0 ", checksum(&header));
+        header[148..156].copy_from_slice(sum.as_bytes());
+
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(data);
+        let padding = (BLOCK - data.len() % BLOCK) % BLOCK;
+        archive.resize(archive.len() + padding, 0);
+    }
+    archive.resize(archive.len() + BLOCK * 2, 0);
+    Ok(archive)
+}
+
+/// Reads into `buf` until it is full or the input ends, returning how much
+/// was read.
+fn fill<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, String> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(format!("Reading the tar archive failed: {}", err)),
+        }
+    }
+    Ok(filled)
+}
+
+/// Goes through the regular files of a tar archive in order, handing each
+/// name and its contents to `entry` without holding the archive in memory.
+/// Whatever `entry` leaves unread is skipped. Directories, links and
+/// extended headers are passed over.
+pub fn read_stream<R, F>(mut reader: R, mut entry: F) -> Result<(), String>
+where
+    R: Read,
+    F: FnMut(&str, &mut dyn Read) -> Result<(), String>,
+{
+    let mut offset: u64 = 0;
+    let mut header = [0u8; BLOCK];
+    loop {
+        if fill(&mut reader, &mut header)? < BLOCK {
+            break;
+        }
+        if header.iter().all(|byte| *byte == 0) {
+            return Ok(());
+        }
+        if read_octal(&header[148..156]) != Some(checksum(&header)) {
+            return Err(format!("The tar header at byte {} is damaged.", offset));
+        }
+        let size = read_octal(&header[124..136])
+            .ok_or(format!("The tar header at byte {} has no valid size.", offset))?;
+        let mut name = read_name(&header[..100]);
+        if &header[257..262] == b"ustar" {
+            let prefix = read_name(&header[345..500]);
+            if !prefix.is_empty() {
+                name = format!("{}/{}", prefix, name);
+            }
+        }
+
+        let padded = size + (BLOCK as u64 - size % BLOCK as u64) % BLOCK as u64;
+        let mut data = (&mut reader).take(padded);
+        if header[156] == b'0' || header[156] == 0 {
+            entry(&name, &mut (&mut data).take(size))?;
+        }
+        io::copy(&mut data, &mut io::sink())
+            .map_err(|err| format!("Reading the tar archive failed: {}", err))?;
+        if data.limit() > padded - size {
+            return Err(format!("The tar archive ends inside \"{}\".", name));
+        }
+        offset += BLOCK as u64 + padded;
+    }
+    if offset == 0 {
+        return Err("The body is not a tar archive.".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::env;
+
+use mongodb::bson::{DateTime, Document};
+
+use crate::models::trash::{TrashItem, TrashKind};
+
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// Days trashed resources are kept before they are purged, from
+/// `TRASH_RETENTION_DAYS`.
+pub fn retention_days() -> i64 {
+    env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+        .max(0)
+}
+
+/// Resources trashed before this time are due to be purged at `now`.
+pub fn purge_before(now: DateTime, retention_days: i64) -> DateTime {
+    DateTime::from_millis(now.timestamp_millis() - retention_days * DAY_MILLIS)
+}
+
+/// What to call a trashed document: its name, title or text, the day and
+/// meal of a meal plan entry, the day of a daily pick, or else the path of
+/// its media.
+fn label(document: &Document) -> String {
+    for field in ["name", "title", "description", "text"] {
+        if let Ok(text) = document.get_str(field) {
+            if !text.trim().is_empty() {
+                return text.trim().to_string();
+            }
+        }
+    }
+    if let (Ok(date), Ok(slot)) = (document.get_str("date"), document.get_str("slot")) {
+        return format!("{} {}", date, slot);
+    }
+    if let Ok(date) = document.get_str("date") {
+        return date.to_string();
+    }
+    document
+        .get_document("gif")
+        .unwrap_or(document)
+        .get_str("path")
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The listing entry of a document from the trash, `None` if it isn't one.
+pub fn to_item(kind: TrashKind, document: &Document, retention_days: i64) -> Option<TrashItem> {
+    let id = document.get_object_id("_id").ok()?;
+    let deleted_at = *document.get_datetime("deleted_at").ok()?;
+    let purge_at = DateTime::from_millis(deleted_at.timestamp_millis() + retention_days * DAY_MILLIS);
+    Some(TrashItem {
+        kind,
+        _id: id.to_string(),
+        label: label(document),
+        user_id: document.get_str("user_id").ok().map(str::to_string),
+        deleted_at: deleted_at.to_string(),
+        purge_at: purge_at.to_string(),
+    })
+}
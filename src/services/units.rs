@@ -0,0 +1,179 @@
+use crate::models::recipe::{Ingredient, IngredientUnit, Recipe, UnitSystem};
+use crate::services::nutrition;
+
+/// A pinch is taken as a sixteenth of a teaspoon.
+const PINCH_IN_ML: f64 = 5.0 / 16.0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Dimension {
+    Mass,
+    Volume,
+    Count,
+}
+
+pub fn dimension(unit: IngredientUnit) -> Dimension {
+    match unit {
+        IngredientUnit::mg | IngredientUnit::g | IngredientUnit::kg => Dimension::Mass,
+        IngredientUnit::oz | IngredientUnit::lb => Dimension::Mass,
+        IngredientUnit::ml | IngredientUnit::dcl | IngredientUnit::l => Dimension::Volume,
+        IngredientUnit::tsp | IngredientUnit::tbsp => Dimension::Volume,
+        IngredientUnit::cup | IngredientUnit::fl_oz => Dimension::Volume,
+        IngredientUnit::piece | IngredientUnit::pinch | IngredientUnit::clove => Dimension::Count,
+    }
+}
+
+/// Size of the unit in grams for masses, millilitres for volumes and pieces for counts.
+pub fn base_size(unit: IngredientUnit) -> f64 {
+    match unit {
+        IngredientUnit::mg => 0.001,
+        IngredientUnit::g => 1.0,
+        IngredientUnit::kg => 1000.0,
+        IngredientUnit::oz => 28.349523125,
+        IngredientUnit::lb => 453.59237,
+        IngredientUnit::ml => 1.0,
+        IngredientUnit::dcl => 100.0,
+        IngredientUnit::l => 1000.0,
+        IngredientUnit::tsp => 5.0,
+        IngredientUnit::tbsp => 15.0,
+        IngredientUnit::cup => 240.0,
+        IngredientUnit::fl_oz => 29.5735295625,
+        IngredientUnit::piece | IngredientUnit::pinch | IngredientUnit::clove => 1.0,
+    }
+}
+
+/// The system a unit belongs to. Spoons are shared and count as metric.
+pub fn native_system(unit: IngredientUnit) -> UnitSystem {
+    match unit {
+        IngredientUnit::oz | IngredientUnit::lb | IngredientUnit::cup | IngredientUnit::fl_oz => {
+            UnitSystem::imperial
+        }
+        _ => UnitSystem::metric,
+    }
+}
+
+/// Weight of an amount of the ingredient in grams. Volumes use its density
+/// in the food composition dataset, then `fallback_density`. `None` when the weight can't be known,
+/// e.g. pieces of an ingredient without a piece weight.
+pub fn to_grams(
+    amount: f64,
+    unit: IngredientUnit,
+    ingredient: &str,
+    fallback_density: Option<f64>,
+) -> Option<f64> {
+    let weights = nutrition::lookup(ingredient);
+    let density = weights.and_then(|weights| weights.density).or(fallback_density);
+    match (dimension(unit), unit) {
+        (Dimension::Mass, _) => Some(amount * base_size(unit)),
+        (Dimension::Volume, _) => density.map(|density| amount * base_size(unit) * density),
+        (Dimension::Count, IngredientUnit::pinch) => {
+            density.map(|density| amount * PINCH_IN_ML * density)
+        }
+        (Dimension::Count, _) => weights
+            .and_then(|weights| weights.piece_grams)
+            .map(|grams| amount * grams),
+    }
+}
+
+/// Converts an amount of the ingredient between any two units, going
+/// through its weight when the units measure different things.
+pub fn convert(
+    amount: f64,
+    from: IngredientUnit,
+    to: IngredientUnit,
+    ingredient: &str,
+) -> Option<f64> {
+    if from == to {
+        return Some(amount);
+    }
+    if dimension(from) == dimension(to) && dimension(from) != Dimension::Count {
+        return Some(amount * base_size(from) / base_size(to));
+    }
+    let grams = to_grams(amount, from, ingredient, None)?;
+    let per_unit = to_grams(1.0, to, ingredient, None)?;
+    Some(grams / per_unit)
+}
+
+/// Rounds to a precision that makes sense for the unit: steps of 5 and 10 for
+/// large gram and millilitre amounts, twentieths of kilos and litres, and
+/// quarters of spoons, cups and ounces.
+pub fn round_amount(amount: f64, unit: IngredientUnit) -> f64 {
+    let step = match unit {
+        IngredientUnit::mg | IngredientUnit::g | IngredientUnit::ml => {
+            if amount >= 1000.0 {
+                10.0
+            } else if amount >= 100.0 {
+                5.0
+            } else if amount >= 10.0 {
+                1.0
+            } else {
+                0.5
+            }
+        }
+        IngredientUnit::kg | IngredientUnit::l | IngredientUnit::dcl => 0.05,
+        IngredientUnit::piece | IngredientUnit::clove => 0.5,
+        IngredientUnit::pinch => 1.0,
+        _ => 0.25,
+    };
+    let rounded = (amount / step).round() * step;
+    if rounded == 0.0 && amount > 0.0 {
+        step
+    } else {
+        // Keeps 0.1 + 0.2 style float noise out of the responses.
+        (rounded * 1000.0).round() / 1000.0
+    }
+}
+
+/// Units an amount can be expressed in, smallest first, each with the
+/// smallest amount it is used for.
+fn ladder(source: IngredientUnit, system: UnitSystem) -> Vec<(IngredientUnit, f64)> {
+    use IngredientUnit::*;
+    match (dimension(source), system) {
+        (Dimension::Count, _) => vec![(source, 0.0)],
+        (Dimension::Mass, UnitSystem::metric) => vec![(mg, 0.0), (g, 1.0), (kg, 1.0)],
+        (Dimension::Mass, UnitSystem::imperial) => vec![(oz, 0.0), (lb, 1.0)],
+        (Dimension::Volume, UnitSystem::metric) => match source {
+            tsp | tbsp | cup => vec![(tsp, 0.0), (tbsp, 1.0), (ml, 60.0), (l, 1.0)],
+            dcl => vec![(ml, 0.0), (dcl, 1.0), (l, 1.0)],
+            _ => vec![(ml, 0.0), (l, 1.0)],
+        },
+        (Dimension::Volume, UnitSystem::imperial) => match source {
+            fl_oz => vec![(tsp, 0.0), (tbsp, 1.0), (fl_oz, 1.0)],
+            _ => vec![(tsp, 0.0), (tbsp, 1.0), (cup, 0.25)],
+        },
+    }
+}
+
+/// Expresses the amount in the given system, in the largest unit that
+/// reads naturally, e.g. 1500 g as 1.5 kg and 0.5 kg as 500 g.
+pub fn normalize(amount: f64, unit: IngredientUnit, system: UnitSystem) -> (f64, IngredientUnit) {
+    let base = amount * base_size(unit);
+    let ladder = ladder(unit, system);
+    let (target, _) = ladder
+        .iter()
+        .rev()
+        .find(|(candidate, minimum)| base / base_size(*candidate) >= *minimum)
+        .unwrap_or(&ladder[0]);
+    (round_amount(base / base_size(*target), *target), *target)
+}
+
+pub fn express(ingredient: &Ingredient, system: UnitSystem) -> Ingredient {
+    let (amount, unit) = normalize(ingredient.amount, ingredient.unit, system);
+    Ingredient {
+        name: ingredient.name.clone(),
+        amount,
+        unit,
+        name_translations: ingredient.name_translations.clone(),
+    }
+}
+
+/// Converts every ingredient of the recipe to the measurement system.
+pub fn express_recipe(recipe: Recipe, system: UnitSystem) -> Recipe {
+    Recipe {
+        ingredients: recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| express(ingredient, system))
+            .collect(),
+        ..recipe
+    }
+}
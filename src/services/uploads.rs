@@ -0,0 +1,76 @@
+use std::env;
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+use crate::models::recipe::Recipe;
+
+/// Directory uploaded images and GIFs are stored in, `UPLOAD_DIR` or
+/// `threesixfive-uploads` in the temporary directory.
+pub fn upload_dir() -> PathBuf {
+    match env::var("UPLOAD_DIR") {
+        Ok(dir) if !dir.trim().is_empty() => PathBuf::from(dir.trim()),
+        _ => env::temp_dir().join("threesixfive-uploads"),
+    }
+}
+
+/// A new path in the upload directory for a file called `title`, keeping a
+/// safe version of the name to make the directory easier to look through.
+pub fn upload_path(title: &str) -> Result<PathBuf, String> {
+    let dir = upload_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|err| format!("Cannot create {}: {}", dir.display(), err))?;
+    let file_name: String = title
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '_' || *c == '-')
+        .take(64)
+        .collect();
+    Ok(dir.join(format!("{}-{}", Uuid::new_v4(), file_name.trim_start_matches('.'))))
+}
+
+/// The stored file at `path` if it lies inside the upload directory, once
+/// links and `..` are resolved. Media is only ever read through here.
+pub fn resolve(path: &str) -> Option<PathBuf> {
+    if path.is_empty() {
+        return None;
+    }
+    let dir = std::fs::canonicalize(upload_dir()).ok()?;
+    let file = std::fs::canonicalize(path).ok()?;
+    match file.starts_with(&dir) && file.is_file() {
+        true => Some(file),
+        false => None,
+    }
+}
+
+fn is_remote(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+/// Keeps clients from pointing recipes at files of the server. Embedded
+/// images and steps take their id and media path from the `stored` recipe
+/// when they match one of its own; others keep only media URLs.
+pub fn keep_stored_media(mut recipe: Recipe, stored: Option<&Recipe>) -> Recipe {
+    for image in recipe.images.iter_mut() {
+        match stored.and_then(|stored| stored.images.iter().find(|known| known._id == image._id)) {
+            Some(known) if !image._id.is_empty() => image.path = known.path.clone(),
+            _ => {
+                image._id = String::new();
+                if !is_remote(&image.path) {
+                    image.path = String::new();
+                }
+            }
+        }
+    }
+    for step in recipe.steps.iter_mut() {
+        match stored.and_then(|stored| stored.steps.iter().find(|known| known._id == step._id)) {
+            Some(known) if !step._id.is_empty() => step.gif.path = known.gif.path.clone(),
+            _ => {
+                step._id = String::new();
+                if !is_remote(&step.gif.path) {
+                    step.gif.path = String::new();
+                }
+            }
+        }
+    }
+    recipe
+}
@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+use mongodb::bson::oid::ObjectId;
+
+use crate::models::catalog::{CatalogAction, ConflictPolicy, CATALOG_VERSION};
+use crate::models::gif::{Gif, RecipeStep};
+use crate::models::image::Image;
+use crate::models::recipe::Recipe;
+use crate::services::catalog::{extract_media, media_entry, plan_import, referenced_media, remap_recipe, summarize, CatalogArchive};
+use crate::services::tar;
+
+use super::fixtures::recipe;
+
+const SOUP_ID: &str = "634a1b2c3d4e5f6a7b8c9d0e";
+const CAKE_ID: &str = "634a1b2c3d4e5f6a7b8c9d0f";
+
+fn image(id: &str, path: &str) -> Image {
+    Image {
+        _id: id.to_string(),
+        path: path.to_string(),
+        width: 10,
+        height: 10,
+        title: "photo".to_string(),
+        created_at: String::new(),
+    }
+}
+
+fn step(id: &str, path: &str) -> RecipeStep {
+    RecipeStep {
+        _id: id.to_string(),
+        description: "Stir.".to_string(),
+        description_translations: Default::default(),
+        duration_in_seconds: None,
+        gif: Gif {
+            path: path.to_string(),
+            width: 10,
+            height: 10,
+            title: "stir".to_string(),
+        },
+        created_at: String::new(),
+    }
+}
+
+fn soup() -> Recipe {
+    let mut soup = recipe(SOUP_ID, 2, 600);
+    soup.images = vec![image("img1", "/tmp/soup.jpg"), image("", "https://example.com/soup.jpg")];
+    soup.steps = vec![step("step1", "/tmp/stir.gif")];
+    soup
+}
+
+fn read_tar(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut entries = vec![];
+    tar::read_stream(bytes, |name, data| {
+        let mut contents = vec![];
+        data.read_to_end(&mut contents).unwrap();
+        entries.push((name.to_string(), contents));
+        Ok(())
+    })?;
+    Ok(entries)
+}
+
+#[test]
+fn tar_round_trip() {
+    let entries = vec![
+        ("manifest.json".to_string(), b"{}".to_vec()),
+        ("media/0-photo.jpg".to_string(), vec![7u8; 1300]),
+        ("empty".to_string(), vec![]),
+    ];
+    let archive = tar::write(&entries, 1_665_000_000).unwrap();
+    assert_eq!(archive.len() % 512, 0);
+    assert_eq!(&archive[257..262], b"ustar");
+    assert_eq!(read_tar(&archive).unwrap(), entries);
+
+    let mut damaged = archive.clone();
+    damaged[0] = b'X';
+    assert!(read_tar(&damaged).is_err());
+    assert!(read_tar(&archive[..1600]).is_err());
+    assert!(read_tar(b"").is_err());
+    assert!(tar::write(&[("a".repeat(101), vec![])], 0).is_err());
+}
+
+#[test]
+fn archive_round_trip_with_media() {
+    let mut archive = CatalogArchive::new(
+        vec![soup()],
+        vec![step("step1", "/tmp/stir.gif")],
+        vec![image("img1", "/tmp/soup.jpg")],
+        "2022-10-15T10:00:00+00:00".to_string(),
+    );
+    assert_eq!(archive.media_paths(), vec!["/tmp/soup.jpg", "/tmp/stir.gif"]);
+    archive.add_media("/tmp/soup.jpg", b"jpeg".to_vec());
+
+    let bytes = archive.to_bytes(0).unwrap();
+    let read = CatalogArchive::read(bytes.as_slice()).unwrap();
+    assert_eq!(read.manifest.version, CATALOG_VERSION);
+    assert_eq!((read.manifest.recipes, read.manifest.media), (1, 1));
+    assert_eq!(read.recipes[0].images[0].path, "media/0-soup.jpg");
+    assert_eq!(read.recipes[0].images[1].path, "https://example.com/soup.jpg");
+    assert_eq!(read.recipes[0].steps[0].gif.path, "/tmp/stir.gif");
+    assert_eq!(read.images[0].path, "media/0-soup.jpg");
+    assert_eq!(read.steps[0].description, "Stir.");
+    assert!(read.has_media("media/0-soup.jpg"));
+    assert!(read.media.iter().all(|(_, data)| data.is_empty()));
+
+    let path = std::env::temp_dir().join(format!("{}-soup.jpg", ObjectId::new()));
+    let paths = HashMap::from([("media/0-soup.jpg".to_string(), path.clone())]);
+    extract_media(bytes.as_slice(), &paths).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"jpeg");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn refuses_foreign_and_newer_archives() {
+    let mut archive = CatalogArchive::new(vec![], vec![], vec![], String::new());
+    archive.manifest.version = CATALOG_VERSION + 1;
+    let error = CatalogArchive::read(archive.to_bytes(0).unwrap().as_slice()).unwrap_err();
+    assert!(error.contains("version"));
+
+    archive.manifest.version = CATALOG_VERSION;
+    archive.manifest.format = "something-else".to_string();
+    assert!(CatalogArchive::read(archive.to_bytes(0).unwrap().as_slice()).is_err());
+
+    let broken = tar::write(&[("manifest.json".to_string(), b"{}".to_vec())], 0).unwrap();
+    assert!(CatalogArchive::read(broken.as_slice()).is_err());
+}
+
+#[test]
+fn plans_conflicts_by_policy() {
+    let recipes = vec![soup(), recipe(CAKE_ID, 4, 1600), recipe("not-an-id", 1, 100)];
+    let existing = HashSet::from([SOUP_ID.to_string()]);
+
+    let skip = plan_import(&recipes, &existing, &HashSet::new(), ConflictPolicy::skip);
+    let actions: Vec<CatalogAction> = skip.iter().map(|item| item.action).collect();
+    assert_eq!(actions, vec![CatalogAction::skip, CatalogAction::create, CatalogAction::create]);
+    assert_eq!(skip[1].target_id, CAKE_ID);
+    assert_ne!(skip[2].target_id, "not-an-id");
+
+    let overwrite = plan_import(&recipes, &existing, &HashSet::new(), ConflictPolicy::overwrite);
+    assert_eq!(overwrite[0].action, CatalogAction::overwrite);
+    assert_eq!(overwrite[0].target_id, SOUP_ID);
+
+    let duplicate = plan_import(&recipes, &existing, &HashSet::new(), ConflictPolicy::duplicate);
+    assert_eq!(duplicate[0].action, CatalogAction::duplicate);
+    assert_ne!(duplicate[0].target_id, SOUP_ID);
+
+    let report = summarize(duplicate, CATALOG_VERSION, ConflictPolicy::duplicate, true);
+    assert_eq!((report.created, report.duplicated, report.skipped), (2, 1, 0));
+}
+
+#[test]
+fn overwriting_a_trashed_recipe_restores_it() {
+    let recipes = vec![soup(), recipe(CAKE_ID, 4, 1600)];
+    let trashed = HashSet::from([SOUP_ID.to_string()]);
+
+    let skip = plan_import(&recipes, &HashSet::new(), &trashed, ConflictPolicy::skip);
+    assert_eq!(skip[0].action, CatalogAction::skip);
+
+    let overwrite = plan_import(&recipes, &HashSet::new(), &trashed, ConflictPolicy::overwrite);
+    assert_eq!(overwrite[0].action, CatalogAction::restore);
+    assert_eq!(overwrite[0].target_id, SOUP_ID);
+    assert_eq!(overwrite[1].action, CatalogAction::create);
+    let report = summarize(overwrite, CATALOG_VERSION, ConflictPolicy::overwrite, false);
+    assert_eq!((report.restored, report.overwritten, report.created), (1, 0, 1));
+}
+
+#[test]
+fn repeated_ids_in_an_archive_conflict() {
+    let recipes = vec![recipe(CAKE_ID, 4, 1600), recipe(CAKE_ID, 4, 1600)];
+    let items = plan_import(&recipes, &HashSet::new(), &HashSet::new(), ConflictPolicy::skip);
+    assert_eq!(items[0].action, CatalogAction::create);
+    assert_eq!(items[1].action, CatalogAction::skip);
+}
+
+#[test]
+fn remaps_ids_of_images_and_steps() {
+    let mut archived = soup();
+    archived.images[0].path = "media/0-soup.jpg".to_string();
+    let ids = HashMap::from([
+        ("img1".to_string(), "new-img".to_string()),
+        ("step1".to_string(), "new-step".to_string()),
+    ]);
+
+    let remapped = remap_recipe(archived.clone(), CAKE_ID, &ids);
+    assert_eq!(remapped._id, CAKE_ID);
+    assert_eq!(remapped.images[0]._id, "new-img");
+    assert_eq!(remapped.images[1]._id, "");
+    assert_eq!(remapped.steps[0]._id, "new-step");
+    assert_eq!(referenced_media([archived].iter()), vec!["media/0-soup.jpg"]);
+    assert_eq!(media_entry(3, "C:\\uploads\\my photo?.jpg"), "media/3-myphoto.jpg");
+}
@@ -0,0 +1,43 @@
+use crate::models::collection::{Collection, Visibility};
+use crate::services::collections::{insert_at, reorder};
+
+#[test]
+fn inserts_and_moves_recipes() {
+    let ids = vec!["a", "b", "c"];
+    assert_eq!(insert_at(&ids, "d", None), vec!["a", "b", "c", "d"]);
+    assert_eq!(insert_at(&ids, "d", Some(0)), vec!["d", "a", "b", "c"]);
+    assert_eq!(insert_at(&ids, "d", Some(99)), vec!["a", "b", "c", "d"]);
+    // Already in the collection, so moved instead of added twice
+    assert_eq!(insert_at(&ids, "c", Some(0)), vec!["c", "a", "b"]);
+    assert_eq!(insert_at(&ids, "a", None), vec!["b", "c", "a"]);
+}
+
+#[test]
+fn reorders_only_the_same_recipes() {
+    let ids = vec!["a", "b", "c"];
+    assert_eq!(reorder(&ids, &["c", "a", "b"]), Ok(vec!["c", "a", "b"]));
+    assert!(reorder(&ids, &["a", "b"]).is_err());
+    assert!(reorder(&ids, &["a", "b", "d"]).is_err());
+    assert!(reorder(&ids, &["a", "a", "b"]).is_err());
+}
+
+#[test]
+fn private_collections_are_only_visible_to_their_owner() {
+    let mut collection = Collection {
+        _id: "collection".to_string(),
+        user_id: "owner".to_string(),
+        name: "Weeknight dinners".to_string(),
+        description: None,
+        cover_image: None,
+        visibility: Visibility::private,
+        recipe_ids: vec![],
+        created_at: String::new(),
+        updated_at: String::new(),
+    };
+    assert!(collection.is_visible_to(Some("owner")));
+    assert!(!collection.is_visible_to(Some("someone")));
+    assert!(!collection.is_visible_to(None));
+
+    collection.visibility = Visibility::unlisted;
+    assert!(collection.is_visible_to(None));
+}
@@ -0,0 +1,69 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+
+use crate::models::comment::{CommentDocument, CommentThread};
+use crate::models::ObjectConvertable;
+
+const MINUTE_MILLIS: i64 = 60 * 1000;
+
+fn comment(parent: Option<&CommentDocument>, text: &str, minute: i64) -> CommentDocument {
+    let created_at = DateTime::from_millis(1_666_000_000_000 + minute * MINUTE_MILLIS);
+    CommentDocument {
+        _id: Some(ObjectId::new()),
+        recipe_id: ObjectId::new(),
+        step_id: None,
+        parent_id: parent.and_then(|parent| parent._id),
+        thread_id: parent.map(|parent| parent.thread_id.or(parent._id).unwrap()),
+        user_id: "u1".to_string(),
+        text: text.to_string(),
+        deleted: false,
+        deleted_at: None,
+        created_at,
+        updated_at: created_at,
+    }
+}
+
+#[test]
+fn nests_replies_oldest_first() {
+    let root = comment(None, "Can I use leeks?", 0);
+    let first = comment(Some(&root), "Yes", 5);
+    let second = comment(Some(&root), "Only the white part", 600);
+    let nested = comment(Some(&first), "Thanks!", 7);
+    let other = comment(None, "Great soup", 1);
+
+    let threads = CommentThread::build(
+        vec![other.to_object(), root.to_object()],
+        vec![second.clone(), nested.clone(), first.clone()],
+    );
+    assert_eq!(threads.len(), 2);
+    assert_eq!(threads[0].comment.text, "Great soup");
+    assert!(threads[0].replies.is_empty());
+
+    let replies = &threads[1].replies;
+    let texts: Vec<&str> = replies.iter().map(|reply| reply.comment.text.as_str()).collect();
+    assert_eq!(texts, vec!["Yes", "Only the white part"]);
+    assert_eq!(replies[0].replies[0].comment.text, "Thanks!");
+    assert_eq!(nested.thread_id, root._id);
+}
+
+#[test]
+fn deleted_comments_are_tombstones() {
+    let mut root = comment(None, "Too salty", 0);
+    let reply = comment(Some(&root), "Use less stock", 1);
+
+    root.deleted_at = Some(DateTime::from_millis(1_666_000_000_000 + 2 * MINUTE_MILLIS));
+    let trashed = root.to_object();
+    assert!(trashed.deleted);
+    assert_eq!(trashed.text, "");
+
+    let threads = CommentThread::build(vec![trashed], vec![reply]);
+    assert!(threads[0].comment.deleted);
+    assert_eq!(threads[0].replies[0].comment.text, "Use less stock");
+
+    root.deleted_at = None;
+    assert_eq!(root.to_object().text, "Too salty");
+
+    let mut purged = comment(None, "", 0);
+    purged.deleted = true;
+    assert!(purged.to_object().deleted);
+}
@@ -0,0 +1,81 @@
+use mongodb::bson::DateTime;
+
+use crate::models::gif::{Gif, RecipeStep};
+use crate::services::cooking::{cooking_step, detect_duration, due_timers, new_timer, step_duration};
+
+use super::fixtures::recipe;
+
+fn step(description: &str, duration_in_seconds: Option<i32>) -> RecipeStep {
+    RecipeStep {
+        _id: "".to_string(),
+        description: description.to_string(),
+        description_translations: Default::default(),
+        duration_in_seconds,
+        gif: Gif {
+            path: "".to_string(),
+            width: 0,
+            height: 0,
+            title: "".to_string(),
+        },
+        created_at: String::new(),
+    }
+}
+
+#[test]
+fn detects_durations_in_descriptions() {
+    assert_eq!(detect_duration("Simmer 10 minutes."), Some(600));
+    assert_eq!(detect_duration("Bake for 25-30 min until golden"), Some(1500));
+    assert_eq!(detect_duration("Cook 2 to 3 mins per side"), Some(120));
+    assert_eq!(detect_duration("Rest 1 hour and 15 minutes"), Some(4500));
+    assert_eq!(detect_duration("Braise 1.5 hours"), Some(5400));
+    assert_eq!(detect_duration("Let it rise for an hour"), Some(3600));
+    assert_eq!(detect_duration("Whisk 30 sec, then 1/2 min more"), Some(30));
+    assert_eq!(detect_duration("Boil the pasta 8min"), Some(480));
+}
+
+#[test]
+fn ignores_numbers_that_are_not_durations() {
+    assert_eq!(detect_duration("Preheat the oven to 180 degrees."), None);
+    assert_eq!(detect_duration("Add 2 eggs, then stir 5 minutes"), Some(300));
+    assert_eq!(detect_duration("Simmer 10 minutes. 5 minutes before the end add salt"), Some(600));
+    assert_eq!(detect_duration("Add a pinch of salt"), None);
+    assert_eq!(detect_duration(""), None);
+}
+
+#[test]
+fn prefers_the_explicit_duration() {
+    assert_eq!(step_duration(&step("Simmer 10 minutes", Some(900))), Some(900));
+    assert_eq!(step_duration(&step("Simmer 10 minutes", Some(0))), Some(600));
+    assert_eq!(step_duration(&step("Serve.", None)), None);
+}
+
+#[test]
+fn shows_one_step_at_a_time() {
+    let mut soup = recipe("soup", 2, 400);
+    soup.steps = vec![step("Chop the onions.", None), step("Simmer 20 minutes.", None)];
+
+    let second = cooking_step(&soup, 1).unwrap();
+    assert_eq!((second.index, second.step_count), (1, 2));
+    assert_eq!(second.description, "Simmer 20 minutes.");
+    assert_eq!(second.duration_in_seconds, Some(1200));
+    assert!(cooking_step(&soup, 2).is_none());
+    assert!(cooking_step(&soup, -1).is_none());
+}
+
+#[test]
+fn timers_expire_once() {
+    let now = DateTime::from_millis(1_666_000_000_000);
+    let mut timer = new_timer("t1".to_string(), 1, None, 600, now);
+    assert_eq!(timer.label, "Step 2");
+    assert_eq!(timer.ends_at.timestamp_millis() - now.timestamp_millis(), 600_000);
+    let named = new_timer("t2".to_string(), 0, Some("Pasta".to_string()), 480, now);
+    assert_eq!(named.label, "Pasta");
+
+    let timers = vec![timer.clone(), named];
+    let later = DateTime::from_millis(now.timestamp_millis() + 500_000);
+    assert_eq!(due_timers(&timers, later).iter().map(|timer| timer.id.as_str()).collect::<Vec<&str>>(), vec!["t2"]);
+    assert!(due_timers(&timers, now).is_empty());
+
+    timer.expired = true;
+    assert!(due_timers(&[timer], DateTime::from_millis(now.timestamp_millis() + 700_000)).is_empty());
+}
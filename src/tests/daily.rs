@@ -0,0 +1,67 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::services::daily::{pick, season_of, DailyCandidate, Season};
+use crate::services::meal_plan::parse_date;
+
+use super::fixtures::recipe;
+
+fn date(value: &str) -> NaiveDate {
+    parse_date(value).unwrap()
+}
+
+fn candidates(count: usize) -> Vec<DailyCandidate> {
+    (0..count)
+        .map(|index| DailyCandidate {
+            recipe: recipe(&format!("recipe-{:02}", index), 1, 500),
+            seasons: vec![],
+        })
+        .collect()
+}
+
+#[test]
+fn knows_the_seasons() {
+    assert_eq!(season_of(date("2024-01-10")), Season::winter);
+    assert_eq!(season_of(date("2024-04-10")), Season::spring);
+    assert_eq!(season_of(date("2024-07-10")), Season::summer);
+    assert_eq!(season_of(date("2024-10-10")), Season::autumn);
+    assert_eq!("fall".parse::<Season>(), Ok(Season::autumn));
+}
+
+#[test]
+fn picks_the_same_recipe_for_a_date() {
+    let recipes = candidates(20);
+    let first = pick(date("2024-05-13"), &recipes, &[]).unwrap();
+    let mut reversed = recipes.clone();
+    reversed.reverse();
+    assert_eq!(pick(date("2024-05-13"), &reversed, &[]).unwrap()._id, first._id);
+    assert!(pick(date("2024-05-13"), &[], &[]).is_none());
+}
+
+#[test]
+fn rotates_without_repeating_within_the_window() {
+    let recipes = candidates(10);
+    let mut recent: Vec<String> = vec![];
+    let start = date("2024-05-01");
+    for offset in 0..10 {
+        let picked = pick(start + Duration::days(offset), &recipes, &recent).unwrap();
+        assert!(!recent.contains(&picked._id));
+        recent.push(picked._id);
+    }
+    // Every recipe was picked recently, so the pick falls back to all of them.
+    assert!(pick(start + Duration::days(10), &recipes, &recent).is_some());
+}
+
+#[test]
+fn honors_seasons() {
+    let mut recipes = candidates(3);
+    recipes[0].seasons = vec![Season::summer];
+    recipes[1].seasons = vec![Season::winter];
+    recipes[2].seasons = vec![Season::winter];
+    for day in 0..30 {
+        let summer_day = date("2024-07-01") + Duration::days(day);
+        assert_eq!(pick(summer_day, &recipes, &[]).unwrap()._id, "recipe-00");
+        let winter_day = date("2024-12-01") + Duration::days(day);
+        assert_ne!(pick(winter_day, &recipes, &[]).unwrap()._id, "recipe-00");
+    }
+    assert!(pick(date("2024-04-01"), &recipes, &[]).is_none());
+}
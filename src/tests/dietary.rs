@@ -0,0 +1,81 @@
+use crate::models::dietary::{Allergen, DietaryOverrides};
+use crate::models::recipe::{Ingredient, IngredientUnit};
+use crate::services::dietary::{infer, lookup};
+
+fn ingredient(name: &str) -> Ingredient {
+    Ingredient {
+        name: name.to_string(),
+        amount: 1.0,
+        unit: IngredientUnit::g,
+        name_translations: Default::default(),
+    }
+}
+
+#[test]
+fn lookup_prefers_exact_then_longest_match() {
+    assert_eq!(lookup("Garlic Cloves").unwrap().name, "garlic");
+    assert_eq!(lookup("whole milk").unwrap().name, "milk");
+    assert_eq!(lookup("dark chocolate").unwrap().name, "dark chocolate");
+    assert_eq!(lookup("grated parmesan").unwrap().name, "cheese");
+    assert_eq!(lookup("tomatoes").unwrap().name, "tomato");
+    assert!(lookup("dragon fruit").is_none());
+}
+
+#[test]
+fn infers_allergens_and_diets() {
+    let info = infer(
+        &[ingredient("spaghetti"), ingredient("eggs"), ingredient("parmesan")],
+        &DietaryOverrides::default(),
+    );
+    assert_eq!(info.allergens, vec![Allergen::gluten, Allergen::eggs, Allergen::milk]);
+    assert!(info.vegetarian);
+    assert!(!info.vegan);
+    assert!(!info.gluten_free);
+
+    let info = infer(
+        &[ingredient("rice"), ingredient("tofu"), ingredient("spring onion")],
+        &DietaryOverrides::default(),
+    );
+    assert_eq!(info.allergens, vec![Allergen::soy]);
+    assert!(info.vegan && info.vegetarian && info.gluten_free);
+
+    let info = infer(&[ingredient("chicken thighs")], &DietaryOverrides::default());
+    assert!(!info.vegetarian);
+}
+
+#[test]
+fn egg_noodles_and_oats_contain_gluten() {
+    let info = infer(&[ingredient("egg noodles")], &DietaryOverrides::default());
+    assert_eq!(info.allergens, vec![Allergen::gluten, Allergen::eggs]);
+    assert!(info.vegetarian && !info.vegan && !info.gluten_free);
+
+    let info = infer(&[ingredient("noodles")], &DietaryOverrides::default());
+    assert_eq!(info.allergens, vec![Allergen::gluten]);
+    assert!(info.vegan);
+
+    // Oats are usually contaminated with wheat, so they count as gluten
+    // like the other cereals of the EU list.
+    let info = infer(&[ingredient("rolled oats")], &DietaryOverrides::default());
+    assert_eq!(info.allergens, vec![Allergen::gluten]);
+    assert!(info.vegan && !info.gluten_free);
+}
+
+#[test]
+fn unknown_ingredients_block_diet_claims_unless_overridden() {
+    let ingredients = [ingredient("rice"), ingredient("dragon fruit")];
+    let info = infer(&ingredients, &DietaryOverrides::default());
+    assert_eq!(info.unknown_ingredients, vec!["dragon fruit".to_string()]);
+    assert!(!info.vegan && !info.gluten_free);
+
+    let overrides = DietaryOverrides {
+        add_allergens: vec![Allergen::sulphites],
+        remove_allergens: vec![],
+        vegan: Some(true),
+        vegetarian: Some(true),
+        gluten_free: None,
+    };
+    let info = infer(&ingredients, &overrides);
+    assert!(info.vegan && info.vegetarian);
+    assert!(!info.gluten_free);
+    assert_eq!(info.allergens, vec![Allergen::sulphites]);
+}
@@ -0,0 +1,37 @@
+use crate::models::dietary::{DietaryInfo, DietaryOverrides};
+use crate::models::recipe::{Nutrition, Recipe, RecipeStatus};
+
+/// A recipe with the given id and nutrition, and nothing else of interest.
+pub fn recipe(id: &str, servings: i32, calories: i32) -> Recipe {
+    Recipe {
+        _id: id.to_string(),
+        name: id.to_string(),
+        name_translations: Default::default(),
+        images: vec![],
+        preparation_time_in_minutes: 30,
+        servings,
+        nutrition: Nutrition {
+            calories,
+            fat: 0,
+            carbs: 0,
+            fiber: 0,
+            protein: 0,
+        },
+        nutrition_per_serving: Nutrition::default(),
+        num_of_likes: 0,
+        num_of_views: 0,
+        rating_average: 0.0,
+        rating_count: 0,
+        ingredients: vec![],
+        steps: vec![],
+        term_ids: vec![],
+        dietary: DietaryInfo::default(),
+        dietary_overrides: DietaryOverrides::default(),
+        collections: vec![],
+        forked_from: None,
+        localized: None,
+        status: RecipeStatus::published,
+        publish_at: None,
+        created_at: String::new(),
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::models::gif::{Gif, RecipeStep};
+use crate::models::image::Image;
+use crate::models::recipe::{Recipe, RecipeDocument};
+use crate::models::{DocumentConvertable, ObjectConvertable};
+use crate::services::forks::fork_recipe;
+
+use super::fixtures::recipe;
+
+const ORIGINAL_ID: &str = "634a1b2c3d4e5f6a7b8c9d0e";
+
+fn original() -> Recipe {
+    let mut original = recipe(ORIGINAL_ID, 2, 600);
+    original.num_of_likes = 12;
+    original.num_of_views = 340;
+    original.rating_average = 4.5;
+    original.rating_count = 8;
+    original.images = vec![
+        Image {
+            _id: "img1".to_string(),
+            path: "/tmp/soup.jpg".to_string(),
+            width: 10,
+            height: 10,
+            title: "soup".to_string(),
+            created_at: String::new(),
+        },
+        Image {
+            _id: "".to_string(),
+            path: "https://example.com/soup.jpg".to_string(),
+            width: 0,
+            height: 0,
+            title: "".to_string(),
+            created_at: String::new(),
+        },
+    ];
+    original.steps = vec![RecipeStep {
+        _id: "step1".to_string(),
+        description: "Stir.".to_string(),
+        description_translations: Default::default(),
+        duration_in_seconds: None,
+        gif: Gif {
+            path: "/tmp/stir.gif".to_string(),
+            width: 10,
+            height: 10,
+            title: "stir".to_string(),
+        },
+        created_at: String::new(),
+    }];
+    original
+}
+
+#[test]
+fn forks_with_new_ids_and_shared_media() {
+    let original = original();
+    let ids = HashMap::from([
+        ("img1".to_string(), "img2".to_string()),
+        ("step1".to_string(), "step2".to_string()),
+    ]);
+
+    let fork = fork_recipe(&original, &ids);
+    assert_eq!(fork._id, "");
+    assert_eq!(fork.forked_from.as_deref(), Some(ORIGINAL_ID));
+    assert_eq!(fork.name, original.name);
+    assert_eq!(fork.images[0]._id, "img2");
+    assert_eq!(fork.images[0].path, "/tmp/soup.jpg");
+    assert_eq!(fork.images[1]._id, "");
+    assert_eq!(fork.steps[0]._id, "step2");
+    assert_eq!(fork.steps[0].gif.path, "/tmp/stir.gif");
+    assert_eq!((fork.num_of_likes, fork.num_of_views, fork.rating_count), (0, 0, 0));
+    assert_eq!(fork.rating_average, 0.0);
+    assert_eq!(original.images[0]._id, "img1");
+}
+
+#[test]
+fn stores_lineage_as_object_id() {
+    let fork = fork_recipe(&original(), &HashMap::new());
+    let document: RecipeDocument = fork.to_document();
+    assert_eq!(document.forked_from.map(|id| id.to_hex()), Some(ORIGINAL_ID.to_string()));
+    assert_eq!(document.to_object().forked_from.as_deref(), Some(ORIGINAL_ID));
+
+    let mut unknown = recipe("plain", 1, 100);
+    unknown.forked_from = Some("not-an-id".to_string());
+    assert!(unknown.to_document().forked_from.is_none());
+}
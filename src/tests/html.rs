@@ -0,0 +1,30 @@
+use serde_json::json;
+
+use crate::services::html::{escape, render, render_recipe};
+
+use super::fixtures::recipe;
+
+#[test]
+fn fills_placeholders_once() {
+    let page = render(
+        "<h1>{{name}}</h1>{{body}}{{unknown}}",
+        &[("name", "{{body}}".to_string()), ("body", "<p>text</p>".to_string())],
+    );
+    assert_eq!(page, "<h1>{{body}}</h1><p>text</p>{{unknown}}");
+    assert_eq!(render("dangling {{name", &[("name", "x".to_string())]), "dangling {{name");
+}
+
+#[test]
+fn renders_escaped_recipe_page() {
+    let mut soup = recipe("soup", 2, 600);
+    soup.name = "Tom & Jerry's <soup>".to_string();
+    let json_ld = json!({ "name": "</script><script>alert(1)</script>", "image": ["https://example.com/soup.jpg"] });
+
+    let page = render_recipe(&soup, &json_ld);
+    assert!(page.contains("<h1>Tom &amp; Jerry&#39;s &lt;soup&gt;</h1>"));
+    assert!(page.contains("<img src=\"https://example.com/soup.jpg\""));
+    assert!(page.contains("<td>Calories</td><td>300 kcal</td>"));
+    assert!(!page.contains("</script><script>"));
+    assert!(!page.contains("{{"));
+    assert_eq!(escape("\"a\" <b>"), "&quot;a&quot; &lt;b&gt;");
+}
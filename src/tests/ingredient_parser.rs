@@ -0,0 +1,87 @@
+use crate::models::recipe::{Ingredient, IngredientUnit};
+use crate::services::ingredient_parser::{format_line, parse_line, parse_lines};
+
+/// Lines collected from published recipes, with the amount, range, unit,
+/// name and notes they should parse to.
+#[allow(clippy::type_complexity)]
+const CORPUS: &[(&str, Option<f64>, Option<f64>, Option<IngredientUnit>, &str, Option<&str>)] = &[
+    ("2 1/2 cups all-purpose flour, sifted", Some(2.5), None, Some(IngredientUnit::cup), "all-purpose flour", Some("sifted")),
+    ("1½ tsp baking soda", Some(1.5), None, Some(IngredientUnit::tsp), "baking soda", None),
+    ("½ cup sugar", Some(0.5), None, Some(IngredientUnit::cup), "sugar", None),
+    ("1 ¼ cups milk", Some(1.25), None, Some(IngredientUnit::cup), "milk", None),
+    ("¾ lb ground beef", Some(0.75), None, Some(IngredientUnit::lb), "ground beef", None),
+    ("2-3 cloves garlic, minced", Some(2.0), Some(3.0), Some(IngredientUnit::clove), "garlic", Some("minced")),
+    ("2 – 3 tablespoons olive oil", Some(2.0), Some(3.0), Some(IngredientUnit::tbsp), "olive oil", None),
+    ("1 to 2 tbsp. honey", Some(1.0), Some(2.0), Some(IngredientUnit::tbsp), "honey", None),
+    ("200g dark chocolate, chopped", Some(200.0), None, Some(IngredientUnit::g), "dark chocolate", Some("chopped")),
+    ("1.5kg potatoes", Some(1.5), None, Some(IngredientUnit::kg), "potatoes", None),
+    ("0,5 l whole milk", Some(0.5), None, Some(IngredientUnit::l), "whole milk", None),
+    ("3 dl cream", Some(3.0), None, Some(IngredientUnit::dcl), "cream", None),
+    ("250 ml vegetable stock", Some(250.0), None, Some(IngredientUnit::ml), "vegetable stock", None),
+    ("8 fl oz buttermilk", Some(8.0), None, Some(IngredientUnit::fl_oz), "buttermilk", None),
+    ("4 ounces cream cheese, softened", Some(4.0), None, Some(IngredientUnit::oz), "cream cheese", Some("softened")),
+    ("1 T soy sauce", Some(1.0), None, Some(IngredientUnit::tbsp), "soy sauce", None),
+    ("1 t vanilla extract", Some(1.0), None, Some(IngredientUnit::tsp), "vanilla extract", None),
+    ("2 cups of water", Some(2.0), None, Some(IngredientUnit::cup), "water", None),
+    ("3 large eggs", Some(3.0), None, None, "large eggs", None),
+    ("1 onion (about 150 g), diced", Some(1.0), None, None, "onion", Some("about 150 g; diced")),
+    ("a pinch of salt", None, None, None, "a pinch of salt", None),
+    ("1 pinch nutmeg", Some(1.0), None, Some(IngredientUnit::pinch), "nutmeg", None),
+    ("Salt and pepper, to taste", None, None, None, "Salt and pepper", Some("to taste")),
+    ("fresh parsley, for garnish", None, None, None, "fresh parsley", Some("for garnish")),
+    ("1 lemon, zested and juiced", Some(1.0), None, None, "lemon", Some("zested and juiced")),
+    ("- 1/3 cup maple syrup", Some(1.0 / 3.0), None, Some(IngredientUnit::cup), "maple syrup", None),
+    ("• 2 pcs star anise", Some(2.0), None, Some(IngredientUnit::piece), "star anise", None),
+    ("1 tin chopped tomatoes", Some(1.0), None, None, "tin chopped tomatoes", None),
+    ("cumin seeds, optional", None, None, None, "cumin seeds", Some("optional")),
+];
+
+#[test]
+fn parses_the_corpus() {
+    for (line, amount, amount_max, unit, name, notes) in CORPUS {
+        let parsed = parse_line(line);
+        assert_eq!(parsed.amount, *amount, "amount of {:?}", line);
+        assert_eq!(parsed.amount_max, *amount_max, "range of {:?}", line);
+        assert_eq!(parsed.unit, *unit, "unit of {:?}", line);
+        assert_eq!(parsed.name, *name, "name of {:?}", line);
+        assert_eq!(parsed.notes.as_deref(), *notes, "notes of {:?}", line);
+    }
+}
+
+#[test]
+fn builds_recipe_ingredients() {
+    let eggs = parse_line("3 eggs").ingredient.unwrap();
+    assert_eq!((eggs.amount, eggs.unit), (3.0, IngredientUnit::piece));
+    assert!(parse_line("salt to taste").ingredient.is_none());
+
+    let parsed = parse_lines("2 cups flour\n\n  \n1 tsp salt\n");
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed[1].name, "salt");
+}
+
+#[test]
+fn formats_lines_it_can_parse() {
+    let units = [
+        IngredientUnit::kg,
+        IngredientUnit::g,
+        IngredientUnit::dcl,
+        IngredientUnit::tbsp,
+        IngredientUnit::fl_oz,
+        IngredientUnit::lb,
+        IngredientUnit::pinch,
+        IngredientUnit::piece,
+    ];
+    for unit in units {
+        let ingredient = Ingredient { name: "brown sugar".to_string(), amount: 1.25, unit, name_translations: Default::default() };
+        let line = format_line(&ingredient);
+        let parsed = parse_line(&line).ingredient.unwrap();
+        assert_eq!(
+            (parsed.name.as_str(), parsed.amount, parsed.unit),
+            ("brown sugar", 1.25, unit),
+            "{}",
+            line
+        );
+    }
+    let eggs = Ingredient { name: "egg".to_string(), amount: 2.0, unit: IngredientUnit::piece, name_translations: Default::default() };
+    assert_eq!(format_line(&eggs), "2 egg");
+}
@@ -0,0 +1,207 @@
+use serde_json::json;
+
+use crate::models::gif::{Gif, RecipeStep};
+use crate::models::image::Image;
+use crate::models::recipe::{Ingredient, IngredientUnit, Recipe};
+use crate::services::jsonld::{
+    clean_text, find_recipes, format_duration, from_recipe, parse_documents, parse_duration,
+    to_recipe,
+};
+
+use super::fixtures::recipe;
+
+const BLOG_PAGE: &str = r#"<html><head>
+<script type="application/ld+json">{ "@context": "https://schema.org", "@type": "WebSite" }</script>
+<script type='application/ld+json'>
+{
+  "@context": "https://schema.org",
+  "@graph": [
+    { "@type": "WebPage", "name": "Weeknight chili" },
+    {
+      "@type": ["Recipe", "NewsArticle"],
+      "name": "Weeknight Chili &amp; Cornbread",
+      "image": [
+        "https://example.com/chili.jpg",
+        { "@type": "ImageObject", "url": "https://example.com/chili-wide.jpg", "width": 1200, "height": "675" }
+      ],
+      "recipeYield": ["4", "4 servings"],
+      "prepTime": "PT15M",
+      "cookTime": "PT45M",
+      "recipeIngredient": [
+        "1 lb ground beef",
+        "2 (15 oz) cans kidney beans, drained",
+        "Salt to taste"
+      ],
+      "recipeInstructions": [
+        {
+          "@type": "HowToSection",
+          "name": "Chili",
+          "itemListElement": [
+            { "@type": "HowToStep", "text": "Brown the <b>beef</b>." },
+            { "@type": "HowToStep", "text": "Add the beans and simmer." }
+          ]
+        },
+        "Serve with cornbread."
+      ],
+      "nutrition": {
+        "@type": "NutritionInformation",
+        "calories": "450 kcal",
+        "proteinContent": "32 g",
+        "fatContent": "18,5 g",
+        "sodiumContent": "800 mg"
+      },
+      "keywords": "chili, beef",
+      "recipeCuisine": "American"
+    }
+  ]
+}
+</script>
+</head><body></body></html>"#;
+
+#[test]
+fn parses_durations() {
+    assert_eq!(parse_duration("PT1H30M"), Some(90));
+    assert_eq!(parse_duration("PT45M"), Some(45));
+    assert_eq!(parse_duration("P1DT2H"), Some(1560));
+    assert_eq!(parse_duration("PT90S"), Some(2));
+    assert_eq!(parse_duration("PT0.5H"), Some(30));
+    assert_eq!(parse_duration("P"), None);
+    assert_eq!(parse_duration("45 minutes"), None);
+    assert_eq!(parse_duration("PT45"), None);
+}
+
+#[test]
+fn cleans_markup_from_text() {
+    assert_eq!(clean_text("Brown the <b>beef</b>."), "Brown the beef.");
+    assert_eq!(clean_text("Whisk<br/>then rest"), "Whisk then rest");
+    assert_eq!(clean_text("Mac &amp; cheese&#8217;s &#x27;best&#x27;"), "Mac & cheese’s 'best'");
+    assert_eq!(clean_text("Salt & pepper"), "Salt & pepper");
+}
+
+#[test]
+fn imports_recipe_from_html() {
+    let documents = parse_documents(BLOG_PAGE).unwrap();
+    let recipes: Vec<_> = documents.iter().flat_map(find_recipes).collect();
+    assert_eq!(recipes.len(), 1);
+
+    let imported = to_recipe(recipes[0]).unwrap();
+    let recipe = imported.recipe;
+    assert_eq!(recipe.name, "Weeknight Chili & Cornbread");
+    assert_eq!(recipe.servings, 4);
+    assert_eq!(recipe.preparation_time_in_minutes, 60);
+
+    assert_eq!(recipe.ingredients.len(), 2);
+    assert_eq!(recipe.ingredients[0].name, "ground beef");
+    assert_eq!(recipe.ingredients[0].unit, IngredientUnit::lb);
+    assert_eq!(recipe.ingredients[1].amount, 2.0);
+
+    let steps: Vec<&str> = recipe.steps.iter().map(|step| step.description.as_str()).collect();
+    assert_eq!(steps, vec!["Brown the beef.", "Add the beans and simmer.", "Serve with cornbread."]);
+
+    // Per serving values scaled to the whole recipe
+    assert_eq!(recipe.nutrition.calories, 1800);
+    assert_eq!(recipe.nutrition.protein, 128);
+    assert_eq!(recipe.nutrition.fat, 76);
+    assert_eq!(recipe.nutrition_per_serving.calories, 450);
+
+    assert_eq!(recipe.images.len(), 2);
+    assert_eq!(recipe.images[1].path, "https://example.com/chili-wide.jpg");
+    assert_eq!((recipe.images[1].width, recipe.images[1].height), (1200, 675));
+
+    assert_eq!(
+        imported.unmapped,
+        vec![
+            "keywords",
+            "recipeCuisine",
+            "recipeIngredient: Salt to taste",
+            "nutrition.sodiumContent",
+        ]
+    );
+}
+
+#[test]
+fn reports_documents_without_recipes() {
+    assert!(parse_documents("<html><body>No structured data</body></html>").is_err());
+    assert!(parse_documents("{ not json").is_err());
+
+    let documents = parse_documents(r#"{ "@type": "WebSite" }"#).unwrap();
+    assert!(find_recipes(&documents[0]).is_empty());
+
+    let documents = parse_documents(r#"{ "@type": "Recipe" }"#).unwrap();
+    assert!(to_recipe(find_recipes(&documents[0])[0]).is_err());
+}
+
+fn chili() -> Recipe {
+    let mut chili = recipe("chili", 4, 1800);
+    chili.name = "Chili".to_string();
+    chili.preparation_time_in_minutes = 90;
+    chili.ingredients = vec![
+        Ingredient { name: "ground beef".to_string(), amount: 1.0, unit: IngredientUnit::lb, name_translations: Default::default() },
+        Ingredient { name: "onion".to_string(), amount: 2.0, unit: IngredientUnit::piece, name_translations: Default::default() },
+    ];
+    chili.steps = vec![step("Brown the beef.", ""), step("Simmer.", "/tmp/simmer.gif")];
+    chili.images = vec![Image {
+        _id: "image".to_string(),
+        path: "/tmp/chili.jpg".to_string(),
+        width: 1200,
+        height: 800,
+        title: "chili.jpg".to_string(),
+        created_at: String::new(),
+    }];
+    chili
+}
+
+fn step(description: &str, gif: &str) -> RecipeStep {
+    RecipeStep {
+        _id: format!("step-{}", description.len()),
+        description: description.to_string(),
+        description_translations: Default::default(),
+        duration_in_seconds: None,
+        gif: Gif { path: gif.to_string(), width: 0, height: 0, title: String::new() },
+        created_at: String::new(),
+    }
+}
+
+#[test]
+fn formats_durations() {
+    assert_eq!(format_duration(45), "PT45M");
+    assert_eq!(format_duration(60), "PT1H");
+    assert_eq!(format_duration(90), "PT1H30M");
+    assert_eq!(parse_duration(&format_duration(135)), Some(135));
+}
+
+#[test]
+fn exports_schema_org_recipe() {
+    let exported = from_recipe(&chili(), "https://api.example.com");
+    assert_eq!(exported["@type"], "Recipe");
+    assert_eq!(exported["@id"], "https://api.example.com/recipe/chili");
+    assert_eq!(exported["totalTime"], "PT1H30M");
+    assert_eq!(exported["recipeYield"], "4 servings");
+    assert_eq!(exported["recipeIngredient"], json!(["1 lb ground beef", "2 onion"]));
+    assert_eq!(exported["image"], json!(["https://api.example.com/media/image/image"]));
+    assert_eq!(exported["recipeInstructions"][0]["@type"], "HowToStep");
+    assert_eq!(exported["recipeInstructions"][0]["position"], 1);
+    assert!(exported["recipeInstructions"][0].get("image").is_none());
+    assert_eq!(exported["recipeInstructions"][1]["image"], "https://api.example.com/media/gif/step-7");
+    // schema.org nutrition is per serving
+    assert_eq!(exported["nutrition"]["calories"], "450 kcal");
+    assert!(exported.get("aggregateRating").is_none());
+}
+
+#[test]
+fn exported_recipes_import_unchanged() {
+    let exported = from_recipe(&chili(), "");
+    let imported = to_recipe(exported.as_object().unwrap()).unwrap();
+    assert!(imported.unmapped.is_empty(), "{:?}", imported.unmapped);
+
+    let recipe = imported.recipe;
+    let original = chili();
+    assert_eq!(recipe.name, original.name);
+    assert_eq!(recipe.servings, original.servings);
+    assert_eq!(recipe.preparation_time_in_minutes, original.preparation_time_in_minutes);
+    assert_eq!(recipe.nutrition, original.nutrition);
+    assert_eq!(recipe.ingredients.len(), 2);
+    assert_eq!(recipe.ingredients[0].unit, IngredientUnit::lb);
+    assert_eq!(recipe.ingredients[1].unit, IngredientUnit::piece);
+    assert_eq!(recipe.steps.len(), 2);
+}
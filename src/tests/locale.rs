@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use crate::db::recipe::missing_translations_filter;
+use crate::models::gif::{Gif, RecipeStep};
+use crate::models::recipe::Recipe;
+use crate::services::ingredient_parser::parse_line;
+use crate::services::locale::{
+    fallback_chain, localize_recipe, missing_translations, normalize_tag, normalize_translations,
+    parse_accept_language, with_localized_text,
+};
+
+use super::fixtures::recipe;
+
+fn translations(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+    entries
+        .iter()
+        .map(|(tag, text)| (tag.to_string(), text.to_string()))
+        .collect()
+}
+
+fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+/// Tomato soup with a German name, one of two ingredients and its only
+/// step in German, and a Spanish name.
+fn soup() -> Recipe {
+    let mut soup = recipe("soup", 4, 800);
+    soup.name = "Tomato soup".to_string();
+    soup.name_translations = translations(&[("de", "Tomatensuppe"), ("es", "Sopa de tomate")]);
+    let mut tomatoes = parse_line("800 g tomatoes").ingredient.unwrap();
+    tomatoes.name_translations = translations(&[("de", "Tomaten")]);
+    soup.ingredients = vec![tomatoes, parse_line("1 tsp salt").ingredient.unwrap()];
+    soup.steps = vec![RecipeStep {
+        _id: "step".to_string(),
+        description: "Simmer.".to_string(),
+        description_translations: translations(&[("de", "Köcheln lassen.")]),
+        duration_in_seconds: None,
+        gif: Gif {
+            path: "".to_string(),
+            width: 0,
+            height: 0,
+            title: "".to_string(),
+        },
+        created_at: String::new(),
+    }];
+    soup
+}
+
+#[test]
+fn normalizes_language_tags() {
+    assert_eq!(normalize_tag("pt_BR"), Some("pt-br".to_string()));
+    assert_eq!(normalize_tag(" DE "), Some("de".to_string()));
+    assert_eq!(normalize_tag("zh-Hant-TW"), Some("zh-hant-tw".to_string()));
+    assert_eq!(normalize_tag("*"), None);
+    assert_eq!(normalize_tag("d"), None);
+    assert_eq!(normalize_tag("de-"), None);
+}
+
+#[test]
+fn orders_accept_language_by_quality() {
+    assert_eq!(
+        parse_accept_language("fr;q=0.5, de-AT, de;q=0.9, *;q=0.1, it;q=0, en;q=0.5"),
+        tags(&["de-at", "de", "fr", "en"])
+    );
+    assert!(parse_accept_language("").is_empty());
+}
+
+#[test]
+fn falls_back_to_the_language_then_the_default() {
+    assert_eq!(fallback_chain(&tags(&["de-at"]), "en"), tags(&["de-at", "de", "en"]));
+    assert_eq!(
+        fallback_chain(&tags(&["de-at", "fr", "de"]), "en"),
+        tags(&["de-at", "fr", "de", "en"])
+    );
+    assert_eq!(fallback_chain(&[], "en"), tags(&["en"]));
+}
+
+#[test]
+fn localizes_each_field_separately() {
+    let german = localize_recipe(soup(), &fallback_chain(&tags(&["de-ch"]), "en"), "en");
+    assert_eq!(german.name, "Tomatensuppe");
+    assert_eq!(german.localized.map(|localized| localized.locale), Some("de".to_string()));
+    assert_eq!(german.ingredients[0].name, "Tomaten");
+    assert_eq!(german.ingredients[1].name, "salt");
+    assert_eq!(german.steps[0].description, "Köcheln lassen.");
+
+    let spanish = localize_recipe(soup(), &fallback_chain(&tags(&["es", "de"]), "en"), "en");
+    assert_eq!(spanish.name, "Sopa de tomate");
+    assert_eq!(spanish.ingredients[0].name, "Tomaten");
+
+    let english = localize_recipe(soup(), &fallback_chain(&tags(&["en", "de"]), "en"), "en");
+    assert_eq!(english.name, "Tomato soup");
+    assert_eq!(english.localized.map(|localized| localized.locale), Some("en".to_string()));
+    assert_eq!(english.ingredients[0].name, "tomatoes");
+}
+
+#[test]
+fn keeps_the_default_fields_of_json_recipes() {
+    let german = with_localized_text(soup(), &fallback_chain(&tags(&["de"]), "en"), "en");
+    assert_eq!(german.name, "Tomato soup");
+    assert_eq!(german.ingredients[0].name, "tomatoes");
+    assert_eq!(german.steps[0].description, soup().steps[0].description);
+
+    let localized = german.localized.unwrap();
+    assert_eq!((localized.locale.as_str(), localized.name.as_str()), ("de", "Tomatensuppe"));
+    assert_eq!(localized.ingredient_names, vec!["Tomaten", "salt"]);
+    assert_eq!(localized.step_descriptions, vec!["Köcheln lassen."]);
+}
+
+#[test]
+fn normalizes_translations_before_storing() {
+    let mut soup = soup();
+    soup.name_translations = translations(&[("DE_at", " Paradeisersuppe "), ("fr", " ")]);
+    let stored = normalize_translations(soup.clone(), "en").unwrap();
+    assert_eq!(stored.name_translations, translations(&[("de-at", "Paradeisersuppe")]));
+
+    soup.name_translations = translations(&[("EN", "Tomato soup")]);
+    assert!(normalize_translations(soup.clone(), "en").is_err());
+
+    soup.name_translations = BTreeMap::new();
+    soup.steps[0].description_translations = translations(&[("not a tag", "Köcheln.")]);
+    assert!(normalize_translations(soup, "en").is_err());
+}
+
+#[test]
+fn lists_missing_translations() {
+    let german = missing_translations(&soup(), "de");
+    assert_eq!(german.recipe_id, "soup");
+    assert_eq!(german.missing, vec!["ingredients[1].name"]);
+
+    let spanish = missing_translations(&soup(), "es");
+    assert_eq!(
+        spanish.missing,
+        vec!["ingredients[0].name", "ingredients[1].name", "steps[0].description"]
+    );
+}
+
+#[test]
+fn looks_for_missing_translations_only_with_locales() {
+    assert_eq!(missing_translations_filter(&[]), None);
+
+    let filter = missing_translations_filter(&tags(&["de", "es"])).unwrap();
+    assert_eq!(filter.get_array("$or").unwrap().len(), 6);
+}
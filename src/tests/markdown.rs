@@ -0,0 +1,166 @@
+use crate::models::gif::{Gif, RecipeStep};
+use crate::models::image::Image;
+use crate::models::recipe::{IngredientUnit, Recipe};
+use crate::services::ingredient_parser::format_line;
+use crate::services::markdown::{from_recipe, to_recipe};
+
+use super::fixtures::recipe;
+
+const DRAFT: &str = "
+---
+title: \"Tomato soup: the good one\"
+servings: 4
+time: 1 h 15 min
+calories: 1200
+protein: 40
+image: /media/soup.jpg
+author: Dana
+---
+
+# Ignored when the front matter has a name
+
+A warming soup for cold evenings.
+
+## Ingredients
+
+- 800 g tomatoes
+* 2 cloves garlic
+- salt to taste
+
+## Steps
+
+1. Roast the tomatoes
+   until they blister.
+   ![roasting](/media/roast.gif)
+2) Blend with the garlic.
+Serve hot.
+
+## Notes
+
+Keeps for three days.
+";
+
+#[test]
+fn reads_editorial_markdown() {
+    let imported = to_recipe(DRAFT).unwrap();
+    let soup = imported.recipe;
+
+    assert_eq!(soup.name, "Tomato soup: the good one");
+    assert_eq!(soup.servings, 4);
+    assert_eq!(soup.preparation_time_in_minutes, 75);
+    assert_eq!(soup.nutrition.calories, 1200);
+    assert_eq!(soup.nutrition_per_serving.protein, 10);
+    assert_eq!(soup.images[0].path, "/media/soup.jpg");
+
+    assert_eq!(soup.ingredients.len(), 2);
+    assert_eq!(soup.ingredients[0].name, "tomatoes");
+    assert_eq!(soup.ingredients[0].unit, IngredientUnit::g);
+    assert_eq!(soup.ingredients[1].amount, 2.0);
+
+    assert_eq!(soup.steps.len(), 2);
+    assert_eq!(soup.steps[0].description, "Roast the tomatoes until they blister.");
+    assert_eq!(soup.steps[0].gif.path, "/media/roast.gif");
+    assert_eq!(soup.steps[0].gif.title, "roasting");
+    assert_eq!(soup.steps[1].description, "Blend with the garlic.");
+
+    assert_eq!(
+        imported.unmapped,
+        vec![
+            "author",
+            "description",
+            "Ingredients: salt to taste",
+            "Steps: Serve hot.",
+            "## Notes",
+        ]
+    );
+}
+
+#[test]
+fn takes_the_name_from_the_heading() {
+    let imported = to_recipe("# Toast\n\n## Steps\n\n1. Toast the bread.\n").unwrap();
+    assert_eq!(imported.recipe.name, "Toast");
+    assert_eq!(imported.recipe.servings, 1);
+    assert_eq!(imported.recipe.steps.len(), 1);
+    assert!(imported.unmapped.is_empty());
+
+    assert!(to_recipe("## Ingredients\n\n- 1 egg\n").is_err());
+    assert!(to_recipe("---\nname: Toast\n").is_err());
+}
+
+#[test]
+fn round_trips_through_markdown() {
+    let mut soup: Recipe = to_recipe(DRAFT).unwrap().recipe;
+    soup.name = "\"Grandma's soup\"".to_string();
+    soup.images.push(Image {
+        _id: "".to_string(),
+        path: "https://example.com/soup.jpg".to_string(),
+        width: 0,
+        height: 0,
+        title: "".to_string(),
+        created_at: "".to_string(),
+    });
+
+    soup.images.push(Image {
+        _id: "img1".to_string(),
+        path: "/tmp/upload-soup.jpg".to_string(),
+        width: 0,
+        height: 0,
+        title: "".to_string(),
+        created_at: "".to_string(),
+    });
+    soup.steps[0]._id = "step1".to_string();
+
+    let markdown = from_recipe(&soup, "https://api.example.com");
+    assert!(markdown.starts_with("---\nname: \"\"Grandma's soup\"\"\nservings: 4\ntime: 75\n"));
+    assert!(!markdown.contains("/tmp/"));
+    assert!(markdown.contains("image: https://api.example.com/media/image/img1\n"));
+    assert!(markdown.contains(
+        "\n1. Roast the tomatoes until they blister.\n   ![roasting](https://api.example.com/media/gif/step1)\n"
+    ));
+    // Local files without a record aren't served, so they are left out.
+    soup.images.remove(0);
+    soup.images[1].path = "https://api.example.com/media/image/img1".to_string();
+    soup.steps[0].gif.path = "https://api.example.com/media/gif/step1".to_string();
+
+    let imported = to_recipe(&markdown).unwrap();
+    let read = imported.recipe;
+    assert!(imported.unmapped.is_empty());
+    assert_eq!(read.name, soup.name);
+    assert_eq!(read.servings, soup.servings);
+    assert_eq!(read.preparation_time_in_minutes, soup.preparation_time_in_minutes);
+    assert_eq!(read.nutrition.calories, soup.nutrition.calories);
+    assert_eq!(read.nutrition.protein, soup.nutrition.protein);
+    let paths = |recipe: &Recipe| recipe.images.iter().map(|image| image.path.clone()).collect::<Vec<String>>();
+    assert_eq!(paths(&read), paths(&soup));
+    let lines = |recipe: &Recipe| recipe.ingredients.iter().map(format_line).collect::<Vec<String>>();
+    assert_eq!(lines(&read), lines(&soup));
+    let steps = |recipe: &Recipe| {
+        recipe
+            .steps
+            .iter()
+            .map(|step| (step.description.clone(), step.gif.path.clone(), step.gif.title.clone()))
+            .collect::<Vec<(String, String, String)>>()
+    };
+    assert_eq!(steps(&read), steps(&soup));
+}
+
+#[test]
+fn writes_recipes_without_ingredients() {
+    let mut plain = recipe("plain", 2, 300);
+    plain.steps = vec![RecipeStep {
+        _id: "".to_string(),
+        description: "Mix\nwell.".to_string(),
+        description_translations: Default::default(),
+        duration_in_seconds: None,
+        gif: Gif {
+            path: "".to_string(),
+            width: 0,
+            height: 0,
+            title: "".to_string(),
+        },
+        created_at: "".to_string(),
+    }];
+    let markdown = from_recipe(&plain, "");
+    assert!(!markdown.contains("## Ingredients"));
+    assert!(markdown.ends_with("## Steps\n\n1. Mix well.\n"));
+}
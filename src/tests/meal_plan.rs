@@ -0,0 +1,157 @@
+use chrono::NaiveDate;
+
+use crate::models::meal_plan::{MealPlanEntry, MealSlot};
+use crate::models::recipe::Recipe;
+use crate::services::meal_plan::{build_range, month_of, parse_date, shift_date, week_of};
+use crate::services::meal_planner::{generate, Candidate, PlanConstraints};
+
+use super::fixtures::recipe;
+
+fn date(value: &str) -> NaiveDate {
+    parse_date(value).unwrap()
+}
+
+fn entry(date: &str, slot: MealSlot, recipe_id: &str, servings: i32) -> MealPlanEntry {
+    MealPlanEntry {
+        _id: String::new(),
+        user_id: "user".to_string(),
+        date: date.to_string(),
+        slot,
+        recipe_id: recipe_id.to_string(),
+        servings,
+        note: None,
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+#[test]
+fn computes_weeks_and_months() {
+    assert!(parse_date("2024-02-30").is_err());
+    assert_eq!(week_of(date("2024-05-15")), (date("2024-05-13"), date("2024-05-19")));
+    assert_eq!(week_of(date("2024-05-13")), (date("2024-05-13"), date("2024-05-19")));
+    assert_eq!(week_of(date("2024-05-19")), (date("2024-05-13"), date("2024-05-19")));
+    assert_eq!(month_of("2024-02"), Ok((date("2024-02-01"), date("2024-02-29"))));
+    assert_eq!(month_of("2023-12"), Ok((date("2023-12-01"), date("2023-12-31"))));
+    assert!(month_of("2024-13").is_err());
+    assert_eq!(shift_date("2024-12-30", 7), Ok("2025-01-06".to_string()));
+}
+
+#[test]
+fn totals_nutrition_per_day() {
+    let recipes = vec![recipe("soup", 4, 800), recipe("toast", 1, 300)];
+    let entries = vec![
+        entry("2024-05-14", MealSlot::dinner, "soup", 2),
+        entry("2024-05-14", MealSlot::breakfast, "toast", 1),
+        entry("2024-05-15", MealSlot::lunch, "deleted", 1),
+    ];
+    let range = build_range(date("2024-05-13"), date("2024-05-19"), entries, &recipes);
+
+    assert_eq!(range.days.len(), 7);
+    assert_eq!(range.days[0].entries.len(), 0);
+    let tuesday = &range.days[1];
+    assert_eq!(tuesday.date, "2024-05-14");
+    assert_eq!(tuesday.entries[0].slot, MealSlot::breakfast);
+    assert_eq!(tuesday.nutrition.calories, 700);
+    assert_eq!(range.days[2].entries.len(), 1);
+    assert_eq!(range.days[2].nutrition.calories, 0);
+}
+
+fn candidates(count: usize) -> Vec<Candidate> {
+    (0..count)
+        .map(|index| Candidate {
+            recipe: recipe(&format!("recipe-{:02}", index), 1, 400 + 20 * index as i32),
+            slots: vec![],
+        })
+        .collect()
+}
+
+fn constraints() -> PlanConstraints {
+    PlanConstraints {
+        no_repeat_days: 7,
+        ..PlanConstraints::default()
+    }
+}
+
+#[test]
+fn generates_reproducible_plans() {
+    let slots = [MealSlot::breakfast, MealSlot::lunch, MealSlot::dinner];
+    let start = date("2024-05-13");
+    let recipes = candidates(30);
+    let plan = generate(&recipes, &constraints(), start, 7, &slots, &[], 42);
+    assert_eq!(plan.len(), 21);
+    assert_eq!(plan, generate(&recipes, &constraints(), start, 7, &slots, &[], 42));
+
+    let mut reversed = recipes.clone();
+    reversed.reverse();
+    assert_eq!(plan, generate(&reversed, &constraints(), start, 7, &slots, &[], 42));
+    assert_ne!(plan, generate(&recipes, &constraints(), start, 7, &slots, &[], 43));
+}
+
+#[test]
+fn keeps_close_to_the_calorie_target() {
+    let slots = [MealSlot::breakfast, MealSlot::lunch, MealSlot::dinner];
+    let recipes = candidates(30);
+    let constraints = PlanConstraints {
+        calories: Some(2000),
+        ..constraints()
+    };
+    let plan = generate(&recipes, &constraints, date("2024-05-13"), 7, &slots, &[], 7);
+    let entries = plan
+        .iter()
+        .map(|meal| entry(&meal.date, meal.slot, &meal.recipe_id, 1))
+        .collect();
+    let recipes: Vec<Recipe> = recipes.into_iter().map(|candidate| candidate.recipe).collect();
+    let range = build_range(date("2024-05-13"), date("2024-05-19"), entries, &recipes);
+    for day in range.days {
+        assert!((day.nutrition.calories - 2000).abs() <= 100, "{:?}", day.nutrition);
+    }
+}
+
+#[test]
+fn does_not_repeat_recipes_within_the_window() {
+    let slots = [MealSlot::dinner];
+    let recipes = candidates(7);
+    let history = vec![(date("2024-05-12"), "recipe-03".to_string())];
+    let plan = generate(&recipes, &constraints(), date("2024-05-13"), 7, &slots, &history, 1);
+    let mut ids: Vec<&str> = plan.iter().map(|meal| meal.recipe_id.as_str()).collect();
+    // recipe-03 was eaten yesterday, so it can only come back last.
+    assert_eq!(ids[6], "recipe-03");
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 7);
+}
+
+#[test]
+fn respects_restrictions_and_slots() {
+    let mut recipes = candidates(10);
+    recipes[0].recipe.dietary.vegan = true;
+    recipes[0].recipe.dietary.vegetarian = true;
+    recipes[1].recipe.dietary.vegan = true;
+    recipes[1].recipe.dietary.vegetarian = true;
+    recipes[1].recipe.preparation_time_in_minutes = 90;
+    recipes[2].recipe.dietary.vegan = true;
+    recipes[2].recipe.dietary.vegetarian = true;
+    recipes[2].slots = vec![MealSlot::breakfast];
+
+    let constraints = PlanConstraints {
+        vegan: true,
+        max_preparation_minutes: Some(45),
+        ..constraints()
+    };
+    let slots = [MealSlot::breakfast, MealSlot::dinner];
+    let plan = generate(&recipes, &constraints, date("2024-05-13"), 3, &slots, &[], 5);
+    for meal in plan.iter() {
+        match meal.slot {
+            MealSlot::breakfast => assert!(["recipe-00", "recipe-02"].contains(&meal.recipe_id.as_str())),
+            _ => assert_eq!(meal.recipe_id, "recipe-00"),
+        }
+    }
+    assert_eq!(plan.len(), 6);
+
+    let nothing = PlanConstraints {
+        max_preparation_minutes: Some(5),
+        ..constraints
+    };
+    assert!(generate(&recipes, &nothing, date("2024-05-13"), 3, &slots, &[], 5).is_empty());
+}
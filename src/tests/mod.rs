@@ -1,3 +1,30 @@
+mod collections;
+mod comments;
+mod catalog;
+mod cooking;
+mod daily;
+mod dietary;
+mod fixtures;
+mod forks;
+mod html;
+mod ingredient_parser;
+mod jsonld;
+mod locale;
+mod markdown;
+mod meal_plan;
+mod nutrition;
+mod pantry;
+mod print;
+mod publishing;
+mod reviews;
+mod scaling;
+mod search;
+mod shopping;
+mod similarity;
+mod trash;
+mod units;
+mod uploads;
+
 use super::rocket;
 use crate::models::response::MessageResponse;
 use rocket::http::Status;
@@ -0,0 +1,52 @@
+use crate::models::recipe::{Ingredient, IngredientUnit};
+use crate::services::nutrition::{calculate, lookup, parse_dataset};
+
+fn ingredient(name: &str, amount: f64, unit: IngredientUnit) -> Ingredient {
+    Ingredient {
+        name: name.to_string(),
+        amount,
+        unit,
+        name_translations: Default::default(),
+    }
+}
+
+#[test]
+fn bundled_dataset_loads() {
+    assert_eq!(lookup("Plain Flour").unwrap().name, "all purpose flour");
+    assert_eq!(lookup("extra virgin olive oil").unwrap().name, "olive oil");
+    assert!(lookup("unobtainium").is_none());
+}
+
+#[test]
+fn parses_columns_in_any_order() {
+    let foods = parse_dataset(
+        "# comment\nprotein,name,calories,fat,carbs,fiber,aliases\n3.5,Milk,61,3.3,4.8,0,whole milk;full fat milk\n",
+    )
+    .unwrap();
+    assert_eq!(foods.len(), 1);
+    assert_eq!(foods[0].aliases, vec!["whole milk", "full fat milk"]);
+    assert_eq!((foods[0].density, foods[0].piece_grams), (None, None));
+    assert!(parse_dataset("name,aliases\nmilk,\n").is_err());
+}
+
+#[test]
+fn calculates_totals_per_serving_and_unmatched() {
+    let report = calculate(
+        &[
+            ingredient("flour", 200.0, IngredientUnit::g),
+            ingredient("butter", 100.0, IngredientUnit::g),
+            ingredient("moon dust", 1.0, IngredientUnit::tsp),
+            ingredient("saffron", 3.0, IngredientUnit::piece),
+        ],
+        4,
+    );
+    // 200 g flour = 728 kcal, 100 g butter = 717 kcal
+    assert_eq!(report.total.calories, 1445);
+    assert_eq!(report.per_serving.calories, 361);
+    assert_eq!(report.total.fat, 83);
+    assert_eq!(report.ingredients.len(), 2);
+    assert_eq!(
+        report.unmatched_ingredients,
+        vec!["moon dust".to_string(), "saffron".to_string()]
+    );
+}
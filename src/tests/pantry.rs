@@ -0,0 +1,71 @@
+use crate::models::recipe::{Ingredient, IngredientUnit, Recipe};
+use crate::services::pantry::{aliases, canonical, covers, parse_aliases, rank};
+
+use super::fixtures::recipe;
+
+fn with(name: &str, ingredients: &[&str]) -> Recipe {
+    let mut recipe = recipe(name, 1, 500);
+    recipe.ingredients = ingredients
+        .iter()
+        .map(|name| Ingredient {
+            name: name.to_string(),
+            amount: 1.0,
+            unit: IngredientUnit::piece,
+            name_translations: Default::default(),
+        })
+        .collect();
+    recipe
+}
+
+#[test]
+fn resolves_synonyms() {
+    let groups = aliases();
+    assert_eq!(canonical("Scallions", groups), "green onion");
+    assert_eq!(canonical("chopped spring onions", groups), "chopped green onion");
+    assert_eq!(canonical("Aubergine", groups), "eggplant");
+    assert_eq!(canonical("garbanzo beans", groups), "chickpea");
+    assert_eq!(canonical("Large Eggs", groups), "egg");
+    assert_eq!(canonical("dragon fruit", groups), "dragon fruit");
+    assert_eq!(canonical("chicken broth", groups), "chicken stock");
+    assert_eq!(canonical("Flour", groups), "all purpose flour");
+    assert_eq!(canonical("almond flour", groups), "almond flour");
+    assert_eq!(canonical("red pepper flakes", groups), "red pepper flake");
+    assert_eq!(canonical("roasted red peppers", groups), "roasted bell pepper");
+
+    let custom = parse_aliases("name,aliases\nspud,potato;tater\n").unwrap();
+    assert_eq!(canonical("Taters", &custom), "spud");
+}
+
+#[test]
+fn matches_head_ingredients() {
+    assert!(covers("basil", "fresh basil"));
+    assert!(covers("butter", "salted butter"));
+    assert!(!covers("salt", "salted butter"));
+    assert!(!covers("tomato", "tomato paste"));
+    assert!(!covers("", "tomato"));
+}
+
+#[test]
+fn ranks_recipes_by_coverage() {
+    let recipes = vec![
+        with("omelette", &["eggs", "butter", "salt", "chives"]),
+        with("scrambled eggs", &["eggs", "butter", "salt"]),
+        with("stir fry", &["green onion", "garlic", "soy sauce", "rice", "eggplant"]),
+        with("fruit salad", &["apple", "banana"]),
+    ];
+    let pantry = vec![
+        "egg".to_string(),
+        "Unsalted butter".to_string(),
+        "sea salt".to_string(),
+        "scallions".to_string(),
+        "aubergine".to_string(),
+    ];
+    let matches = rank(&recipes, &pantry, 0.0);
+    let names: Vec<&str> = matches.iter().map(|found| found.recipe.name.as_str()).collect();
+    assert_eq!(names, vec!["scrambled eggs", "omelette", "stir fry"]);
+    assert_eq!(matches[0].coverage, 1.0);
+    assert_eq!(matches[1].missing_ingredients, vec!["chives"]);
+    assert_eq!(matches[2].matched_ingredients, vec!["green onion", "eggplant"]);
+
+    assert_eq!(rank(&recipes, &pantry, 0.5).len(), 2);
+}
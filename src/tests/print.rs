@@ -0,0 +1,172 @@
+use std::io::Cursor;
+
+use image::{ImageOutputFormat, RgbImage};
+use rocket::request::FromParam;
+
+use crate::models::gif::{Gif, RecipeStep};
+use crate::models::meal_plan::{MealPlanEntry, MealSlot};
+use crate::models::print::{PageSize, PdfName};
+use crate::models::recipe::{Ingredient, IngredientUnit, Recipe};
+use crate::services::meal_plan::{build_range, parse_date};
+use crate::services::pdf::{text_width, wrap, Font, Jpeg};
+use crate::services::print::{meal_plan_pdf, recipe_pdf};
+
+use super::fixtures::recipe;
+
+fn contains(pdf: &[u8], text: &str) -> bool {
+    pdf.windows(text.len()).any(|window| window == text.as_bytes())
+}
+
+fn page_count(pdf: &[u8]) -> usize {
+    pdf.windows(b"/Type /Page ".len())
+        .filter(|window| *window == b"/Type /Page ")
+        .count()
+}
+
+fn step(description: &str) -> RecipeStep {
+    RecipeStep {
+        _id: String::new(),
+        description: description.to_string(),
+        description_translations: Default::default(),
+        duration_in_seconds: None,
+        gif: Gif {
+            path: String::new(),
+            width: 0,
+            height: 0,
+            title: String::new(),
+        },
+        created_at: String::new(),
+    }
+}
+
+fn cake() -> Recipe {
+    let mut cake = recipe("Crème brûlée", 4, 1600);
+    cake.ingredients = vec![
+        Ingredient {
+            name: "cream".to_string(),
+            amount: 0.5,
+            unit: IngredientUnit::l,
+            name_translations: Default::default(),
+        },
+        Ingredient {
+            name: "sugar (fine)".to_string(),
+            amount: 100.0,
+            unit: IngredientUnit::g,
+            name_translations: Default::default(),
+        },
+    ];
+    cake.steps = vec![step("Warm the cream."), step("Whisk in the sugar and bake.")];
+    cake
+}
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = Cursor::new(vec![]);
+    RgbImage::new(width, height)
+        .write_to(&mut bytes, ImageOutputFormat::Png)
+        .unwrap();
+    bytes.into_inner()
+}
+
+#[test]
+fn prints_a_complete_document() {
+    let pdf = recipe_pdf(&cake(), PageSize::a4, None, vec![]);
+
+    assert!(pdf.starts_with(b"%PDF-1.4\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+    assert_eq!(page_count(&pdf), 1);
+    assert!(contains(&pdf, "/MediaBox [0 0 595.28 841.89]"));
+    assert!(contains(&pdf, "(Cr\\350me br\\373l\\351e) Tj"));
+    assert!(contains(&pdf, "(sugar \\(fine\\)) Tj"));
+    assert!(contains(&pdf, "(0.5 l) Tj"));
+    assert!(contains(&pdf, "(2.) Tj"));
+    assert!(contains(&pdf, "(400 kcal) Tj"));
+}
+
+#[test]
+fn long_recipes_run_over_pages() {
+    let mut long = cake();
+    long.steps = (0..60)
+        .map(|index| step(&format!("Step {} stirs the pot slowly for a long while until it thickens.", index)))
+        .collect();
+
+    let a4 = recipe_pdf(&long, PageSize::a4, None, vec![]);
+    let card = recipe_pdf(&long, PageSize::index_card, None, vec![]);
+    assert!(page_count(&a4) > 1);
+    assert!(page_count(&card) > page_count(&a4));
+    assert!(contains(&card, "/MediaBox [0 0 432.00 288.00]"));
+    assert!(contains(&a4, "(60.) Tj"));
+}
+
+#[test]
+fn long_steps_break_across_pages() {
+    let mut long = cake();
+    long.steps = vec![step(&"Stir the pot slowly until it thickens. ".repeat(200))];
+
+    let card = recipe_pdf(&long, PageSize::index_card, None, vec![]);
+    assert!(page_count(&card) > 2);
+    assert!(contains(&card, "(Stir the pot"));
+}
+
+#[test]
+fn embeds_hero_and_step_frames() {
+    let hero = Jpeg::from_image_bytes(&png(2400, 1200)).unwrap();
+    assert_eq!((hero.width, hero.height), (1200, 600));
+    let frame = Jpeg::from_image_bytes(&png(40, 30)).unwrap();
+    assert_eq!((frame.width, frame.height), (40, 30));
+    assert!(Jpeg::from_image_bytes(b"not an image").is_none());
+
+    let pdf = recipe_pdf(&cake(), PageSize::letter, Some(hero), vec![None, Some(frame)]);
+    assert!(contains(&pdf, "/Im0 5 0 R /Im1 6 0 R"));
+    assert!(contains(&pdf, "/Im1 Do"));
+    assert!(contains(&pdf, "/Width 1200 /Height 600"));
+}
+
+#[test]
+fn wraps_within_width() {
+    let lines = wrap("a few short words and one Supercalifragilistic", Font::Regular, 10.0, 60.0);
+    assert!(lines.len() > 2);
+    assert!(lines.iter().all(|line| text_width(line, Font::Regular, 10.0) <= 60.0));
+    assert_eq!(lines.join("").replace(' ', ""), "afewshortwordsandoneSupercalifragilistic");
+    assert!(text_width("Bold", Font::Bold, 10.0) > text_width("Bold", Font::Regular, 10.0));
+    assert!(wrap("   ", Font::Regular, 10.0, 60.0).is_empty());
+}
+
+#[test]
+fn prints_meal_plan_days() {
+    let entry = |date: &str, slot: MealSlot, recipe_id: &str| MealPlanEntry {
+        _id: String::new(),
+        user_id: "user".to_string(),
+        date: date.to_string(),
+        slot,
+        recipe_id: recipe_id.to_string(),
+        servings: 2,
+        note: Some("double the salt".to_string()),
+        created_at: String::new(),
+        updated_at: String::new(),
+    };
+    let recipes = vec![recipe("soup", 2, 600)];
+    let range = build_range(
+        parse_date("2022-10-03").unwrap(),
+        parse_date("2022-10-09").unwrap(),
+        vec![
+            entry("2022-10-03", MealSlot::lunch, "soup"),
+            entry("2022-10-04", MealSlot::dinner, "gone"),
+        ],
+        &recipes,
+    );
+
+    let pdf = meal_plan_pdf(&range, &recipes, PageSize::a4);
+    assert!(contains(&pdf, "(Monday, October 3) Tj"));
+    assert!(contains(&pdf, "(Sunday, October 9) Tj"));
+    assert!(contains(&pdf, "(soup \\(2 servings\\) \\226 double the salt) Tj"));
+    assert!(contains(&pdf, "(Deleted recipe \\(2 servings\\) \\226 double the salt) Tj"));
+    assert!(contains(&pdf, "(Nothing planned) Tj"));
+}
+
+#[test]
+fn parses_pdf_names() {
+    assert_eq!(PdfName::from_param("634a.pdf").map(|name| name.0), Ok("634a".to_string()));
+    assert!(PdfName::from_param("634a").is_err());
+    assert!(PdfName::from_param(".pdf").is_err());
+    assert_eq!(PageSize::index_card.dimensions(), (432.0, 288.0));
+}
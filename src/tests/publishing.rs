@@ -0,0 +1,62 @@
+use mongodb::bson::DateTime;
+
+use crate::models::recipe::{Recipe, RecipeDocument, RecipeStatus};
+use crate::models::{DocumentConvertable, ObjectConvertable};
+use crate::services::publishing::{check_transition, needs_admin, parse_publish_at};
+
+use super::fixtures::recipe;
+
+#[test]
+fn follows_the_workflow() {
+    use RecipeStatus::*;
+    assert!(check_transition(draft, in_review).is_ok());
+    assert!(check_transition(in_review, published).is_ok());
+    assert!(check_transition(in_review, draft).is_ok());
+    assert!(check_transition(published, archived).is_ok());
+    assert!(check_transition(archived, draft).is_ok());
+
+    assert!(check_transition(draft, published).is_err());
+    assert!(check_transition(archived, published).is_err());
+    assert_eq!(
+        check_transition(published, published).unwrap_err(),
+        "The recipe is already published."
+    );
+}
+
+#[test]
+fn publishing_and_archiving_need_an_admin() {
+    use RecipeStatus::*;
+    assert!(!needs_admin(draft, in_review));
+    assert!(!needs_admin(in_review, draft));
+    assert!(needs_admin(in_review, published));
+    assert!(needs_admin(published, archived));
+    assert!(needs_admin(archived, draft));
+}
+
+#[test]
+fn schedules_only_future_times() {
+    let now = DateTime::parse_rfc3339_str("2022-10-20T08:00:00Z").unwrap();
+    let later = parse_publish_at("2022-10-21T08:00:00+02:00", now).unwrap();
+    assert_eq!(later.unwrap().try_to_rfc3339_string().unwrap(), "2022-10-21T06:00:00Z");
+    assert_eq!(parse_publish_at("2022-10-20T08:00:00Z", now), Ok(None));
+    assert_eq!(parse_publish_at("2022-10-01T08:00:00Z", now), Ok(None));
+    assert!(parse_publish_at("tomorrow", now).is_err());
+}
+
+#[test]
+fn stored_recipes_without_status_are_published() {
+    let mut legacy = serde_json::to_value(recipe("legacy", 1, 100)).unwrap();
+    let fields = legacy.as_object_mut().unwrap();
+    fields.remove("status");
+    fields.remove("publish_at");
+    let legacy: Recipe = serde_json::from_value(legacy).unwrap();
+    assert_eq!(legacy.status, RecipeStatus::published);
+    assert_eq!(legacy.publish_at, None);
+
+    let mut scheduled = recipe("scheduled", 1, 100);
+    scheduled.status = RecipeStatus::in_review;
+    scheduled.publish_at = Some("2022-10-21T06:00:00Z".to_string());
+    let document: RecipeDocument = scheduled.to_document();
+    assert_eq!(document.status, RecipeStatus::in_review);
+    assert_eq!(document.to_object().publish_at.as_deref(), Some("2022-10-21T06:00:00Z"));
+}
@@ -0,0 +1,70 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime;
+
+use crate::models::recipe::RecipeDocument;
+use crate::models::review::{ReviewDocument, ReviewFlag, ReviewInput, MAX_RATING, MIN_RATING};
+use crate::models::{DocumentConvertable, ObjectConvertable};
+use crate::services::jsonld;
+
+use super::fixtures::recipe;
+
+fn input(rating: i32) -> ReviewInput {
+    ReviewInput {
+        rating,
+        text: "Lovely".to_string(),
+    }
+}
+
+#[test]
+fn ratings_are_one_to_five_stars() {
+    assert!(input(MIN_RATING).validate().is_ok());
+    assert!(input(MAX_RATING).validate().is_ok());
+    assert_eq!(input(0).validate().unwrap_err(), "Rating must be between 1 and 5.");
+    assert!(input(6).validate().is_err());
+    assert!(input(-3).validate().is_err());
+}
+
+#[test]
+fn converts_stored_reviews() {
+    let id = ObjectId::new();
+    let recipe_id = ObjectId::new();
+    let created_at = DateTime::from_millis(1_666_000_000_000);
+    let document = ReviewDocument {
+        _id: Some(id),
+        recipe_id,
+        user_id: "u1".to_string(),
+        rating: 4,
+        text: "Lovely".to_string(),
+        flags: vec![ReviewFlag {
+            user_id: "u2".to_string(),
+            reason: "spam".to_string(),
+        }],
+        hidden: true,
+        created_at,
+        updated_at: created_at,
+    };
+
+    let review = document.to_object();
+    assert_eq!(review._id, id.to_string());
+    assert_eq!(review.recipe_id, recipe_id.to_string());
+    assert_eq!((review.rating, review.hidden), (4, true));
+    assert_eq!(review.flags[0].user_id, "u2");
+    assert_eq!(review.created_at, created_at.to_string());
+}
+
+#[test]
+fn stored_ratings_are_shown() {
+    let mut soup = recipe("soup", 2, 400);
+    soup.rating_average = 4.25;
+    soup.rating_count = 8;
+    let document: RecipeDocument = soup.to_document();
+    let shown = document.to_object();
+    assert_eq!((shown.rating_average, shown.rating_count), (4.25, 8));
+
+    let json_ld = jsonld::from_recipe(&shown, "https://example.com");
+    assert_eq!(json_ld["aggregateRating"]["ratingValue"], 4.3);
+    assert_eq!(json_ld["aggregateRating"]["ratingCount"], 8);
+
+    let unrated = jsonld::from_recipe(&recipe("stew", 2, 400), "https://example.com");
+    assert!(unrated.get("aggregateRating").is_none());
+}
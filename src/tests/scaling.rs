@@ -0,0 +1,43 @@
+use crate::models::recipe::{Ingredient, IngredientUnit};
+use crate::services::scaling::{scale_ingredient, scaled_ingredients};
+
+use super::fixtures::recipe;
+
+fn ingredient(amount: f64, unit: IngredientUnit) -> Ingredient {
+    Ingredient {
+        name: "flour".to_string(),
+        amount,
+        unit,
+        name_translations: Default::default(),
+    }
+}
+
+#[test]
+fn scales_ingredients() {
+    let scaled = scale_ingredient(&ingredient(250.0, IngredientUnit::g), 3.0);
+    assert_eq!((scaled.amount, scaled.unit), (750.0, IngredientUnit::g));
+    let scaled = scale_ingredient(&ingredient(500.0, IngredientUnit::g), 2.0);
+    assert_eq!((scaled.amount, scaled.unit), (1.0, IngredientUnit::kg));
+    let scaled = scale_ingredient(&ingredient(1.0, IngredientUnit::kg), 1.0 / 3.0);
+    assert_eq!((scaled.amount, scaled.unit), (335.0, IngredientUnit::g));
+}
+
+#[test]
+fn keeps_the_measurement_system() {
+    let scaled = scale_ingredient(&ingredient(0.75, IngredientUnit::cup), 3.0);
+    assert_eq!((scaled.amount, scaled.unit), (2.25, IngredientUnit::cup));
+    let scaled = scale_ingredient(&ingredient(12.0, IngredientUnit::oz), 2.0);
+    assert_eq!((scaled.amount, scaled.unit), (1.5, IngredientUnit::lb));
+    let scaled = scale_ingredient(&ingredient(2.0, IngredientUnit::tsp), 3.0);
+    assert_eq!((scaled.amount, scaled.unit), (2.0, IngredientUnit::tbsp));
+    let scaled = scale_ingredient(&ingredient(3.0, IngredientUnit::clove), 0.5);
+    assert_eq!((scaled.amount, scaled.unit), (1.5, IngredientUnit::clove));
+}
+
+#[test]
+fn scales_ingredients_unrounded() {
+    let mut bread = recipe("bread", 4, 2000);
+    bread.ingredients = vec![ingredient(333.0, IngredientUnit::g)];
+    let scaled = scaled_ingredients(&bread, 3);
+    assert_eq!((scaled[0].amount, scaled[0].unit), (249.75, IngredientUnit::g));
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, Document, Regex};
+
+use crate::models::taxonomy::{slugify, TaxonomyTerm, TermKind};
+use crate::services::search::{facets, name_filter, term_filter, FACET_KINDS};
+
+fn term(kind: TermKind, name: &str) -> TaxonomyTerm {
+    TaxonomyTerm {
+        _id: ObjectId::new().to_string(),
+        kind,
+        name: name.to_string(),
+        slug: slugify(name),
+        created_at: String::new(),
+    }
+}
+
+fn locales(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn requires_every_term() {
+    assert_eq!(term_filter(vec![]), Document::new());
+
+    let ids = vec![ObjectId::new(), ObjectId::new()];
+    assert_eq!(term_filter(ids.clone()), doc! { "term_ids": { "$all": ids } });
+}
+
+#[test]
+fn matches_names_in_every_locale_asked_for() {
+    assert_eq!(name_filter("  ", &locales(&["en"]), "en"), None);
+
+    let filter = name_filter(" Tomato soup ", &locales(&["de", "en"]), "en").unwrap();
+    let regex = Regex { pattern: "Tomato soup".to_string(), options: "i".to_string() };
+    assert_eq!(
+        filter,
+        doc! { "$or": [
+            { "name_translations.de": regex.clone() },
+            { "name": regex },
+        ] }
+    );
+}
+
+#[test]
+fn escapes_the_query() {
+    let filter = name_filter("mac & cheese (v2)", &locales(&["en"]), "en").unwrap();
+    let names = filter.get_array("$or").unwrap();
+    match names[0].as_document().unwrap().get("name") {
+        Some(Bson::RegularExpression(regex)) => {
+            assert_eq!(regex.pattern, "mac \\& cheese \\(v2\\)");
+        }
+        other => panic!("expected a regex, got {:?}", other),
+    }
+}
+
+#[test]
+fn groups_facets_by_kind_most_frequent_first() {
+    let vegan = term(TermKind::diet, "Vegan");
+    let quick = term(TermKind::tag, "Quick");
+    let cheap = term(TermKind::tag, "Cheap");
+    let easy = term(TermKind::tag, "Easy");
+    let thai = term(TermKind::cuisine, "Thai");
+    let counts = HashMap::from([
+        (vegan._id.clone(), 2),
+        (quick._id.clone(), 3),
+        (cheap._id.clone(), 5),
+        (easy._id.clone(), 3),
+    ]);
+
+    let facets = facets(&[vegan, quick, cheap, easy, thai], &counts);
+    assert_eq!(facets.iter().map(|facet| facet.kind).collect::<Vec<TermKind>>(), FACET_KINDS.to_vec());
+
+    let tags: Vec<(&str, i64)> = facets[0].counts.iter().map(|count| (count.slug.as_str(), count.count)).collect();
+    assert_eq!(tags, vec![("cheap", 5), ("easy", 3), ("quick", 3)]);
+    assert_eq!(facets[1].counts[0].count, 0);
+    assert!(facets[2].counts.is_empty());
+    assert_eq!(facets[3].counts[0].name, "Vegan");
+}
@@ -0,0 +1,80 @@
+use crate::models::recipe::{Ingredient, IngredientUnit};
+use crate::models::shopping_list::{Aisle, ShoppingListItem};
+use crate::services::shopping::{aggregate, aisle_of};
+
+fn ingredient(name: &str, amount: f64, unit: IngredientUnit) -> Ingredient {
+    Ingredient {
+        name: name.to_string(),
+        amount,
+        unit,
+        name_translations: Default::default(),
+    }
+}
+
+fn find<'a>(items: &'a [ShoppingListItem], name: &str) -> &'a ShoppingListItem {
+    items.iter().find(|item| item.name == name).unwrap()
+}
+
+#[test]
+fn sorts_ingredients_into_aisles() {
+    assert_eq!(aisle_of("red onions"), Aisle::produce);
+    assert_eq!(aisle_of("Garlic cloves"), Aisle::produce);
+    assert_eq!(aisle_of("unsalted butter"), Aisle::dairy);
+    assert_eq!(aisle_of("coconut milk"), Aisle::pantry);
+    assert_eq!(aisle_of("smoked paprika"), Aisle::spices);
+    assert_eq!(aisle_of("chicken thighs"), Aisle::meat);
+    assert_eq!(aisle_of("dragon fruit"), Aisle::other);
+}
+
+#[test]
+fn adds_up_ingredients_across_recipes() {
+    use IngredientUnit::*;
+    let items = aggregate(&[
+        ("a".to_string(), ingredient("Flour", 200.0, g)),
+        ("a".to_string(), ingredient("Eggs", 2.0, piece)),
+        ("b".to_string(), ingredient("flour", 0.3, kg)),
+        ("b".to_string(), ingredient("egg", 1.0, piece)),
+        ("b".to_string(), ingredient("milk", 1.0, cup)),
+        ("c".to_string(), ingredient("milk", 1.0, cup)),
+        ("c".to_string(), ingredient("garlic", 2.0, clove)),
+    ]);
+    assert_eq!(items.len(), 4);
+
+    let flour = find(&items, "Flour");
+    assert_eq!((flour.amount, flour.unit), (500.0, g));
+    assert_eq!(flour.recipe_ids, vec!["a", "b"]);
+    assert_eq!(flour.aisle, Aisle::baking);
+
+    let eggs = find(&items, "Eggs");
+    assert_eq!((eggs.amount, eggs.unit), (3.0, piece));
+
+    let milk = find(&items, "milk");
+    assert_eq!((milk.amount, milk.unit), (2.0, cup));
+    assert!(!milk.checked);
+}
+
+#[test]
+fn folds_volumes_into_weights() {
+    use IngredientUnit::*;
+    let items = aggregate(&[
+        ("a".to_string(), ingredient("butter", 100.0, g)),
+        ("b".to_string(), ingredient("butter", 2.0, tbsp)),
+        ("b".to_string(), ingredient("dragon fruit", 1.0, cup)),
+        ("c".to_string(), ingredient("dragon fruit", 50.0, g)),
+    ]);
+    assert_eq!(items.len(), 3);
+    let butter = find(&items, "butter");
+    assert_eq!((butter.amount, butter.unit), (125.0, g));
+    assert_eq!(butter.recipe_ids, vec!["a", "b"]);
+}
+
+#[test]
+fn rounds_only_the_totals() {
+    use IngredientUnit::*;
+    let items = aggregate(&[
+        ("a".to_string(), ingredient("sugar", 102.4, g)),
+        ("b".to_string(), ingredient("sugar", 102.4, g)),
+    ]);
+    // Rounding each to 100 g first would give 200 g.
+    assert_eq!(find(&items, "sugar").amount, 205.0);
+}
@@ -0,0 +1,72 @@
+use crate::models::recipe::{Ingredient, IngredientUnit, Recipe};
+use crate::services::similarity::{self, SimilarityIndex};
+
+use super::fixtures::recipe;
+
+fn with(id: &str, ingredients: &[&str], term_ids: &[&str], minutes: i32, calories: i32) -> Recipe {
+    let mut recipe = recipe(id, 1, calories);
+    recipe.ingredients = ingredients
+        .iter()
+        .map(|name| Ingredient {
+            name: name.to_string(),
+            amount: 1.0,
+            unit: IngredientUnit::piece,
+            name_translations: Default::default(),
+        })
+        .collect();
+    recipe.term_ids = term_ids.iter().map(|id| id.to_string()).collect();
+    recipe.preparation_time_in_minutes = minutes;
+    recipe
+}
+
+fn recipes() -> Vec<Recipe> {
+    vec![
+        with("carbonara", &["spaghetti", "eggs", "pancetta", "parmesan", "salt"], &["italian", "pasta"], 25, 700),
+        with("amatriciana", &["spaghetti", "pancetta", "tomatoes", "pecorino", "salt"], &["italian", "pasta"], 30, 650),
+        with("cacio e pepe", &["spaghetti", "pecorino", "black pepper", "salt"], &["italian", "pasta"], 15, 600),
+        with("omelette", &["egg", "butter", "salt"], &["breakfast"], 10, 300),
+        with("pancakes", &["flour", "egg", "milk", "butter", "salt"], &["breakfast"], 20, 450),
+        with("salad", &["lettuce", "cucumber", "salt"], &[], 5, 150),
+    ]
+}
+
+#[test]
+fn ranks_recipes_by_similarity() {
+    let index = SimilarityIndex::build(&recipes());
+    let similar = index.similar("carbonara", 5);
+    let ids: Vec<&str> = similar.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids[0], "amatriciana");
+    assert_eq!(ids[1], "cacio e pepe");
+    assert_eq!(ids[4], "salad");
+    assert!(similar.iter().all(|(_, score)| (0.0..=1.0).contains(score)));
+    assert!(similar.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+    let ids: Vec<String> = index.similar("omelette", 1).into_iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec!["pancakes"]);
+    assert_eq!(index.similar("carbonara", 2).len(), 2);
+    assert!(index.similar("missing", 5).is_empty());
+}
+
+#[test]
+fn weights_rare_ingredients_higher() {
+    let index = SimilarityIndex::build(&[
+        with("a", &["saffron", "salt"], &[], 30, 500),
+        with("b", &["saffron", "pepper"], &[], 30, 500),
+        with("c", &["salt", "pepper"], &[], 30, 500),
+        with("d", &["salt", "rice"], &[], 30, 500),
+    ]);
+    assert_eq!(index.similar("a", 1)[0].0, "b");
+}
+
+#[test]
+fn drops_stale_indexes() {
+    let generation = similarity::generation();
+    similarity::invalidate();
+    similarity::store(SimilarityIndex::build(&recipes()), generation);
+    assert!(similarity::cached().is_none());
+
+    similarity::store(SimilarityIndex::build(&recipes()), similarity::generation());
+    assert!(similarity::cached().is_some_and(|index| index.contains("salad")));
+    similarity::invalidate();
+    assert!(similarity::cached().is_none());
+}
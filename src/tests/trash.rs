@@ -0,0 +1,62 @@
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, DateTime};
+
+use crate::models::trash::{TrashKind, TRASH_KINDS};
+use crate::services::trash::{purge_before, to_item};
+
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+#[test]
+fn kinds_are_named_like_their_paths() {
+    assert_eq!("shopping-list".parse::<TrashKind>(), Ok(TrashKind::shopping_list));
+    assert_eq!(TrashKind::meal_plan.to_string(), "meal-plan");
+    assert_eq!(serde_json::to_string(&TrashKind::meal_plan).unwrap(), "\"meal-plan\"");
+    assert!("customer".parse::<TrashKind>().is_err());
+    for kind in TRASH_KINDS {
+        assert_eq!(kind.to_string().parse::<TrashKind>(), Ok(kind));
+    }
+}
+
+#[test]
+fn lists_trashed_documents_with_a_label() {
+    let id = ObjectId::new();
+    let deleted_at = DateTime::from_millis(1_666_000_000_000);
+    let recipe = doc! { "_id": id, "name": "Tomato soup", "deleted_at": deleted_at };
+    let item = to_item(TrashKind::recipe, &recipe, 30).unwrap();
+    assert_eq!(item._id, id.to_string());
+    assert_eq!(item.label, "Tomato soup");
+    assert_eq!(item.user_id, None);
+    assert_eq!(item.deleted_at, deleted_at.to_string());
+    assert_eq!(
+        item.purge_at,
+        DateTime::from_millis(1_666_000_000_000 + 30 * DAY_MILLIS).to_string()
+    );
+
+    let entry = doc! {
+        "_id": id, "user_id": "u1", "date": "2022-10-20", "slot": "dinner", "deleted_at": deleted_at
+    };
+    let item = to_item(TrashKind::meal_plan, &entry, 30).unwrap();
+    assert_eq!(item.label, "2022-10-20 dinner");
+    assert_eq!(item.user_id.as_deref(), Some("u1"));
+
+    let pick = doc! { "_id": id, "date": "2022-10-20", "recipe_id": ObjectId::new(), "deleted_at": deleted_at };
+    assert_eq!(to_item(TrashKind::daily, &pick, 30).unwrap().label, "2022-10-20");
+
+    let comment = doc! { "_id": id, "user_id": "u2", "text": "Too salty", "deleted": false, "deleted_at": deleted_at };
+    let item = to_item(TrashKind::comment, &comment, 30).unwrap();
+    assert_eq!((item.label.as_str(), item.user_id.as_deref()), ("Too salty", Some("u2")));
+
+    let step = doc! {
+        "_id": id, "description": " ", "gif": { "path": "/tmp/stir.gif" }, "deleted_at": deleted_at
+    };
+    assert_eq!(to_item(TrashKind::gif, &step, 30).unwrap().label, "/tmp/stir.gif");
+
+    assert!(to_item(TrashKind::recipe, &doc! { "_id": id, "name": "Live" }, 30).is_none());
+}
+
+#[test]
+fn purges_after_the_retention_period() {
+    let now = DateTime::from_millis(100 * DAY_MILLIS);
+    assert_eq!(purge_before(now, 30), DateTime::from_millis(70 * DAY_MILLIS));
+    assert_eq!(purge_before(now, 0), now);
+}
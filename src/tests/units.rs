@@ -0,0 +1,57 @@
+use crate::models::recipe::{IngredientUnit, UnitSystem};
+use crate::services::units::{convert, normalize, round_amount, to_grams};
+
+#[test]
+fn rounds_sensibly() {
+    assert_eq!(round_amount(0.2, IngredientUnit::g), 0.5);
+    assert_eq!(round_amount(7.4, IngredientUnit::g), 7.5);
+    assert_eq!(round_amount(123.0, IngredientUnit::g), 125.0);
+    assert_eq!(round_amount(1234.0, IngredientUnit::ml), 1230.0);
+    assert_eq!(round_amount(1.57, IngredientUnit::kg), 1.55);
+    assert_eq!(round_amount(0.3, IngredientUnit::cup), 0.25);
+    assert_eq!(round_amount(0.1, IngredientUnit::tsp), 0.25);
+}
+
+#[test]
+fn promotes_within_the_metric_system() {
+    use IngredientUnit::*;
+    let system = UnitSystem::metric;
+    assert_eq!(normalize(1000.0, g, system), (1.0, kg));
+    assert_eq!(normalize(1500.0, g, system), (1.5, kg));
+    assert_eq!(normalize(0.5, kg, system), (500.0, g));
+    assert_eq!(normalize(0.3, g, system), (300.0, mg));
+    assert_eq!(normalize(6.0, tsp, system), (2.0, tbsp));
+    assert_eq!(normalize(8.0, tbsp, system), (120.0, ml));
+    assert_eq!(normalize(200.0, ml, system), (200.0, ml));
+    assert_eq!(normalize(4.0, dcl, system), (4.0, dcl));
+    assert_eq!(normalize(2000.0, ml, system), (2.0, l));
+}
+
+#[test]
+fn converts_between_systems() {
+    use IngredientUnit::*;
+    assert_eq!(normalize(1.0, cup, UnitSystem::metric), (240.0, ml));
+    assert_eq!(normalize(1.0, lb, UnitSystem::metric), (455.0, g));
+    assert_eq!(normalize(500.0, g, UnitSystem::imperial), (1.0, lb));
+    assert_eq!(normalize(100.0, g, UnitSystem::imperial), (3.5, oz));
+    assert_eq!(normalize(120.0, ml, UnitSystem::imperial), (0.5, cup));
+    assert_eq!(normalize(30.0, ml, UnitSystem::imperial), (2.0, tbsp));
+    assert_eq!(normalize(2.0, fl_oz, UnitSystem::imperial), (2.0, fl_oz));
+    assert_eq!(normalize(2.0, clove, UnitSystem::imperial), (2.0, clove));
+}
+
+#[test]
+fn converts_with_densities_and_piece_weights() {
+    use IngredientUnit::*;
+    assert_eq!(to_grams(2.0, kg, "anything", None), Some(2000.0));
+    assert_eq!(to_grams(2.0, dcl, "water", None), Some(200.0));
+    assert_eq!(to_grams(1.0, cup, "plain flour", None).map(f64::round), Some(127.0));
+    assert_eq!(to_grams(2.0, piece, "eggs", None), Some(100.0));
+    assert_eq!(to_grams(3.0, clove, "garlic", None), Some(15.0));
+    assert_eq!(to_grams(1.0, tbsp, "dragon fruit", None), None);
+    assert_eq!(to_grams(1.0, tbsp, "dragon fruit", Some(1.0)), Some(15.0));
+    assert_eq!(to_grams(1.0, piece, "dragon fruit", Some(1.0)), None);
+    assert_eq!(convert(2.0, cup, ml, "milk"), Some(480.0));
+    assert_eq!(convert(100.0, g, piece, "eggs"), Some(2.0));
+    assert_eq!(convert(1.0, piece, g, "dragon fruit"), None);
+}
@@ -0,0 +1,52 @@
+use crate::models::image::Image;
+use crate::services::uploads::{keep_stored_media, resolve, upload_path};
+
+use super::fixtures::recipe;
+
+fn image(id: &str, path: &str) -> Image {
+    Image {
+        _id: id.to_string(),
+        path: path.to_string(),
+        width: 10,
+        height: 10,
+        title: "photo".to_string(),
+        created_at: String::new(),
+    }
+}
+
+#[test]
+fn only_resolves_uploaded_files() {
+    let path = upload_path("../../soup photo.jpg").unwrap();
+    assert!(!path.file_name().unwrap().to_string_lossy().contains('/'));
+    std::fs::write(&path, b"jpeg").unwrap();
+
+    assert!(resolve(path.to_str().unwrap()).is_some());
+    let escaped = format!("{}/../../../etc/passwd", path.parent().unwrap().display());
+    assert_eq!(resolve(&escaped), None);
+    assert_eq!(resolve("/etc/passwd"), None);
+    assert_eq!(resolve(""), None);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn ignores_media_paths_sent_by_clients() {
+    let mut stored = recipe("soup", 2, 400);
+    stored.images = vec![image("img1", "/uploads/soup.jpg")];
+
+    let mut sent = stored.clone();
+    sent.images = vec![
+        image("img1", "/etc/passwd"),
+        image("forged", "/etc/shadow"),
+        image("", "https://example.com/soup.jpg"),
+    ];
+    let kept = keep_stored_media(sent.clone(), Some(&stored));
+    let images: Vec<(&str, &str)> = kept.images.iter().map(|image| (image._id.as_str(), image.path.as_str())).collect();
+    assert_eq!(
+        images,
+        vec![("img1", "/uploads/soup.jpg"), ("", ""), ("", "https://example.com/soup.jpg")]
+    );
+
+    let new = keep_stored_media(sent, None);
+    assert_eq!(new.images[0].path, "");
+    assert_eq!(new.images[0]._id, "");
+}